@@ -1,18 +1,41 @@
-use std::sync::Arc;
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
 use crate::{
-    fetch::{fetch_vertex, fetch_vertices},
-    vertex::{Vertex, VertexKind},
+    backend::{Backend, HttpBackend, ListQuery},
+    derived::{bibliographic_coupling, citation, co_citation, collaborations},
+    diagnostics::Diagnostic,
+    explain::{explain_query, QueryPlan},
+    fetch::{FetchError, OpenAlexClient, PageBudget, DEFAULT_MAX_PAGES},
+    filter::{filter_item, tag_filter, validate_filter, FilterError, AWARD_ID_KEY},
+    json_path::JsonPath,
+    mirror::{MirrorBackend, SyncReport},
+    vertex::{DehydratedTopic, FilteredCount, Vertex, VertexKind, YearCount},
 };
 
+use serde_json::Value;
 use trustfall_core::{
     interpreter::{
         Adapter, ContextIterator, ContextOutcomeIterator, DataContext, ResolveEdgeInfo,
         ResolveInfo, VertexIterator,
     },
-    ir::{EdgeParameters, FieldValue},
+    ir::{EdgeParameters, FieldValue, IRQuery},
 };
 
+/// Puts an abstract back together from OpenAlex's inverted index, which maps each word
+/// to the positions it appears at.
+fn abstract_text(inverted_index: &HashMap<String, Vec<u32>>) -> String {
+    let mut words = inverted_index
+        .iter()
+        .flat_map(|(word, positions)| positions.iter().map(move |position| (*position, word)))
+        .collect::<Vec<_>>();
+    words.sort();
+    words
+        .into_iter()
+        .map(|(_, word)| word.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 fn get_work_property(vertex: &Vertex, field_name: &str) -> FieldValue {
     let work = vertex.as_work().expect("Vertex was not a work");
     match field_name {
@@ -26,8 +49,8 @@ fn get_work_property(vertex: &Vertex, field_name: &str) -> FieldValue {
         "object_ids_pmid" => work.object.clone().ids.pmid.into(),
         "object_ids_pmcid" => work.object.clone().ids.pmcid.into(),
         "object_updated_date" => work.object.clone().updated_date.into(),
-        "abstract_text" => match work.abstract_inverted_index.clone() {
-            Some(hashmap) => hashmap.keys().last().into(),
+        "abstract_text" => match &work.abstract_inverted_index {
+            Some(inverted_index) => abstract_text(inverted_index).into(),
             _ => FieldValue::Null,
         },
         "apc_list_value" => work
@@ -133,8 +156,9 @@ fn get_work_property(vertex: &Vertex, field_name: &str) -> FieldValue {
             .into(),
         "publication_date" => work.publication_date.clone().into(),
         "publication_year" => work.publication_year.into(),
-        "referenced_works" => work.referenced_works.clone().into(),
-        "related_works" => work.related_works.clone().into(),
+        "ngrams_url" => work.ngrams_url.clone().into(),
+        "referenced_work_ids" => work.referenced_works.clone().into(),
+        "related_work_ids" => work.related_works.clone().into(),
         "title" => work.title.clone().into(),
         "ttype" => work.ttype.clone().into(),
         "is_oa" => work.is_oa.into(),
@@ -196,10 +220,17 @@ fn get_source_property(vertex: &Vertex, field_name: &str) -> FieldValue {
         "object_updated_date" => source.object.clone().updated_date.into(),
         "abreviated_title" => source.abreviated_title.clone().into(),
         "alternative_titles" => source.alternative_titles.clone().into(),
-        "apc_prices" => match (source.apc_prices.clone()) {
+        "apc_prices" => match source.apc_prices.clone() {
             Some(price_vec) => price_vec
                 .into_iter()
-                .map(|price_obj| price_obj.price.to_string() + " - " + &price_obj.currency)
+                // A price the API left out can't be shown.
+                .filter_map(|price_obj| {
+                    let price = price_obj.price?.to_string();
+                    Some(match price_obj.currency {
+                        Some(currency) => price + " - " + &currency,
+                        None => price,
+                    })
+                })
                 .collect::<Vec<String>>()
                 .into(),
             _ => FieldValue::Null,
@@ -216,7 +247,13 @@ fn get_source_property(vertex: &Vertex, field_name: &str) -> FieldValue {
             .societies
             .clone()
             .into_iter()
-            .map(|society| society.url + " - " + &society.organization)
+            .map(|society| {
+                [society.url, society.organization]
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<_>>()
+                    .join(" - ")
+            })
             .collect::<Vec<String>>()
             .into(),
         "summary_stats_mean_citeness" => match source
@@ -238,7 +275,6 @@ fn get_source_property(vertex: &Vertex, field_name: &str) -> FieldValue {
             .and_then(|stats| stats.i10_index)
             .into(),
         "ttype" => source.ttype.clone().into(),
-        "works_api_url" => source.works_api_url.clone().into(),
         "works_count" => source.works_count.into(),
         _ => unreachable!("Source property {field_name}"),
     }
@@ -300,7 +336,7 @@ fn get_institution_property(vertex: &Vertex, field_name: &str) -> FieldValue {
         "object_ids_pmid" => institution.object.clone().ids.pmid.into(),
         "object_ids_pmcid" => institution.object.clone().ids.pmcid.into(),
         "object_updated_date" => institution.object.clone().updated_date.into(),
-        "country_codes" => institution.country_code.clone().into(),
+        "country_code" => institution.country_code.clone().into(),
         "display_name_alternatives" => institution.display_name_alternatives.clone().into(),
         "geo_city" => institution.geo.clone().and_then(|geo| geo.city).into(),
         "geo_geonames_city_id" => institution
@@ -433,6 +469,275 @@ fn get_funder_property(vertex: &Vertex, field_name: &str) -> FieldValue {
     }
 }
 
+fn get_topic_property(vertex: &Vertex, field_name: &str) -> FieldValue {
+    let topic = vertex.as_topic().expect("Vertex was not a topic");
+    match field_name {
+        "object_cited_by_count" => topic.object.cited_by_count.into(),
+        "object_created_date" => topic.object.created_date.clone().into(),
+        "object_display_name" => topic.object.display_name.clone().into(),
+        "object_id" => topic.object.id.clone().into(),
+        "object_ids_openalex" => topic.object.ids.openalex.clone().into(),
+        "object_ids_wikipedia" => topic.object.ids.wikipedia.clone().into(),
+        "object_updated_date" => topic.object.updated_date.clone().into(),
+        "count" => topic.count.into(),
+        "description" => topic.description.clone().into(),
+        "domain_display_name" => topic
+            .domain
+            .clone()
+            .and_then(|domain| domain.display_name)
+            .into(),
+        "field_display_name" => topic
+            .field
+            .clone()
+            .and_then(|field| field.display_name)
+            .into(),
+        "keywords" => topic.keywords.clone().into(),
+        "score" => match topic.score {
+            Some(num) => FieldValue::Float64(num),
+            _ => FieldValue::Null,
+        },
+        "subfield_display_name" => topic
+            .subfield
+            .clone()
+            .and_then(|subfield| subfield.display_name)
+            .into(),
+        "works_count" => topic.works_count.into(),
+        _ => unreachable!("Topic property {field_name}"),
+    }
+}
+
+fn get_subfield_property(vertex: &Vertex, field_name: &str) -> FieldValue {
+    let subfield = vertex.as_subfield().expect("Vertex was not a subfield");
+    match field_name {
+        "object_cited_by_count" => subfield.object.cited_by_count.into(),
+        "object_created_date" => subfield.object.created_date.clone().into(),
+        "object_display_name" => subfield.object.display_name.clone().into(),
+        "object_id" => subfield.object.id.clone().into(),
+        "object_ids_openalex" => subfield.object.ids.openalex.clone().into(),
+        "object_ids_wikidata" => subfield.object.ids.wikidata.clone().into(),
+        "object_ids_wikipedia" => subfield.object.ids.wikipedia.clone().into(),
+        "object_updated_date" => subfield.object.updated_date.clone().into(),
+        "description" => subfield.description.clone().into(),
+        "display_name_alternatives" => subfield.display_name_alternatives.clone().into(),
+        "works_count" => subfield.works_count.into(),
+        _ => unreachable!("Subfield property {field_name}"),
+    }
+}
+
+fn get_field_property(vertex: &Vertex, field_name: &str) -> FieldValue {
+    let field = vertex.as_field().expect("Vertex was not a field");
+    match field_name {
+        "object_cited_by_count" => field.object.cited_by_count.into(),
+        "object_created_date" => field.object.created_date.clone().into(),
+        "object_display_name" => field.object.display_name.clone().into(),
+        "object_id" => field.object.id.clone().into(),
+        "object_ids_openalex" => field.object.ids.openalex.clone().into(),
+        "object_ids_wikidata" => field.object.ids.wikidata.clone().into(),
+        "object_ids_wikipedia" => field.object.ids.wikipedia.clone().into(),
+        "object_updated_date" => field.object.updated_date.clone().into(),
+        "description" => field.description.clone().into(),
+        "display_name_alternatives" => field.display_name_alternatives.clone().into(),
+        "works_count" => field.works_count.into(),
+        _ => unreachable!("Field property {field_name}"),
+    }
+}
+
+fn get_domain_property(vertex: &Vertex, field_name: &str) -> FieldValue {
+    let domain = vertex.as_domain().expect("Vertex was not a domain");
+    match field_name {
+        "object_cited_by_count" => domain.object.cited_by_count.into(),
+        "object_created_date" => domain.object.created_date.clone().into(),
+        "object_display_name" => domain.object.display_name.clone().into(),
+        "object_id" => domain.object.id.clone().into(),
+        "object_ids_openalex" => domain.object.ids.openalex.clone().into(),
+        "object_ids_wikidata" => domain.object.ids.wikidata.clone().into(),
+        "object_ids_wikipedia" => domain.object.ids.wikipedia.clone().into(),
+        "object_updated_date" => domain.object.updated_date.clone().into(),
+        "description" => domain.description.clone().into(),
+        "display_name_alternatives" => domain.display_name_alternatives.clone().into(),
+        "works_count" => domain.works_count.into(),
+        _ => unreachable!("Domain property {field_name}"),
+    }
+}
+
+fn get_sdg_property(vertex: &Vertex, field_name: &str) -> FieldValue {
+    let sdg = vertex.as_sdg().expect("Vertex was not an SDG");
+    match field_name {
+        "id" => sdg.id.clone().into(),
+        "display_name" => sdg.display_name.clone().into(),
+        "score" => match sdg.score {
+            Some(num) => FieldValue::Float64(num),
+            _ => FieldValue::Null,
+        },
+        _ => unreachable!("SDG property {field_name}"),
+    }
+}
+
+fn get_keyword_property(vertex: &Vertex, field_name: &str) -> FieldValue {
+    let keyword = vertex.as_keyword().expect("Vertex was not a keyword");
+    match field_name {
+        "id" => keyword.id.clone().into(),
+        "display_name" => keyword
+            .display_name
+            .clone()
+            .or_else(|| keyword.keyword.clone())
+            .into(),
+        "score" => match keyword.score {
+            Some(num) => FieldValue::Float64(num),
+            _ => FieldValue::Null,
+        },
+        _ => unreachable!("Keyword property {field_name}"),
+    }
+}
+
+fn get_year_count_property(vertex: &Vertex, field_name: &str) -> FieldValue {
+    let year_count = vertex.as_year_count().expect("Vertex was not a year count");
+    match field_name {
+        "year" => year_count.year.into(),
+        "works_count" => year_count.works_count.into(),
+        "cited_by_count" => year_count.cited_by_count.into(),
+        _ => unreachable!("YearCount property {field_name}"),
+    }
+}
+
+fn get_mesh_property(vertex: &Vertex, field_name: &str) -> FieldValue {
+    let mesh = vertex.as_mesh().expect("Vertex was not a MeSH heading");
+    match field_name {
+        "descriptor_ui" => mesh.descriptor_ui.clone().into(),
+        "descriptor_name" => mesh.descriptor_name.clone().into(),
+        "qualifier_ui" => mesh.qualifier_ui.clone().into(),
+        "qualifier_name" => mesh.qualifier_name.clone().into(),
+        "is_major_topic" => mesh.is_major_topic.into(),
+        _ => unreachable!("Mesh property {field_name}"),
+    }
+}
+
+fn get_role_property(vertex: &Vertex, field_name: &str) -> FieldValue {
+    let role = vertex.as_role().expect("Vertex was not a role");
+    match field_name {
+        "role" => role.role.clone().into(),
+        "id" => role.id.clone().into(),
+        "works_count" => role.works_count.into(),
+        _ => unreachable!("Role property {field_name}"),
+    }
+}
+
+fn get_affiliation_property(vertex: &Vertex, field_name: &str) -> FieldValue {
+    let affiliation = vertex
+        .as_affiliation()
+        .expect("Vertex was not an affiliation");
+    match field_name {
+        "institution_id" => affiliation.institution.id.clone().into(),
+        "institution_display_name" => affiliation.institution.display_name.clone().into(),
+        "institution_country_code" => affiliation.institution.country_code.clone().into(),
+        "years" => affiliation.years.clone().into(),
+        "first_year" => affiliation.years.iter().min().copied().into(),
+        "last_year" => affiliation.years.iter().max().copied().into(),
+        _ => unreachable!("Affiliation property {field_name}"),
+    }
+}
+
+fn get_collaboration_property(vertex: &Vertex, field_name: &str) -> FieldValue {
+    let collaboration = vertex
+        .as_collaboration()
+        .expect("Vertex was not a collaboration");
+    match field_name {
+        "coauthor_id" => collaboration.coauthor.id.clone().into(),
+        "coauthor_display_name" => collaboration.coauthor.display_name.clone().into(),
+        "coauthor_orcid" => collaboration.coauthor.orcid.clone().into(),
+        "shared_works_count" => collaboration.shared_works_count.into(),
+        "first_year" => collaboration.first_year.into(),
+        "last_year" => collaboration.last_year.into(),
+        _ => unreachable!("Collaboration property {field_name}"),
+    }
+}
+
+fn get_work_overlap_property(vertex: &Vertex, field_name: &str) -> FieldValue {
+    let overlap = vertex
+        .as_work_overlap()
+        .expect("Vertex was not a work overlap");
+    match field_name {
+        "work_id" => overlap.work_id.clone().into(),
+        "strength" => overlap.strength.into(),
+        _ => unreachable!("WorkOverlap property {field_name}"),
+    }
+}
+
+fn get_citation_property(vertex: &Vertex, field_name: &str) -> FieldValue {
+    let citation = vertex.as_citation().expect("Vertex was not a citation");
+    match field_name {
+        "cited_work_id" => citation.cited.object.id.clone().into(),
+        "citing_work_id" => citation.citing.object.id.clone().into(),
+        "citing_publication_year" => citation.citing.publication_year.into(),
+        "is_author_self_citation" => citation.is_author_self_citation.into(),
+        "is_institution_self_citation" => citation.is_institution_self_citation.into(),
+        "is_source_self_citation" => citation.is_source_self_citation.into(),
+        _ => unreachable!("Citation property {field_name}"),
+    }
+}
+
+fn get_filtered_count_property(vertex: &Vertex, field_name: &str) -> FieldValue {
+    let filtered_count = vertex
+        .as_filtered_count()
+        .expect("Vertex was not a filtered count");
+    match field_name {
+        "filter" => filtered_count.filter.clone().into(),
+        "count" => filtered_count.count.into(),
+        _ => unreachable!("FilteredCount property {field_name}"),
+    }
+}
+
+fn get_group_bucket_property(vertex: &Vertex, field_name: &str) -> FieldValue {
+    let bucket = vertex
+        .as_group_bucket()
+        .expect("Vertex was not a group bucket");
+    match field_name {
+        "key" => bucket.key.clone().into(),
+        "key_display_name" => bucket.key_display_name.clone().into(),
+        "count" => bucket.count.into(),
+        _ => unreachable!("GroupBucket property {field_name}"),
+    }
+}
+
+fn get_autocomplete_result_property(vertex: &Vertex, field_name: &str) -> FieldValue {
+    let result = vertex
+        .as_autocomplete_result()
+        .expect("Vertex was not an autocomplete result");
+    match field_name {
+        "id" => result.id.clone().into(),
+        "display_name" => result.display_name.clone().into(),
+        "cited_by_count" => result.cited_by_count.into(),
+        "entity_type" => result.entity_type.clone().into(),
+        "external_id" => result.external_id.clone().into(),
+        "hint" => result.hint.clone().into(),
+        "works_count" => result.works_count.into(),
+        _ => unreachable!("AutocompleteResult property {field_name}"),
+    }
+}
+
+fn get_json_value_property(vertex: &Vertex, field_name: &str) -> FieldValue {
+    let value = vertex.as_json_value().expect("Vertex was not a JSON value");
+    match field_name {
+        "json_type" => match value {
+            Value::Null => "null",
+            Value::Bool(..) => "boolean",
+            Value::Number(..) => "number",
+            Value::String(..) => "string",
+            Value::Array(..) => "array",
+            Value::Object(..) => "object",
+        }
+        .into(),
+        "string" => value.as_str().map(|s| s.to_string()).into(),
+        "number" => match value.as_f64() {
+            Some(num) => FieldValue::Float64(num),
+            _ => FieldValue::Null,
+        },
+        "integer" => value.as_i64().into(),
+        "boolean" => value.as_bool().into(),
+        _ => unreachable!("JsonValue property {field_name}"),
+    }
+}
+
 fn property_mapper(
     ctx: DataContext<Vertex>,
     field_name: &str,
@@ -445,15 +750,656 @@ fn property_mapper(
     (ctx, value)
 }
 
-pub struct OpenAlexAdapter;
+// OpenAlex caps the number of OR-ed values in a single filter.
+const CITES_BATCH_SIZE: usize = 50;
+
+// The largest `sample=` OpenAlex draws.
+const MAX_SAMPLE_SIZE: i64 = 10_000;
+
+fn fetch_neighbors_by_id<B: Backend + 'static>(
+    client: &OpenAlexClient<B>,
+    ids: Vec<String>,
+    kind: VertexKind,
+) -> VertexIterator<'static, Vertex> {
+    let client = client.clone();
+    Box::new(
+        ids.into_iter()
+            .filter_map(move |id| client.fetch_vertex(id.clone(), kind).ok()),
+    )
+}
+
+fn fetch_all_vertices<B: Backend + 'static>(
+    client: &OpenAlexClient<B>,
+    kind: VertexKind,
+    query: ListQuery,
+    budget: PageBudget,
+) -> VertexIterator<'static, Vertex> {
+    // The client records the error that ends the list as a diagnostic.
+    Box::new(
+        client
+            .fetch_paginated(kind, query, budget)
+            .map_while(Result::ok),
+    )
+}
+
+/// The `filter` edge parameter, if present and non-empty. A filter naming a key the entity's
+/// endpoint doesn't support is an error, rather than a request that quietly matches nothing.
+fn filter_parameter(
+    parameters: &EdgeParameters,
+    kind: VertexKind,
+) -> Result<Option<String>, FilterError> {
+    let Some(filter) = parameters
+        .get("filter")
+        .and_then(|v| v.as_str())
+        .filter(|filter| !filter.is_empty())
+    else {
+        return Ok(None);
+    };
+    validate_filter(kind, filter)?;
+    Ok(Some(filter.to_string()))
+}
+
+/// The `sort` edge parameter, e.g. `cited_by_count:desc` or `publication_date`.
+fn sort_parameter(parameters: &EdgeParameters) -> Option<String> {
+    parameters
+        .get("sort")
+        .and_then(|v| v.as_str())
+        .filter(|sort| !sort.is_empty())
+        .map(|sort| sort.to_string())
+}
+
+/// The `limit` and `max_pages` edge parameters, falling back to `DEFAULT_MAX_PAGES`
+/// when neither is given.
+pub(crate) fn page_limits(parameters: &EdgeParameters) -> (Option<usize>, Option<u32>) {
+    match explicit_page_limits(parameters) {
+        (None, None) => (None, Some(DEFAULT_MAX_PAGES)),
+        limits => limits,
+    }
+}
+
+/// The `limit` and `max_pages` edge parameters as given, without the default page cap, e.g.
+/// for a sample, which its `size` already bounds.
+pub(crate) fn explicit_page_limits(parameters: &EdgeParameters) -> (Option<usize>, Option<u32>) {
+    let limit = parameters
+        .get("limit")
+        .and_then(|v| v.as_u64())
+        .map(|limit| limit as usize);
+    let max_pages = parameters
+        .get("max_pages")
+        .and_then(|v| v.as_u64())
+        .map(|max_pages| max_pages as u32);
+    (limit, max_pages)
+}
+
+/// How an edge finds its neighbors. `resolve_neighbors` resolves the filtered lists and
+/// counts straight from this, and `explain` describes every edge with it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum EdgeRequest {
+    /// Read from the parent vertex, without a request.
+    Nested,
+    /// At most one request, for the entity the parent vertex names.
+    SingleId,
+    /// One request per entity ID the parent vertex lists.
+    PerId,
+    /// The `kind` entities whose `filter_key` is the parent vertex's ID, narrowed further by
+    /// `extra_filter`, paged within the edge's `limit` and `max_pages`.
+    List {
+        kind: VertexKind,
+        filter_key: &'static str,
+        extra_filter: Option<String>,
+    },
+    /// The number of works whose `filter_key` is the parent vertex's ID.
+    Count { filter_key: &'static str },
+    /// Works whose `filter_key` is the parent vertex's ID, paged like `List` and then
+    /// combined into derived vertices such as citations, any number per work.
+    Derived { filter_key: &'static str },
+    /// Works citing any of the parent's referenced works, one list per batch of references.
+    CitingReferences,
+    /// No request: the edge's parameters can't be sent, so it has no neighbors.
+    Invalid(FilterError),
+}
+
+pub(crate) fn edge_request(
+    type_name: &str,
+    edge_name: &str,
+    parameters: &EdgeParameters,
+) -> Option<EdgeRequest> {
+    let list = |kind, filter_key| EdgeRequest::List {
+        kind,
+        filter_key,
+        extra_filter: None,
+    };
+
+    Some(match (type_name, edge_name) {
+        ("Work", "cited_by_count_filtered") => EdgeRequest::Count {
+            filter_key: "cites",
+        },
+        ("Author", "works_count_filtered") => EdgeRequest::Count {
+            filter_key: "author.id",
+        },
+        ("Source", "works_count_filtered") => EdgeRequest::Count {
+            filter_key: "primary_location.source.id",
+        },
+        ("Institution", "works_count_filtered") => EdgeRequest::Count {
+            filter_key: "institutions.id",
+        },
+        ("Work", "cited_by_api_url") => list(VertexKind::Work, "cites"),
+        ("Author", "works_api_url") => list(VertexKind::Work, "author.id"),
+        ("Source", "works_api_url") => list(VertexKind::Work, "primary_location.source.id"),
+        ("Concept", "works_api_url") => list(VertexKind::Work, "concepts.id"),
+        ("Institution", "works_api_url") => list(VertexKind::Work, "institutions.id"),
+        ("Publisher", "sources_api_url") => list(VertexKind::Source, "host_organization.id"),
+        // Current staff by default; past affiliations too when asked for.
+        ("Institution", "authors") => {
+            match parameters.get("include_past").and_then(|v| v.as_bool()) {
+                Some(true) => list(VertexKind::Author, "affiliations.institution.id"),
+                _ => list(VertexKind::Author, "last_known_institutions.id"),
+            }
+        }
+        ("Publisher", "children") => list(VertexKind::Publisher, "parent_publisher"),
+        ("Publisher", "works") => list(
+            VertexKind::Work,
+            "primary_location.source.publisher_lineage",
+        ),
+        ("Funder", "works") => {
+            let award_id = parameters.get("award_id").and_then(|v| v.as_str());
+            match award_id
+                .map(|award_id| filter_item(VertexKind::Work, AWARD_ID_KEY, award_id))
+                .transpose()
+            {
+                Ok(extra_filter) => EdgeRequest::List {
+                    kind: VertexKind::Work,
+                    filter_key: "grants.funder",
+                    extra_filter,
+                },
+                Err(e) => EdgeRequest::Invalid(e),
+            }
+        }
+        ("Work", "citations" | "co_cited_with") => EdgeRequest::Derived {
+            filter_key: "cites",
+        },
+        ("Author", "coauthors") => EdgeRequest::Derived {
+            filter_key: "author.id",
+        },
+        ("Work", "bibliographically_coupled") => EdgeRequest::CitingReferences,
+        (_, "json_path")
+        | ("Work", "sdgs" | "keywords" | "mesh")
+        | (
+            "Work" | "Author" | "Source" | "Concept" | "Institution" | "Publisher" | "Funder",
+            "object_counts_by_year",
+        )
+        | ("Institution" | "Publisher" | "Funder", "roles")
+        | ("Author", "affiliations")
+        | ("Citation", "cited_work" | "citing_work") => EdgeRequest::Nested,
+        ("Work", "primary_topic" | "primary_location" | "best_oa_location")
+        | ("Author", "last_known_institution")
+        | ("Source", "host_organization")
+        | ("Publisher", "parent_publisher")
+        | ("Topic", "subfield" | "field" | "domain")
+        | ("Subfield", "field" | "domain")
+        | ("Field", "domain")
+        | ("Affiliation", "institution")
+        | ("Collaboration", "coauthor")
+        | ("WorkOverlap", "work")
+        | ("GroupBucket" | "AutocompleteResult", _) => EdgeRequest::SingleId,
+        (
+            "Work",
+            "authorships"
+            | "concepts"
+            | "grants"
+            | "referenced_works"
+            | "related_works"
+            | "topics"
+            | "locations"
+            | "corresponding_author_ids"
+            | "corresponding_institution_ids",
+        )
+        | ("Author", "topics")
+        | ("Source", "host_organization_lineage" | "topics")
+        | ("Concept", "ancestors" | "related_concepts")
+        | (
+            "Institution",
+            "parents"
+            | "children"
+            | "related"
+            | "lineage"
+            | "associated_institutions"
+            | "repositories"
+            | "publisher"
+            | "funder",
+        )
+        | ("Publisher", "lineage" | "institution" | "funder")
+        | ("Funder", "institution" | "publisher")
+        | ("Subfield", "topics")
+        | ("Field", "subfields")
+        | ("Domain", "fields") => EdgeRequest::PerId,
+        _ => return None,
+    })
+}
+
+/// The `filter`, `sort` and `search` edge parameters. The sort is pushed down to OpenAlex,
+/// so that paging stops after the first few results of e.g. "most cited first".
+pub(crate) fn list_query(
+    kind: VertexKind,
+    parameters: &EdgeParameters,
+) -> Result<ListQuery, FilterError> {
+    let string_parameter = |name: &str| {
+        parameters
+            .get(name)
+            .and_then(|v| v.as_str())
+            .filter(|value| !value.is_empty())
+            .map(|value| value.to_string())
+    };
+
+    Ok(ListQuery {
+        filter: filter_parameter(parameters, kind)?,
+        search: string_parameter("search"),
+        sort: string_parameter("sort"),
+        ..Default::default()
+    })
+}
+
+/// The works list behind the `OpenAlexSDGWorks` and `OpenAlexKeywordWorks` starting edges,
+/// or `None` for any other edge.
+pub(crate) fn tagged_works_query(
+    edge_name: &str,
+    parameters: &EdgeParameters,
+) -> Option<Result<ListQuery, FilterError>> {
+    let (key, parameter) = tag_filter(edge_name)?;
+    let tag = parameters.get(parameter)?.as_str()?;
+    Some(
+        filter_item(VertexKind::Work, key, tag)
+            .map(|filter| ListQuery::filtered(filter).with_sort(sort_parameter(parameters))),
+    )
+}
+
+/// The `group_by` edge parameter, and the list it groups.
+pub(crate) fn group_query(
+    kind: VertexKind,
+    parameters: &EdgeParameters,
+) -> Result<(ListQuery, String), FilterError> {
+    let group_by = parameters.get("group_by").and_then(|v| v.as_str()).unwrap();
+    let query = ListQuery {
+        filter: filter_parameter(parameters, kind)?,
+        ..Default::default()
+    };
+    Ok((query, group_by.to_string()))
+}
+
+/// The `filter`, `size` and `seed` edge parameters of the `Sample*` edges. A `size` outside
+/// what OpenAlex accepts is clamped, so a negative one samples nothing.
+pub(crate) fn sample_query(
+    kind: VertexKind,
+    parameters: &EdgeParameters,
+) -> Result<ListQuery, FilterError> {
+    let size = parameters
+        .get("size")
+        .and_then(|v| v.as_i64())
+        .unwrap_or(0)
+        .clamp(0, MAX_SAMPLE_SIZE) as u64;
+    let seed = parameters.get("seed").and_then(|v| v.as_u64());
+
+    Ok(ListQuery {
+        filter: filter_parameter(parameters, kind)?,
+        sample: Some(size),
+        seed,
+        ..Default::default()
+    })
+}
+
+/// The `entity` and `q` edge parameters of `Autocomplete`.
+pub(crate) fn autocomplete_parameters(parameters: &EdgeParameters) -> (Option<String>, String) {
+    let q = parameters.get("q").and_then(|v| v.as_str()).unwrap();
+    let entity = parameters
+        .get("entity")
+        .and_then(|v| v.as_str())
+        .map(|entity| entity.to_string());
+    (entity, q.to_string())
+}
+
+/// Works citing any of the given works, batching the IDs into `cites:` OR-filters.
+/// `budget` applies to all the batches together.
+fn fetch_citing_works<B: Backend + 'static>(
+    client: &OpenAlexClient<B>,
+    work_ids: Vec<String>,
+    budget: PageBudget,
+) -> impl Iterator<Item = Vertex> {
+    let queries = work_ids
+        .chunks(CITES_BATCH_SIZE)
+        .map(|batch| {
+            let batch = batch
+                .iter()
+                .map(|work_id| short_id(work_id))
+                .collect::<Vec<_>>()
+                .join("|");
+            ListQuery::filtered(format!("cites:{batch}"))
+        })
+        .collect();
+
+    // The client records the error that ends the list as a diagnostic.
+    client
+        .fetch_paginated_lists(VertexKind::Work, queries, budget)
+        .map_while(Result::ok)
+}
+
+/// Strips the `https://openalex.org/` prefix, since filters expect bare IDs like `F4320332161`.
+fn short_id(id: &str) -> &str {
+    id.rsplit('/').next().unwrap_or(id)
+}
+
+fn resolve_neighbors_by_id<B: Backend + 'static>(
+    client: &OpenAlexClient<B>,
+    contexts: ContextIterator<'static, Vertex>,
+    kind: VertexKind,
+    neighbor_ids: fn(&Vertex) -> Vec<String>,
+) -> ContextOutcomeIterator<'static, Vertex, VertexIterator<'static, Vertex>> {
+    let client = client.clone();
+    Box::new(contexts.map(move |ctx| {
+        let neighbors = match ctx.active_vertex() {
+            None => Box::new(std::iter::empty()),
+            Some(vertex) => fetch_neighbors_by_id(&client, neighbor_ids(vertex), kind),
+        };
+
+        (ctx, neighbors)
+    }))
+}
+
+/// The ID filters select by: the active vertex's, without the `https://openalex.org/` prefix.
+fn filter_id(vertex: &Vertex) -> &str {
+    let object = vertex
+        .as_object()
+        .expect("vertex was not an OpenAlex entity");
+    short_id(&object.id)
+}
+
+/// Lists every vertex of `kind` whose `filter_key` matches the active vertex's ID, e.g. all
+/// `works` with `primary_location.source.publisher_lineage:P4310319965`, narrowed further
+/// by `extra_filter`.
+fn resolve_filtered_neighbors<B: Backend + 'static>(
+    client: &OpenAlexClient<B>,
+    contexts: ContextIterator<'static, Vertex>,
+    parameters: &EdgeParameters,
+    budget: PageBudget,
+    kind: VertexKind,
+    filter_key: &'static str,
+    extra_filter: Option<String>,
+) -> ContextOutcomeIterator<'static, Vertex, VertexIterator<'static, Vertex>> {
+    let sort = sort_parameter(parameters);
+    let client = client.clone();
+
+    Box::new(contexts.map(move |ctx| {
+        let neighbors = match ctx.active_vertex() {
+            None => Box::new(std::iter::empty()),
+            Some(vertex) => {
+                let mut filter = format!("{filter_key}:{}", filter_id(vertex));
+                if let Some(extra_filter) = &extra_filter {
+                    filter = format!("{filter},{extra_filter}");
+                }
+
+                fetch_all_vertices(
+                    &client,
+                    kind,
+                    ListQuery::filtered(filter).with_sort(sort.clone()),
+                    budget.clone(),
+                )
+            }
+        };
+
+        (ctx, neighbors)
+    }))
+}
+
+/// Records parameters that can't be sent, for an edge that then has no neighbors.
+fn no_neighbors<B: Backend + 'static>(
+    client: &OpenAlexClient<B>,
+    contexts: ContextIterator<'static, Vertex>,
+    error: FilterError,
+) -> ContextOutcomeIterator<'static, Vertex, VertexIterator<'static, Vertex>> {
+    client
+        .diagnostics()
+        .record(Diagnostic::InvalidFilter(error));
+    Box::new(contexts.map(|ctx| {
+        let neighbors: VertexIterator<'static, Vertex> = Box::new(std::iter::empty());
+        (ctx, neighbors)
+    }))
+}
+
+/// Counts the works whose `filter_key` matches the active vertex's ID, narrowed further by
+/// the `filter` edge parameter (an OpenAlex filter expression such as `publication_year:>2020`).
+fn resolve_filtered_count<B: Backend + 'static>(
+    client: &OpenAlexClient<B>,
+    contexts: ContextIterator<'static, Vertex>,
+    parameters: &EdgeParameters,
+    filter_key: &'static str,
+) -> ContextOutcomeIterator<'static, Vertex, VertexIterator<'static, Vertex>> {
+    let extra_filter = match filter_parameter(parameters, VertexKind::Work) {
+        Ok(extra_filter) => extra_filter,
+        Err(e) => return no_neighbors(client, contexts, e),
+    };
+    let client = client.clone();
+
+    Box::new(contexts.map(move |ctx| {
+        let neighbors: VertexIterator<'static, Vertex> = match ctx.active_vertex() {
+            None => Box::new(std::iter::empty()),
+            Some(vertex) => {
+                let mut filter = format!("{filter_key}:{}", filter_id(vertex));
+                if let Some(extra_filter) = &extra_filter {
+                    filter = format!("{filter},{extra_filter}");
+                }
+                let query = ListQuery::filtered(filter.clone());
+
+                match client.fetch_count(VertexKind::Work, &query) {
+                    Ok(count) => Box::new(std::iter::once(Vertex::FilteredCount(FilteredCount {
+                        filter,
+                        count,
+                    }))),
+                    Err(_) => Box::new(std::iter::empty()),
+                }
+            }
+        };
+
+        (ctx, neighbors)
+    }))
+}
+
+/// The IDs naming entities of `kind`, e.g. the publishers among a source's hosts, which
+/// may be institutions instead.
+fn ids_of_kind<'a>(ids: impl Iterator<Item = &'a String>, kind: VertexKind) -> Vec<String> {
+    ids.filter(|id| VertexKind::from_id(id) == Some(kind))
+        .cloned()
+        .collect()
+}
+
+fn associated_institution_ids(vertex: &Vertex, relationship: &str) -> Vec<String> {
+    let institution = vertex
+        .as_institution()
+        .expect("vertex was not an institution");
+    institution
+        .associated_institutions
+        .iter()
+        .filter(|associated| associated.relationship.as_deref() == Some(relationship))
+        .map(|associated| associated.id.clone())
+        .collect()
+}
+
+/// Wraps objects nested in the active vertex, such as a work's MeSH headings, as vertices
+/// of their own, without fetching anything.
+fn resolve_nested<T: 'static>(
+    contexts: ContextIterator<'static, Vertex>,
+    nested: fn(&Vertex) -> Vec<T>,
+    into_vertex: fn(T) -> Vertex,
+) -> ContextOutcomeIterator<'static, Vertex, VertexIterator<'static, Vertex>> {
+    Box::new(contexts.map(move |ctx| {
+        let neighbors: VertexIterator<'static, Vertex> = match ctx.active_vertex() {
+            None => Box::new(std::iter::empty()),
+            Some(vertex) => Box::new(nested(vertex).into_iter().map(into_vertex)),
+        };
+
+        (ctx, neighbors)
+    }))
+}
+
+/// Follows the `path` parameter into the JSON each vertex was read from. A path that doesn't
+/// parse leads nowhere, from any vertex.
+fn resolve_json_path<B: Backend + 'static>(
+    client: &OpenAlexClient<B>,
+    contexts: ContextIterator<'static, Vertex>,
+    parameters: &EdgeParameters,
+) -> ContextOutcomeIterator<'static, Vertex, VertexIterator<'static, Vertex>> {
+    let path = parameters
+        .get("path")
+        .and_then(|v| v.as_str())
+        .expect("json_path was missing its path");
+    let path = match JsonPath::parse(path) {
+        Ok(path) => Some(path),
+        Err(e) => {
+            client.diagnostics().record(Diagnostic::InvalidJsonPath(e));
+            None
+        }
+    };
+
+    Box::new(contexts.map(move |ctx| {
+        let json = ctx.active_vertex().and_then(Vertex::raw_json);
+        let neighbors: VertexIterator<'static, Vertex> = match (&path, json) {
+            (Some(path), Some(json)) => Box::new(
+                path.select(&json)
+                    .into_iter()
+                    .cloned()
+                    .map(Vertex::JsonValue)
+                    .collect::<Vec<_>>()
+                    .into_iter(),
+            ),
+            _ => Box::new(std::iter::empty()),
+        };
+
+        (ctx, neighbors)
+    }))
+}
+
+fn year_counts(vertex: &Vertex) -> Vec<YearCount> {
+    let object = vertex
+        .as_object()
+        .expect("vertex was not an OpenAlex entity");
+    object.counts_by_year.clone()
+}
+
+/// Hydrates dehydrated topics, carrying over the score (works) or count (authors, sources)
+/// that only exist on the dehydrated form.
+fn resolve_topic_neighbors<B: Backend + 'static>(
+    client: &OpenAlexClient<B>,
+    contexts: ContextIterator<'static, Vertex>,
+    dehydrated_topics: fn(&Vertex) -> Vec<DehydratedTopic>,
+) -> ContextOutcomeIterator<'static, Vertex, VertexIterator<'static, Vertex>> {
+    let client = client.clone();
+    Box::new(contexts.map(move |ctx| {
+        let client = client.clone();
+        let neighbors: VertexIterator<'static, Vertex> = match ctx.active_vertex() {
+            None => Box::new(std::iter::empty()),
+            Some(vertex) => Box::new(dehydrated_topics(vertex).into_iter().filter_map(
+                move |dehydrated| {
+                    match client.fetch_vertex(dehydrated.id.clone(), VertexKind::Topic) {
+                        Ok(Vertex::Topic(mut topic)) => {
+                            topic.score = dehydrated.score;
+                            topic.count = dehydrated.count;
+                            Some(Vertex::Topic(topic))
+                        }
+                        Ok(vertex) => Some(vertex),
+                        Err(_) => None,
+                    }
+                },
+            )),
+        };
+
+        (ctx, neighbors)
+    }))
+}
+
+/// Answers Trustfall queries over OpenAlex, with responses from the backend `B`: the API
+/// itself by default, or e.g. a snapshot, or a mirror layered over the API.
+pub struct OpenAlexAdapter<B = HttpBackend> {
+    client: OpenAlexClient<B>,
+}
+
+impl Default for OpenAlexAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl OpenAlexAdapter {
     pub fn new() -> Self {
-        Self
+        Self::with_backend(HttpBackend::default())
+    }
+
+    /// Stops sending requests once `max_requests` have been sent by this adapter.
+    pub fn with_max_requests(self, max_requests: u64) -> Self {
+        self.client.backend().budget().max_requests = Some(max_requests);
+        self
+    }
+
+    /// Stops sending requests once `max_daily_requests` have been sent today (UTC), counting
+    /// earlier runs too: the day's count is kept in the file at `usage_path`.
+    pub fn with_max_daily_requests(
+        self,
+        max_daily_requests: u64,
+        usage_path: impl Into<PathBuf>,
+    ) -> Self {
+        {
+            let mut budget = self.client.backend().budget();
+            budget.max_daily_requests = Some(max_daily_requests);
+            budget.usage_path = Some(usage_path.into());
+        }
+        self
+    }
+}
+
+impl<B: Backend> OpenAlexAdapter<MirrorBackend<B>> {
+    /// Refreshes the mirror's entities that changed in OpenAlex since they were last synced.
+    pub fn sync_mirror(&self) -> Result<SyncReport, FetchError> {
+        self.client.backend().sync()
+    }
+}
+
+impl<B: Backend + 'static> OpenAlexAdapter<B> {
+    pub fn with_backend(backend: B) -> Self {
+        Self {
+            client: OpenAlexClient::new(backend),
+        }
+    }
+
+    pub fn backend(&self) -> &B {
+        self.client.backend()
+    }
+
+    /// The number of requests this adapter has sent to the OpenAlex API so far.
+    pub fn requests(&self) -> u64 {
+        self.client.backend().requests()
+    }
+
+    /// Removes and returns the diagnostics recorded by queries run so far, e.g. list edges
+    /// truncated by their `limit:` or `max_pages:` parameters, or requests that failed.
+    pub fn take_diagnostics(&self) -> Vec<Diagnostic> {
+        self.client.diagnostics().take()
+    }
+
+    /// Describes the requests a query would send, without running it. With `count_matches`,
+    /// spends one request reading how many results the starting edge's list has.
+    pub fn explain(&self, query: &IRQuery, count_matches: bool) -> QueryPlan {
+        explain_query(query, count_matches.then_some(&self.client))
+    }
+
+    /// How far a list edge may page, from its `limit` and `max_pages` parameters.
+    fn page_budget(&self, parameters: &EdgeParameters) -> PageBudget {
+        let (limit, max_pages) = page_limits(parameters);
+        PageBudget {
+            limit,
+            max_pages,
+            diagnostics: self.client.diagnostics().clone(),
+        }
     }
 
     fn search_id(&self, url: String, vertex_kind: String) -> VertexIterator<'static, Vertex> {
-        match fetch_vertex(
+        match self.client.fetch_vertex(
             url.clone(),
             match vertex_kind.as_str() {
                 "Work" => VertexKind::Work,
@@ -463,32 +1409,21 @@ impl OpenAlexAdapter {
                 "Institution" => VertexKind::Institution,
                 "Publisher" => VertexKind::Publisher,
                 "Funder" => VertexKind::Funder,
+                "Topic" => VertexKind::Topic,
+                "Subfield" => VertexKind::Subfield,
+                "Field" => VertexKind::Field,
+                "Domain" => VertexKind::Domain,
                 _ => unreachable!("Not a valid vertex kind"),
             },
         ) {
             Ok(vertex) => Box::new(std::iter::once(vertex)),
-            Err(e) => {
-                eprintln!("API error when fetching or deserializing {url}: {e}");
-                Box::new(std::iter::empty())
-            }
+            Err(_) => Box::new(std::iter::empty()),
         }
     }
 
     fn random(&self, vertex_kind: String) -> VertexIterator<'static, Vertex> {
-        let random_url = "https://api.openalex.org/".to_owned()
-            + match vertex_kind.as_str() {
-                "Work" => "works",
-                "Author" => "authors",
-                "Source" => "sources",
-                "Concept" => "concepts",
-                "Institution" => "institutions",
-                "Publisher" => "publishers",
-                "Funder" => "funders",
-                _ => unreachable!("Not a valid vertex kind"),
-            }
-            + "/random";
-        match fetch_vertex(
-            random_url.clone(),
+        match self.client.fetch_vertex(
+            "random".to_string(),
             match vertex_kind.as_str() {
                 "Work" => VertexKind::Work,
                 "Author" => VertexKind::Author,
@@ -497,19 +1432,111 @@ impl OpenAlexAdapter {
                 "Institution" => VertexKind::Institution,
                 "Publisher" => VertexKind::Publisher,
                 "Funder" => VertexKind::Funder,
+                "Topic" => VertexKind::Topic,
+                "Subfield" => VertexKind::Subfield,
+                "Field" => VertexKind::Field,
+                "Domain" => VertexKind::Domain,
                 _ => unreachable!("Not a valid vertex kind"),
             },
         ) {
             Ok(vertex) => Box::new(std::iter::once(vertex)),
-            Err(e) => {
-                eprintln!("API error when fetching or deserializing {random_url}: {e}");
-                Box::new(std::iter::empty())
-            }
+            Err(_) => Box::new(std::iter::empty()),
+        }
+    }
+
+    fn group(
+        &self,
+        kind: VertexKind,
+        parameters: &EdgeParameters,
+    ) -> VertexIterator<'static, Vertex> {
+        let (query, group_by) = match group_query(kind, parameters) {
+            Ok(query) => query,
+            Err(e) => return self.invalid_filter(e),
+        };
+
+        match self.client.fetch_groups(kind, &query, &group_by) {
+            Ok(buckets) => Box::new(buckets.into_iter().map(Vertex::GroupBucket)),
+            Err(_) => Box::new(std::iter::empty()),
+        }
+    }
+
+    fn autocomplete(&self, parameters: &EdgeParameters) -> VertexIterator<'static, Vertex> {
+        let (entity, q) = autocomplete_parameters(parameters);
+
+        match self.client.fetch_autocomplete(entity.as_deref(), &q) {
+            Ok(results) => Box::new(results.into_iter().map(Vertex::AutocompleteResult)),
+            Err(_) => Box::new(std::iter::empty()),
+        }
+    }
+
+    fn sample(
+        &self,
+        kind: VertexKind,
+        parameters: &EdgeParameters,
+    ) -> VertexIterator<'static, Vertex> {
+        let query = match sample_query(kind, parameters) {
+            Ok(query) => query,
+            Err(e) => return self.invalid_filter(e),
+        };
+        if query.sample == Some(0) {
+            return Box::new(std::iter::empty());
         }
+
+        let (limit, max_pages) = explicit_page_limits(parameters);
+        let budget = PageBudget {
+            limit,
+            max_pages,
+            diagnostics: self.client.diagnostics().clone(),
+        };
+        Box::new(
+            self.client
+                .fetch_numbered_pages(kind, query, budget)
+                .map_while(Result::ok),
+        )
+    }
+
+    fn list(
+        &self,
+        kind: VertexKind,
+        parameters: &EdgeParameters,
+    ) -> VertexIterator<'static, Vertex> {
+        let query = match list_query(kind, parameters) {
+            Ok(query) => query,
+            Err(e) => return self.invalid_filter(e),
+        };
+
+        fetch_all_vertices(&self.client, kind, query, self.page_budget(parameters))
+    }
+
+    /// Records a `filter` parameter that can't be sent, for an edge that then has no vertices.
+    fn invalid_filter(&self, error: FilterError) -> VertexIterator<'static, Vertex> {
+        self.client
+            .diagnostics()
+            .record(Diagnostic::InvalidFilter(error));
+        Box::new(std::iter::empty())
+    }
+
+    fn tagged_works(
+        &self,
+        edge_name: &str,
+        parameters: &EdgeParameters,
+    ) -> VertexIterator<'static, Vertex> {
+        let query = match tagged_works_query(edge_name, parameters) {
+            Some(Ok(query)) => query,
+            Some(Err(e)) => return self.invalid_filter(e),
+            None => unreachable!("`{edge_name}` has no tag parameter"),
+        };
+
+        fetch_all_vertices(
+            &self.client,
+            VertexKind::Work,
+            query,
+            self.page_budget(parameters),
+        )
     }
 }
 
-impl Adapter<'static> for OpenAlexAdapter {
+impl<B: Backend + 'static> Adapter<'static> for OpenAlexAdapter<B> {
     type Vertex = Vertex;
 
     fn resolve_starting_vertices(
@@ -534,27 +1561,105 @@ impl Adapter<'static> for OpenAlexAdapter {
             "OpenAlexRandomInstitution" => self.random("Institution".to_string()),
             "OpenAlexRandomPublisher" => self.random("Publisher".to_string()),
             "OpenAlexRandomFunder" => self.random("Funder".to_string()),
-            _ => unreachable!("todo"),
-        }
-    }
-
-    fn resolve_property(
-        &self,
-        contexts: ContextIterator<'static, Self::Vertex>,
-        type_name: &Arc<str>,
-        property_name: &Arc<str>,
-        _resolve_info: &ResolveInfo,
-    ) -> ContextOutcomeIterator<'static, Self::Vertex, FieldValue> {
-        if property_name.as_ref() == "__typename" {
-            Box::new(
-                contexts.map(|ctx: DataContext<Vertex>| match ctx.active_vertex() {
-                    Some(vertex) => {
-                        let value = vertex.typename().into();
-                        (ctx, value)
-                    }
-                    None => (ctx, FieldValue::Null),
-                }),
+            "OpenAlexIDSearchTopic" => self.search_id(
+                parameters
+                    .get("id")
+                    .and_then(|v| v.as_str())
+                    .unwrap()
+                    .to_string(),
+                "Topic".to_string(),
+            ),
+            "OpenAlexIDSearchSubfield" => self.search_id(
+                parameters
+                    .get("id")
+                    .and_then(|v| v.as_str())
+                    .unwrap()
+                    .to_string(),
+                "Subfield".to_string(),
+            ),
+            "OpenAlexIDSearchField" => self.search_id(
+                parameters
+                    .get("id")
+                    .and_then(|v| v.as_str())
+                    .unwrap()
+                    .to_string(),
+                "Field".to_string(),
+            ),
+            "OpenAlexIDSearchDomain" => self.search_id(
+                parameters
+                    .get("id")
+                    .and_then(|v| v.as_str())
+                    .unwrap()
+                    .to_string(),
+                "Domain".to_string(),
+            ),
+            "OpenAlexSDGWorks" | "OpenAlexKeywordWorks" => {
+                self.tagged_works(edge_name.as_ref(), parameters)
+            }
+            "GroupWorks" => self.group(VertexKind::Work, parameters),
+            "GroupAuthors" => self.group(VertexKind::Author, parameters),
+            "GroupSources" => self.group(VertexKind::Source, parameters),
+            "GroupInstitutions" => self.group(VertexKind::Institution, parameters),
+            "GroupConcepts" => self.group(VertexKind::Concept, parameters),
+            "GroupPublishers" => self.group(VertexKind::Publisher, parameters),
+            "GroupFunders" => self.group(VertexKind::Funder, parameters),
+            "GroupTopics" => self.group(VertexKind::Topic, parameters),
+            "GroupSubfields" => self.group(VertexKind::Subfield, parameters),
+            "GroupFields" => self.group(VertexKind::Field, parameters),
+            "GroupDomains" => self.group(VertexKind::Domain, parameters),
+            "Autocomplete" => self.autocomplete(parameters),
+            "SampleWorks" => self.sample(VertexKind::Work, parameters),
+            "SampleAuthors" => self.sample(VertexKind::Author, parameters),
+            "SampleSources" => self.sample(VertexKind::Source, parameters),
+            "SampleInstitutions" => self.sample(VertexKind::Institution, parameters),
+            "SampleConcepts" => self.sample(VertexKind::Concept, parameters),
+            "SamplePublishers" => self.sample(VertexKind::Publisher, parameters),
+            "SampleFunders" => self.sample(VertexKind::Funder, parameters),
+            "SampleTopics" => self.sample(VertexKind::Topic, parameters),
+            "SampleSubfields" => self.sample(VertexKind::Subfield, parameters),
+            "SampleFields" => self.sample(VertexKind::Field, parameters),
+            "SampleDomains" => self.sample(VertexKind::Domain, parameters),
+            "ListWorks" => self.list(VertexKind::Work, parameters),
+            "ListAuthors" => self.list(VertexKind::Author, parameters),
+            "ListSources" => self.list(VertexKind::Source, parameters),
+            "ListInstitutions" => self.list(VertexKind::Institution, parameters),
+            "ListConcepts" => self.list(VertexKind::Concept, parameters),
+            "ListPublishers" => self.list(VertexKind::Publisher, parameters),
+            "ListFunders" => self.list(VertexKind::Funder, parameters),
+            "ListTopics" => self.list(VertexKind::Topic, parameters),
+            "ListSubfields" => self.list(VertexKind::Subfield, parameters),
+            "ListFields" => self.list(VertexKind::Field, parameters),
+            "ListDomains" => self.list(VertexKind::Domain, parameters),
+            _ => unreachable!("todo"),
+        }
+    }
+
+    fn resolve_property(
+        &self,
+        contexts: ContextIterator<'static, Self::Vertex>,
+        type_name: &Arc<str>,
+        property_name: &Arc<str>,
+        _resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'static, Self::Vertex, FieldValue> {
+        if property_name.as_ref() == "__typename" {
+            Box::new(
+                contexts.map(|ctx: DataContext<Vertex>| match ctx.active_vertex() {
+                    Some(vertex) => {
+                        let value = vertex.typename().into();
+                        (ctx, value)
+                    }
+                    None => (ctx, FieldValue::Null),
+                }),
             )
+        } else if property_name.as_ref() == "raw_json" {
+            Box::new(contexts.map(|ctx: DataContext<Vertex>| {
+                let value = ctx
+                    .active_vertex()
+                    .and_then(Vertex::raw_json)
+                    .map(|json| json.to_string())
+                    .into();
+                (ctx, value)
+            }))
         } else {
             let property_name = property_name.clone();
             match type_name.as_ref() {
@@ -579,6 +1684,61 @@ impl Adapter<'static> for OpenAlexAdapter {
                 "Funder" => Box::new(contexts.map(move |ctx: DataContext<Vertex>| {
                     property_mapper(ctx, property_name.as_ref(), get_funder_property)
                 })),
+                "Topic" => Box::new(contexts.map(move |ctx: DataContext<Vertex>| {
+                    property_mapper(ctx, property_name.as_ref(), get_topic_property)
+                })),
+                "Subfield" => Box::new(contexts.map(move |ctx: DataContext<Vertex>| {
+                    property_mapper(ctx, property_name.as_ref(), get_subfield_property)
+                })),
+                "Field" => Box::new(contexts.map(move |ctx: DataContext<Vertex>| {
+                    property_mapper(ctx, property_name.as_ref(), get_field_property)
+                })),
+                "Domain" => Box::new(contexts.map(move |ctx: DataContext<Vertex>| {
+                    property_mapper(ctx, property_name.as_ref(), get_domain_property)
+                })),
+                "SDG" => Box::new(contexts.map(move |ctx: DataContext<Vertex>| {
+                    property_mapper(ctx, property_name.as_ref(), get_sdg_property)
+                })),
+                "Keyword" => Box::new(contexts.map(move |ctx: DataContext<Vertex>| {
+                    property_mapper(ctx, property_name.as_ref(), get_keyword_property)
+                })),
+                "YearCount" => Box::new(contexts.map(move |ctx: DataContext<Vertex>| {
+                    property_mapper(ctx, property_name.as_ref(), get_year_count_property)
+                })),
+                "Mesh" => Box::new(contexts.map(move |ctx: DataContext<Vertex>| {
+                    property_mapper(ctx, property_name.as_ref(), get_mesh_property)
+                })),
+                "Role" => Box::new(contexts.map(move |ctx: DataContext<Vertex>| {
+                    property_mapper(ctx, property_name.as_ref(), get_role_property)
+                })),
+                "Affiliation" => Box::new(contexts.map(move |ctx: DataContext<Vertex>| {
+                    property_mapper(ctx, property_name.as_ref(), get_affiliation_property)
+                })),
+                "Collaboration" => Box::new(contexts.map(move |ctx: DataContext<Vertex>| {
+                    property_mapper(ctx, property_name.as_ref(), get_collaboration_property)
+                })),
+                "WorkOverlap" => Box::new(contexts.map(move |ctx: DataContext<Vertex>| {
+                    property_mapper(ctx, property_name.as_ref(), get_work_overlap_property)
+                })),
+                "Citation" => Box::new(contexts.map(move |ctx: DataContext<Vertex>| {
+                    property_mapper(ctx, property_name.as_ref(), get_citation_property)
+                })),
+                "FilteredCount" => Box::new(contexts.map(move |ctx: DataContext<Vertex>| {
+                    property_mapper(ctx, property_name.as_ref(), get_filtered_count_property)
+                })),
+                "GroupBucket" => Box::new(contexts.map(move |ctx: DataContext<Vertex>| {
+                    property_mapper(ctx, property_name.as_ref(), get_group_bucket_property)
+                })),
+                "AutocompleteResult" => Box::new(contexts.map(move |ctx: DataContext<Vertex>| {
+                    property_mapper(
+                        ctx,
+                        property_name.as_ref(),
+                        get_autocomplete_result_property,
+                    )
+                })),
+                "JsonValue" => Box::new(contexts.map(move |ctx: DataContext<Vertex>| {
+                    property_mapper(ctx, property_name.as_ref(), get_json_value_property)
+                })),
                 _ => unreachable!("resolve_property {type_name} {property_name}"),
             }
         }
@@ -589,166 +1749,368 @@ impl Adapter<'static> for OpenAlexAdapter {
         contexts: ContextIterator<'static, Self::Vertex>,
         type_name: &Arc<str>,
         edge_name: &Arc<str>,
-        _parameters: &EdgeParameters,
+        parameters: &EdgeParameters,
         _resolve_info: &ResolveEdgeInfo,
     ) -> ContextOutcomeIterator<'static, Self::Vertex, VertexIterator<'static, Self::Vertex>> {
+        // Every vertex has `json_path`, so it's resolved the same way whatever the type.
+        if edge_name.as_ref() == "json_path" {
+            return resolve_json_path(&self.client, contexts, parameters);
+        }
+
+        match edge_request(type_name, edge_name, parameters) {
+            Some(EdgeRequest::List {
+                kind,
+                filter_key,
+                extra_filter,
+            }) => {
+                return resolve_filtered_neighbors(
+                    &self.client,
+                    contexts,
+                    parameters,
+                    self.page_budget(parameters),
+                    kind,
+                    filter_key,
+                    extra_filter,
+                )
+            }
+            Some(EdgeRequest::Count { filter_key }) => {
+                return resolve_filtered_count(&self.client, contexts, parameters, filter_key)
+            }
+            Some(EdgeRequest::Invalid(e)) => return no_neighbors(&self.client, contexts, e),
+            _ => {}
+        }
+
         match type_name.as_ref() {
             "Work" => match edge_name.as_ref() {
-                "Authors" => Box::new(contexts.map(|ctx| {
-                    let vertex = ctx.active_vertex();
-                    let neighbors: VertexIterator<'static, Self::Vertex> = match vertex
-                    {
-                        None => Box::new(std::iter::empty()),
-                        Some(vertex) => {
-                            let work = vertex.as_work().expect("vertex was not a work");
-                            let author_ids = work.authorships.clone();
-
-                            let neighbors_iter = author_ids.into_iter().filter_map(|authorship| {
-                                match fetch_vertex(authorship.author.id.clone(), VertexKind::Author) {
-                                    Ok(author_vertex) => Some(author_vertex),
-                                    Err(e) => {
-                                        eprintln!(
-                                            "API error while fetching or deserializing {}: {e}", authorship.author.id
-                                        );
-                                        None
-                                    }
-                                }
-                            });
+                "authorships" => {
+                    let client = self.client.clone();
+                    Box::new(contexts.map(move |ctx| {
+                        let client = client.clone();
+                        let vertex = ctx.active_vertex();
+                        let neighbors: VertexIterator<'static, Self::Vertex> = match vertex {
+                            None => Box::new(std::iter::empty()),
+                            Some(vertex) => {
+                                let work = vertex.as_work().expect("vertex was not a work");
+                                let author_ids = work.authorships.clone();
 
-                            Box::new(neighbors_iter)
-                        }
-                    };
+                                let neighbors_iter =
+                                    author_ids.into_iter().filter_map(move |authorship| {
+                                        client
+                                            .fetch_vertex(
+                                                authorship.author.id.clone(),
+                                                VertexKind::Author,
+                                            )
+                                            .ok()
+                                    });
 
-                    (ctx, neighbors)
-                })),
+                                Box::new(neighbors_iter)
+                            }
+                        };
 
-                "Cited_by" => Box::new(contexts.map(move |ctx| {
-                    let neighbors: VertexIterator<'static, Self::Vertex> = match ctx.active_vertex()
-                    {
-                        None => Box::new(std::iter::empty()),
-                        Some(vertex) => {
-                            let work = vertex.as_work().expect("vertex was not a work");
-                            let cited_by_ids = &work.cited_by_api_url;
+                        (ctx, neighbors)
+                    }))
+                }
+
+                "concepts" => {
+                    let client = self.client.clone();
+                    Box::new(contexts.map(move |ctx| {
+                        let client = client.clone();
+                        let neighbors: VertexIterator<'static, Self::Vertex> = match ctx
+                            .active_vertex()
+                        {
+                            None => Box::new(std::iter::empty()),
+                            Some(vertex) => {
+                                let work = vertex.as_work().expect("vertex was not a work");
+                                let concept_ids =
+                                    work.concepts.clone().into_iter().map(|concept| concept.id);
+
+                                let neighbors_iter = concept_ids.filter_map(move |concept_id| {
+                                    client
+                                        .fetch_vertex(concept_id.clone(), VertexKind::Concept)
+                                        .ok()
+                                });
 
-                            match fetch_vertices(cited_by_ids.clone(), VertexKind::Work) {
-                                Ok(cited_by_vertices) => Box::new(cited_by_vertices.into_iter()),
-                                Err(e) => {
-                                    eprintln!(
-                                        "API error while fetching or deserializing {cited_by_ids}: {e}"
-                                    );
-                                    Box::new(std::iter::empty())
+                                Box::new(neighbors_iter)
+                            }
+                        };
+
+                        (ctx, neighbors)
+                    }))
+                }
+
+                "grants" => {
+                    let client = self.client.clone();
+                    Box::new(contexts.map(move |ctx| {
+                        let client = client.clone();
+                        let neighbors: VertexIterator<'static, Self::Vertex> =
+                            match ctx.active_vertex() {
+                                None => Box::new(std::iter::empty()),
+                                Some(vertex) => {
+                                    let work = vertex.as_work().expect("vertex was not a work");
+                                    let funder_ids = work
+                                        .grants
+                                        .clone()
+                                        .into_iter()
+                                        .filter_map(|grant| grant.funder);
+
+                                    let neighbors_iter = funder_ids.filter_map(move |funder_id| {
+                                        client
+                                            .fetch_vertex(funder_id.clone(), VertexKind::Funder)
+                                            .ok()
+                                    });
+
+                                    Box::new(neighbors_iter)
                                 }
+                            };
+
+                        (ctx, neighbors)
+                    }))
+                }
+
+                "referenced_works" => {
+                    let client = self.client.clone();
+                    Box::new(contexts.map(move |ctx| {
+                        let client = client.clone();
+                        let neighbors: VertexIterator<'static, Self::Vertex> = match ctx
+                            .active_vertex()
+                        {
+                            None => Box::new(std::iter::empty()),
+                            Some(vertex) => {
+                                let work = vertex.as_work().expect("vertex was not a work");
+                                let reference_ids = work.referenced_works.clone();
+
+                                let neighbors_iter =
+                                    reference_ids.into_iter().filter_map(move |reference_id| {
+                                        client
+                                            .fetch_vertex(reference_id.clone(), VertexKind::Work)
+                                            .ok()
+                                    });
+
+                                Box::new(neighbors_iter)
                             }
-                        }
-                    };
+                        };
 
-                    (ctx, neighbors)
-                })),
+                        (ctx, neighbors)
+                    }))
+                }
 
-                "Concepts" => Box::new(contexts.map(move |ctx| {
-                    let neighbors: VertexIterator<'static, Self::Vertex> = match ctx.active_vertex()
-                    {
-                        None => Box::new(std::iter::empty()),
-                        Some(vertex) => {
-                            let work = vertex.as_work().expect("vertex was not a work");
-                            let concept_ids = work.concepts.clone().into_iter().map(|concept| concept.id);
-
-                            let neighbors_iter = concept_ids.filter_map(move |concept_id| {
-                                match fetch_vertex(concept_id.clone(), VertexKind::Concept) {
-                                    Ok(concept_vertex) => Some(concept_vertex),
-                                    Err(e) => {
-                                        eprintln!(
-                                            "API error while fetching or deserializing {concept_id}: {e}"
-                                        );
-                                        None
-                                    }
+                "related_works" => {
+                    let client = self.client.clone();
+                    Box::new(contexts.map(move |ctx| {
+                        let client = client.clone();
+                        let neighbors: VertexIterator<'static, Self::Vertex> =
+                            match ctx.active_vertex() {
+                                None => Box::new(std::iter::empty()),
+                                Some(vertex) => {
+                                    let work = vertex.as_work().expect("vertex was not a work");
+                                    let related_ids = work.related_works.clone();
+
+                                    let neighbors_iter =
+                                        related_ids.into_iter().filter_map(move |related_id| {
+                                            client
+                                                .fetch_vertex(related_id.clone(), VertexKind::Work)
+                                                .ok()
+                                        });
+
+                                    Box::new(neighbors_iter)
                                 }
-                            });
+                            };
+
+                        (ctx, neighbors)
+                    }))
+                }
+
+                "primary_location" => {
+                    resolve_neighbors_by_id(&self.client, contexts, VertexKind::Source, |vertex| {
+                        let work = vertex.as_work().expect("vertex was not a work");
+                        work.primary_location
+                            .iter()
+                            .filter_map(|location| location.source.clone())
+                            .map(|source| source.id)
+                            .collect()
+                    })
+                }
+
+                "best_oa_location" => {
+                    resolve_neighbors_by_id(&self.client, contexts, VertexKind::Source, |vertex| {
+                        let work = vertex.as_work().expect("vertex was not a work");
+                        work.best_oa_location
+                            .iter()
+                            .filter_map(|location| location.source.clone())
+                            .map(|source| source.id)
+                            .collect()
+                    })
+                }
+
+                "locations" => {
+                    resolve_neighbors_by_id(&self.client, contexts, VertexKind::Source, |vertex| {
+                        let work = vertex.as_work().expect("vertex was not a work");
+                        work.locations
+                            .iter()
+                            .filter_map(|location| location.source.clone())
+                            .map(|source| source.id)
+                            .collect()
+                    })
+                }
+
+                "corresponding_author_ids" => {
+                    resolve_neighbors_by_id(&self.client, contexts, VertexKind::Author, |vertex| {
+                        let work = vertex.as_work().expect("vertex was not a work");
+                        work.corresponding_author_ids.clone().unwrap_or_default()
+                    })
+                }
+
+                "corresponding_institution_ids" => resolve_neighbors_by_id(
+                    &self.client,
+                    contexts,
+                    VertexKind::Institution,
+                    |vertex| {
+                        let work = vertex.as_work().expect("vertex was not a work");
+                        work.corresponding_institution_ids
+                            .clone()
+                            .unwrap_or_default()
+                    },
+                ),
+
+                "mesh" => resolve_nested(
+                    contexts,
+                    |vertex| {
+                        let work = vertex.as_work().expect("vertex was not a work");
+                        work.mesh.clone()
+                    },
+                    Vertex::Mesh,
+                ),
+
+                "object_counts_by_year" => resolve_nested(contexts, year_counts, Vertex::YearCount),
+
+                "primary_topic" => resolve_topic_neighbors(&self.client, contexts, |vertex| {
+                    let work = vertex.as_work().expect("vertex was not a work");
+                    work.primary_topic.clone().into_iter().collect()
+                }),
 
-                            Box::new(neighbors_iter)
-                        }
-                    };
+                "topics" => resolve_topic_neighbors(&self.client, contexts, |vertex| {
+                    let work = vertex.as_work().expect("vertex was not a work");
+                    work.topics.clone().unwrap_or_default()
+                }),
 
-                    (ctx, neighbors)
-                })),
+                "citations" => {
+                    let client = self.client.clone();
+                    let budget = self.page_budget(parameters);
+                    Box::new(contexts.map(move |ctx| {
+                        let client = client.clone();
+                        let neighbors: VertexIterator<'static, Self::Vertex> =
+                            match ctx.active_vertex() {
+                                None => Box::new(std::iter::empty()),
+                                Some(vertex) => {
+                                    let work = Arc::new(
+                                        vertex.as_work().expect("vertex was not a work").clone(),
+                                    );
+                                    let citing_works = fetch_citing_works(
+                                        &client,
+                                        vec![work.object.id.clone()],
+                                        budget.clone(),
+                                    );
 
-                "Funders" => Box::new(contexts.map(move |ctx| {
-                    let neighbors: VertexIterator<'static, Self::Vertex> = match ctx.active_vertex()
-                    {
-                        None => Box::new(std::iter::empty()),
-                        Some(vertex) => {
-                            let work = vertex.as_work().expect("vertex was not a work");
-                            let funder_ids = work.grants.clone().into_iter().map(|grant| grant.funder);
-
-                            let neighbors_iter = funder_ids.filter_map(move |funder_id| {
-                                match fetch_vertex(funder_id.clone(), VertexKind::Funder) {
-                                    Ok(funder_vertex) => Some(funder_vertex),
-                                    Err(e) => {
-                                        eprintln!(
-                                            "API error while fetching or deserializing {funder_id}: {e}"
+                                    Box::new(citing_works.map(move |citing_vertex| {
+                                        match citing_vertex {
+                                            Vertex::Work(citing_work) => {
+                                                Vertex::Citation(citation(&work, citing_work))
+                                            }
+                                            _ => unreachable!("citing vertex was not a work"),
+                                        }
+                                    }))
+                                }
+                            };
+
+                        (ctx, neighbors)
+                    }))
+                }
+
+                "bibliographically_coupled" => {
+                    let client = self.client.clone();
+                    let budget = self.page_budget(parameters);
+                    Box::new(contexts.map(move |ctx| {
+                        let client = client.clone();
+                        let neighbors: VertexIterator<'static, Self::Vertex> =
+                            match ctx.active_vertex() {
+                                None => Box::new(std::iter::empty()),
+                                Some(vertex) => {
+                                    let work = vertex.as_work().expect("vertex was not a work");
+                                    let work_id = work.object.id.clone();
+                                    let references = work.referenced_works.clone();
+
+                                    let budget = budget.clone();
+                                    Box::new(std::iter::once(()).flat_map(move |_| {
+                                        let citing_works = fetch_citing_works(
+                                            &client,
+                                            references.clone(),
+                                            budget.clone(),
                                         );
-                                        None
-                                    }
+                                        bibliographic_coupling(&work_id, &references, citing_works)
+                                            .into_iter()
+                                            .map(Vertex::WorkOverlap)
+                                    }))
                                 }
-                            });
-
-                            Box::new(neighbors_iter)
-                        }
-                    };
+                            };
+
+                        (ctx, neighbors)
+                    }))
+                }
+
+                "co_cited_with" => {
+                    let client = self.client.clone();
+                    let budget = self.page_budget(parameters);
+                    Box::new(contexts.map(move |ctx| {
+                        let client = client.clone();
+                        let neighbors: VertexIterator<'static, Self::Vertex> =
+                            match ctx.active_vertex() {
+                                None => Box::new(std::iter::empty()),
+                                Some(vertex) => {
+                                    let work = vertex.as_work().expect("vertex was not a work");
+                                    let work_id = work.object.id.clone();
+
+                                    let budget = budget.clone();
+                                    Box::new(std::iter::once(()).flat_map(move |_| {
+                                        let citing_works = fetch_citing_works(
+                                            &client,
+                                            vec![work_id.clone()],
+                                            budget.clone(),
+                                        );
+                                        co_citation(&work_id, citing_works)
+                                            .into_iter()
+                                            .map(Vertex::WorkOverlap)
+                                    }))
+                                }
+                            };
 
-                    (ctx, neighbors)
-                })),
+                        (ctx, neighbors)
+                    }))
+                }
 
-                "References" => Box::new(contexts.map(move |ctx| {
+                "sdgs" => Box::new(contexts.map(move |ctx| {
                     let neighbors: VertexIterator<'static, Self::Vertex> = match ctx.active_vertex()
                     {
                         None => Box::new(std::iter::empty()),
                         Some(vertex) => {
                             let work = vertex.as_work().expect("vertex was not a work");
-                            let reference_ids = work.referenced_works.clone();
-
-                            let neighbors_iter =
-                                reference_ids.into_iter().filter_map(move |reference_id| {
-                                    match fetch_vertex(reference_id.clone(), VertexKind::Work) {
-                                        Ok(work_vertex) => Some(work_vertex),
-                                        Err(e) => {
-                                            eprintln!(
-                                "API error while fetching or deserializing {reference_id}: {e}"
-                            );
-                                            None
-                                        }
-                                    }
-                                });
+                            let sdgs = work.sustainable_development_goals.clone();
 
-                            Box::new(neighbors_iter)
+                            Box::new(sdgs.into_iter().map(Vertex::Sdg))
                         }
                     };
 
                     (ctx, neighbors)
                 })),
 
-                "Related" => Box::new(contexts.map(move |ctx| {
+                "keywords" => Box::new(contexts.map(move |ctx| {
                     let neighbors: VertexIterator<'static, Self::Vertex> = match ctx.active_vertex()
                     {
                         None => Box::new(std::iter::empty()),
                         Some(vertex) => {
                             let work = vertex.as_work().expect("vertex was not a work");
-                            let related_ids = work.related_works.clone();
-
-                            let neighbors_iter =
-                                related_ids.into_iter().filter_map(move |related_id| {
-                                    match fetch_vertex(related_id.clone(), VertexKind::Work) {
-                                        Ok(work_vertex) => Some(work_vertex),
-                                        Err(e) => {
-                                            eprintln!(
-                                "API error while fetching or deserializing {related_id}: {e}"
-                            );
-                                            None
-                                        }
-                                    }
-                                });
+                            let keywords = work.keywords.clone().unwrap_or_default();
 
-                            Box::new(neighbors_iter)
+                            Box::new(keywords.into_iter().map(Vertex::Keyword))
                         }
                     };
 
@@ -759,599 +2121,826 @@ impl Adapter<'static> for OpenAlexAdapter {
             },
 
             "Author" => match edge_name.as_ref() {
-                "Institution" => Box::new(contexts.map(move |ctx| {
-                    let neighbors: VertexIterator<'static, Self::Vertex> = match ctx.active_vertex()
-                    {
-                        None => Box::new(std::iter::empty()),
-                        Some(vertex) => {
-                            let author = vertex.as_author().expect("vertex was not a work");
-                            match &author.last_known_institution {
-                                Some(institution) => {
-                                    let institution_id = institution.id.clone();
-
-                                    match fetch_vertex(institution_id.clone(), VertexKind::Institution) {
-                                        Ok(institution_vertex) => {
-                                            Box::new(std::iter::once(institution_vertex))
-                                        }
-                                        Err(e) => {
-                                            eprintln!(
-                                                "API error while fetching or deserializing {institution_id}: {e}"
-                                            );
-                                            Box::new(std::iter::empty())
+                "last_known_institution" => {
+                    let client = self.client.clone();
+                    Box::new(contexts.map(move |ctx| {
+                        let client = client.clone();
+                        let neighbors: VertexIterator<'static, Self::Vertex> =
+                            match ctx.active_vertex() {
+                                None => Box::new(std::iter::empty()),
+                                Some(vertex) => {
+                                    let author = vertex.as_author().expect("vertex was not a work");
+                                    match &author.last_known_institution {
+                                        Some(institution) => {
+                                            let institution_id = institution.id.clone();
+
+                                            match client.fetch_vertex(
+                                                institution_id.clone(),
+                                                VertexKind::Institution,
+                                            ) {
+                                                Ok(institution_vertex) => {
+                                                    Box::new(std::iter::once(institution_vertex))
+                                                }
+                                                Err(_) => Box::new(std::iter::empty()),
+                                            }
                                         }
+                                        _ => Box::new(std::iter::empty()),
                                     }
                                 }
-                                _ => Box::new(std::iter::empty())
+                            };
+
+                        (ctx, neighbors)
+                    }))
+                }
+                "topics" => resolve_topic_neighbors(&self.client, contexts, |vertex| {
+                    let author = vertex.as_author().expect("vertex was not an author");
+                    author.topics.clone().unwrap_or_default()
+                }),
+                "coauthors" => {
+                    let client = self.client.clone();
+                    let budget = self.page_budget(parameters);
+                    Box::new(contexts.map(move |ctx| {
+                        let client = client.clone();
+                        let neighbors: VertexIterator<'static, Self::Vertex> = match ctx
+                            .active_vertex()
+                        {
+                            None => Box::new(std::iter::empty()),
+                            Some(vertex) => {
+                                let author = vertex.as_author().expect("vertex was not an author");
+                                let author_id = author.object.id.clone();
+                                let budget = budget.clone();
+
+                                // Defer paging through the works until the edge is actually expanded.
+                                Box::new(std::iter::once(()).flat_map(move |_| {
+                                    let works = fetch_all_vertices(
+                                        &client,
+                                        VertexKind::Work,
+                                        ListQuery::filtered(format!(
+                                            "author.id:{}",
+                                            short_id(&author_id)
+                                        )),
+                                        budget.clone(),
+                                    );
+                                    collaborations(&author_id, works)
+                                        .into_iter()
+                                        .map(Vertex::Collaboration)
+                                }))
                             }
-                        }
-                    };
+                        };
 
-                    (ctx, neighbors)
-                })),
-                "Works" => Box::new(contexts.map(move |ctx| {
+                        (ctx, neighbors)
+                    }))
+                }
+                "affiliations" => Box::new(contexts.map(move |ctx| {
                     let neighbors: VertexIterator<'static, Self::Vertex> = match ctx.active_vertex()
                     {
                         None => Box::new(std::iter::empty()),
                         Some(vertex) => {
-                            let author = vertex.as_author().expect("vertex was not a work");
-                            let works_ids = &author.works_api_url;
-
-                            match fetch_vertices(works_ids.clone(), VertexKind::Work) {
-                                Ok(work_vertices) => Box::new(work_vertices.into_iter()),
-                                Err(e) => {
-                                    eprintln!(
-                                        "API error while fetching or deserializing {works_ids}: {e}"
-                                    );
-                                    Box::new(std::iter::empty())
-                                }
-                            }
+                            let author = vertex.as_author().expect("vertex was not an author");
+                            let affiliations = author.affiliations.clone().unwrap_or_default();
+
+                            Box::new(affiliations.into_iter().map(Vertex::Affiliation))
                         }
                     };
 
                     (ctx, neighbors)
                 })),
+                "object_counts_by_year" => resolve_nested(contexts, year_counts, Vertex::YearCount),
                 _ => unreachable!("resolve_neighbors {type_name} {edge_name}"),
             },
 
             "Source" => match edge_name.as_ref() {
-                "Host" => Box::new(contexts.map(move |ctx| {
-                    let neighbors: VertexIterator<'static, Self::Vertex> = match ctx.active_vertex()
-                    {
-                        None => Box::new(std::iter::empty()),
-                        Some(vertex) => {
-                            let source = vertex.as_source().expect("vertex was not a work");
-                            match &source.host_organization {
-                                Some(host_id) => {
-                                    match fetch_vertex(host_id.clone(), VertexKind::Institution) {
-                                        Ok(institution_vertex) => {
-                                            Box::new(std::iter::once(institution_vertex))
-                                        }
-                                        Err(e) => {
-                                            eprintln!(
-                                                "API error while fetching or deserializing {host_id}: {e}"
-                                            );
-                                            Box::new(std::iter::empty())
-                                        }
-                                    }
-                                }
-                                _ => Box::new(std::iter::empty())
-                            }
-                        }
-                    };
-
-                    (ctx, neighbors)
-                })),
-
-                "Lineage" => Box::new(contexts.map(move |ctx| {
-                    let neighbors: VertexIterator<'static, Self::Vertex> = match ctx.active_vertex()
-                    {
-                        None => Box::new(std::iter::empty()),
-                        Some(vertex) => {
-                            let source = vertex.as_source().expect("vertex was not a work");
-                            let host_lineage_ids = source.host_organization_lineage.clone();
-
-                            let neighbors_iter =
-                                host_lineage_ids.into_iter().filter_map(move |host_id| {
-                                    match fetch_vertex(host_id.clone(), VertexKind::Institution) {
-                                        Ok(institution_vertex) => Some(institution_vertex),
-                                        Err(e) => {
-                                            eprintln!(
-                                                "API error while fetching or deserializing {host_id}: {e}"
-                                            );
-                                            None
-                                        }
-                                    }
-                                });
-
-                            Box::new(neighbors_iter)
-                        }
-                    };
-
-                    (ctx, neighbors)
-                })),
-
-                "Works" => Box::new(contexts.map(move |ctx| {
-                    let neighbors: VertexIterator<'static, Self::Vertex> = match ctx.active_vertex()
-                    {
-                        None => Box::new(std::iter::empty()),
-                        Some(vertex) => {
-                            let source = vertex.as_source().expect("vertex was not a work");
-                            let works_api_url = &source.works_api_url;
-
-                            match fetch_vertices(works_api_url.clone(), VertexKind::Work) {
-                                Ok(work_vertices) => Box::new(work_vertices.into_iter()),
-                                Err(e) => {
-                                    eprintln!(
-                                        "API error while fetching or deserializing {works_api_url}: {e}"
-                                    );
-                                    Box::new(std::iter::empty())
-                                }
-                            }
-                        }
-                    };
-
-                    (ctx, neighbors)
-                })),
+                "host_organization" => resolve_neighbors_by_id(
+                    &self.client,
+                    contexts,
+                    VertexKind::Institution,
+                    |vertex| {
+                        let source = vertex.as_source().expect("vertex was not a source");
+                        ids_of_kind(source.host_organization.iter(), VertexKind::Institution)
+                    },
+                ),
+                "host_organization_lineage" => resolve_neighbors_by_id(
+                    &self.client,
+                    contexts,
+                    VertexKind::Publisher,
+                    |vertex| {
+                        let source = vertex.as_source().expect("vertex was not a source");
+                        ids_of_kind(
+                            source.host_organization_lineage.iter(),
+                            VertexKind::Publisher,
+                        )
+                    },
+                ),
+                "topics" => resolve_topic_neighbors(&self.client, contexts, |vertex| {
+                    let source = vertex.as_source().expect("vertex was not a source");
+                    source.topics.clone().unwrap_or_default()
+                }),
+                "object_counts_by_year" => resolve_nested(contexts, year_counts, Vertex::YearCount),
                 _ => unreachable!("resolve_neighbors {type_name} {edge_name}"),
             },
 
             "Concept" => match edge_name.as_ref() {
-                "Ancestors" => Box::new(contexts.map(move |ctx| {
-                    let neighbors: VertexIterator<'static, Self::Vertex> = match ctx.active_vertex()
-                    {
-                        None => Box::new(std::iter::empty()),
-                        Some(vertex) => {
-                            let concept = vertex.as_concept().expect("vertex was not a concept");
-                            let ancestor_ids = concept.ancestors.clone().into_iter().map(|ancestor| ancestor.id);
-
-                            let neighbors_iter = ancestor_ids.filter_map(move |ancestor| {
-                                match fetch_vertex(ancestor.clone(), VertexKind::Concept) {
-                                    Ok(concept_vertex) => Some(concept_vertex),
-                                    Err(e) => {
-                                        eprintln!(
-                                            "API error while fetching or deserializing {ancestor}: {e}"
-                                        );
-                                        None
-                                    }
-                                }
-                            });
-
-                            Box::new(neighbors_iter)
-                        }
-                    };
-
-                    (ctx, neighbors)
-                })),
+                "ancestors" => {
+                    let client = self.client.clone();
+                    Box::new(contexts.map(move |ctx| {
+                        let client = client.clone();
+                        let neighbors: VertexIterator<'static, Self::Vertex> =
+                            match ctx.active_vertex() {
+                                None => Box::new(std::iter::empty()),
+                                Some(vertex) => {
+                                    let concept =
+                                        vertex.as_concept().expect("vertex was not a concept");
+                                    let ancestor_ids = concept
+                                        .ancestors
+                                        .clone()
+                                        .into_iter()
+                                        .map(|ancestor| ancestor.id);
+
+                                    let neighbors_iter = ancestor_ids.filter_map(move |ancestor| {
+                                        client
+                                            .fetch_vertex(ancestor.clone(), VertexKind::Concept)
+                                            .ok()
+                                    });
 
-                "Related" => Box::new(contexts.map(move |ctx| {
-                    let neighbors: VertexIterator<'static, Self::Vertex> =
-                        match ctx.active_vertex() {
+                                    Box::new(neighbors_iter)
+                                }
+                            };
+
+                        (ctx, neighbors)
+                    }))
+                }
+
+                "related_concepts" => {
+                    let client = self.client.clone();
+                    Box::new(contexts.map(move |ctx| {
+                        let client = client.clone();
+                        let neighbors: VertexIterator<'static, Self::Vertex> = match ctx
+                            .active_vertex()
+                        {
                             None => Box::new(std::iter::empty()),
                             Some(vertex) => {
                                 let concept =
                                     vertex.as_concept().expect("vertex was not a concept");
-                                let related_concept_ids =
-                                    concept.related_concepts.clone().into_iter().map(|concept| concept.id);
+                                let related_concept_ids = concept
+                                    .related_concepts
+                                    .clone()
+                                    .into_iter()
+                                    .map(|concept| concept.id);
 
                                 let neighbors_iter =
                                     related_concept_ids.filter_map(move |related_id| {
-                                        match fetch_vertex(related_id.clone(), VertexKind::Concept) {
-                                            Ok(concept_vertex) => Some(concept_vertex),
-                                            Err(e) => {
-                                                eprintln!(
-                                                    "API error while fetching or deserializing {related_id}: {e}"
-                                                );
-                                                None
-                                            }
-                                        }
+                                        client
+                                            .fetch_vertex(related_id.clone(), VertexKind::Concept)
+                                            .ok()
                                     });
 
                                 Box::new(neighbors_iter)
                             }
                         };
 
-                    (ctx, neighbors)
-                })),
-
-                "Works" => Box::new(contexts.map(move |ctx| {
-                    let neighbors: VertexIterator<'static, Self::Vertex> = match ctx.active_vertex()
-                    {
-                        None => Box::new(std::iter::empty()),
-                        Some(vertex) => {
-                            let concept = vertex.as_concept().expect("vertex was not a concept");
-                            let works_api_url = &concept.works_api_url;
-
-                            match fetch_vertices(works_api_url.clone(), VertexKind::Work) {
-                                Ok(work_vertices) => Box::new(work_vertices.into_iter()),
-                                Err(e) => {
-                                    eprintln!(
-                                        "API error while fetching or deserializing {works_api_url}: {e}"
-                                    );
-                                    Box::new(std::iter::empty())
-                                }
-                            }
-                        }
-                    };
+                        (ctx, neighbors)
+                    }))
+                }
 
-                    (ctx, neighbors)
-                })),
+                "object_counts_by_year" => resolve_nested(contexts, year_counts, Vertex::YearCount),
                 _ => unreachable!("resolve_neighbors {type_name} {edge_name}"),
             },
 
             "Institution" => match edge_name.as_ref() {
-                "Associated" => Box::new(contexts.map(move |ctx| {
-                    let neighbors: VertexIterator<'static, Self::Vertex> = match ctx.active_vertex()
-                    {
-                        None => Box::new(std::iter::empty()),
-                        Some(vertex) => {
-                            let institution = vertex
-                                .as_institution()
-                                .expect("vertex was not an institution");
-                            let associated_institution_ids = institution
-                                .associated_institutions
-                                .clone()
-                                .into_iter()
-                                .map(|institution| institution.id);
-
-                            let neighbors_iter =
-                                associated_institution_ids.filter_map(move |institution_id| {
-                                    match fetch_vertex(institution_id.clone(), VertexKind::Institution) {
-                                        Ok(institution_vertex) => Some(institution_vertex),
-                                        Err(e) => {
-                                            eprintln!(
-                                                "API error while fetching or deserializing {institution_id}: {e}"
-                                            );
-                                            None
-                                        }
-                                    }
-                                });
-
-                            Box::new(neighbors_iter)
-                        }
-                    };
+                "parents" => resolve_neighbors_by_id(
+                    &self.client,
+                    contexts,
+                    VertexKind::Institution,
+                    |vertex| associated_institution_ids(vertex, "parent"),
+                ),
+                "children" => resolve_neighbors_by_id(
+                    &self.client,
+                    contexts,
+                    VertexKind::Institution,
+                    |vertex| associated_institution_ids(vertex, "child"),
+                ),
+                "related" => resolve_neighbors_by_id(
+                    &self.client,
+                    contexts,
+                    VertexKind::Institution,
+                    |vertex| associated_institution_ids(vertex, "related"),
+                ),
+                "lineage" => resolve_neighbors_by_id(
+                    &self.client,
+                    contexts,
+                    VertexKind::Institution,
+                    |vertex| {
+                        let institution = vertex
+                            .as_institution()
+                            .expect("vertex was not an institution");
+                        institution.lineage.clone().unwrap_or_default()
+                    },
+                ),
+                "associated_institutions" => {
+                    let client = self.client.clone();
+                    Box::new(contexts.map(move |ctx| {
+                        let client = client.clone();
+                        let neighbors: VertexIterator<'static, Self::Vertex> = match ctx
+                            .active_vertex()
+                        {
+                            None => Box::new(std::iter::empty()),
+                            Some(vertex) => {
+                                let institution = vertex
+                                    .as_institution()
+                                    .expect("vertex was not an institution");
+                                let associated_institution_ids = institution
+                                    .associated_institutions
+                                    .clone()
+                                    .into_iter()
+                                    .map(|institution| institution.id);
 
-                    (ctx, neighbors)
-                })),
-                "Repositories" => Box::new(contexts.map(move |ctx| {
-                    let neighbors: VertexIterator<'static, Self::Vertex> = match ctx.active_vertex()
-                    {
-                        None => Box::new(std::iter::empty()),
-                        Some(vertex) => {
-                            let institution = vertex
-                                .as_institution()
-                                .expect("vertex was not an institution");
-                            let repositories_ids =
-                                institution.repositories.clone().into_iter().map(|source| source.id);
-
-                            let neighbors_iter = repositories_ids.filter_map(move |source_id| {
-                                match fetch_vertex(source_id.clone(), VertexKind::Source) {
-                                    Ok(source_vertex) => Some(source_vertex),
-                                    Err(e) => {
-                                        eprintln!(
-                                            "API error while fetching or deserializing {source_id}: {e}"
-                                        );
-                                        None
-                                    }
-                                }
-                            });
+                                let neighbors_iter =
+                                    associated_institution_ids.filter_map(move |institution_id| {
+                                        client
+                                            .fetch_vertex(
+                                                institution_id.clone(),
+                                                VertexKind::Institution,
+                                            )
+                                            .ok()
+                                    });
 
-                            Box::new(neighbors_iter)
-                        }
-                    };
+                                Box::new(neighbors_iter)
+                            }
+                        };
 
-                    (ctx, neighbors)
-                })),
-                "Publisher" => Box::new(contexts.map(move |ctx| {
-                    let neighbor: VertexIterator<'static, Self::Vertex> = match ctx.active_vertex() {
-                        None => Box::new(std::iter::empty()),
-                        Some(vertex) => {
-                            let institution =
-                                vertex.as_institution().expect("vertex was not institution");
-                            let publisher_id_option =
-                                institution
+                        (ctx, neighbors)
+                    }))
+                }
+                "repositories" => {
+                    let client = self.client.clone();
+                    Box::new(contexts.map(move |ctx| {
+                        let client = client.clone();
+                        let neighbors: VertexIterator<'static, Self::Vertex> =
+                            match ctx.active_vertex() {
+                                None => Box::new(std::iter::empty()),
+                                Some(vertex) => {
+                                    let institution = vertex
+                                        .as_institution()
+                                        .expect("vertex was not an institution");
+                                    let repositories_ids = institution
+                                        .repositories
+                                        .clone()
+                                        .into_iter()
+                                        .map(|source| source.id);
+
+                                    let neighbors_iter =
+                                        repositories_ids.filter_map(move |source_id| {
+                                            client
+                                                .fetch_vertex(source_id.clone(), VertexKind::Source)
+                                                .ok()
+                                        });
+
+                                    Box::new(neighbors_iter)
+                                }
+                            };
+
+                        (ctx, neighbors)
+                    }))
+                }
+                "publisher" => {
+                    let client = self.client.clone();
+                    Box::new(contexts.map(move |ctx| {
+                        let client = client.clone();
+                        let neighbor: VertexIterator<'static, Self::Vertex> = match ctx
+                            .active_vertex()
+                        {
+                            None => Box::new(std::iter::empty()),
+                            Some(vertex) => {
+                                let institution =
+                                    vertex.as_institution().expect("vertex was not institution");
+                                let publisher_id_option = institution
                                     .roles
                                     .clone()
                                     .into_iter()
-                                    .find_map(|role_object| match role_object.role.as_str() {
-                                        "publisher" => Some(role_object.id),
+                                    .find_map(|role_object| match role_object.role.as_deref() {
+                                        Some("publisher") => Some(role_object.id),
                                         _ => {
                                             eprintln!("Institution has no linked publisher");
                                             None
                                         }
                                     });
 
-                            match publisher_id_option {
-                                Some(publisher_id) => {
-                                    match fetch_vertex(publisher_id.clone(), VertexKind::Publisher) {
-                                        Ok(publisher_vertex) => {
-                                            Box::new(std::iter::once(publisher_vertex))
-                                        }
-                                        Err(e) => {
-                                            eprintln!(
-                                                "API error when fetching or deserializing {publisher_id}: {e}"
-                                            );
-                                            Box::new(std::iter::empty())
+                                match publisher_id_option {
+                                    Some(publisher_id) => {
+                                        match client.fetch_vertex(
+                                            publisher_id.clone(),
+                                            VertexKind::Publisher,
+                                        ) {
+                                            Ok(publisher_vertex) => {
+                                                Box::new(std::iter::once(publisher_vertex))
+                                            }
+                                            Err(_) => Box::new(std::iter::empty()),
                                         }
                                     }
+                                    None => Box::new(std::iter::empty()),
                                 }
-                                None => Box::new(std::iter::empty()),
                             }
-                        }
-                    };
+                        };
 
-                    (ctx, neighbor)
-                })),
-                "Funder" => Box::new(contexts.map(move |ctx| {
-                    let neighbor: VertexIterator<'static, Self::Vertex> = match ctx.active_vertex() {
-                        None => Box::new(std::iter::empty()),
-                        Some(vertex) => {
-                            let institution =
-                                vertex.as_institution().expect("vertex was not institution");
-                            let funder_id_option =
-                                institution
-                                    .roles
-                                    .clone()
-                                    .into_iter()
-                                    .find_map(|role_object| match role_object.role.as_str() {
-                                        "funder" => Some(role_object.id),
-                                        _ => {
-                                            eprintln!("Institution has no linked funder");
-                                            None
-                                        }
-                                    });
+                        (ctx, neighbor)
+                    }))
+                }
+                "funder" => {
+                    let client = self.client.clone();
+                    Box::new(contexts.map(move |ctx| {
+                        let client = client.clone();
+                        let neighbor: VertexIterator<'static, Self::Vertex> =
+                            match ctx.active_vertex() {
+                                None => Box::new(std::iter::empty()),
+                                Some(vertex) => {
+                                    let institution = vertex
+                                        .as_institution()
+                                        .expect("vertex was not institution");
+                                    let funder_id_option =
+                                        institution.roles.clone().into_iter().find_map(
+                                            |role_object| match role_object.role.as_deref() {
+                                                Some("funder") => Some(role_object.id),
+                                                _ => {
+                                                    eprintln!("Institution has no linked funder");
+                                                    None
+                                                }
+                                            },
+                                        );
 
-                            match funder_id_option {
-                                Some(funder_id) => {
-                                    match fetch_vertex(funder_id.clone(), VertexKind::Funder) {
-                                        Ok(funder_vertex) => {
-                                            Box::new(std::iter::once(funder_vertex))
-                                        }
-                                        Err(e) => {
-                                            eprintln!(
-                                                "API error when fetching or deserializing {funder_id}: {e}"
-                                            );
-                                            Box::new(std::iter::empty())
+                                    match funder_id_option {
+                                        Some(funder_id) => {
+                                            match client
+                                                .fetch_vertex(funder_id.clone(), VertexKind::Funder)
+                                            {
+                                                Ok(funder_vertex) => {
+                                                    Box::new(std::iter::once(funder_vertex))
+                                                }
+                                                Err(_) => Box::new(std::iter::empty()),
+                                            }
                                         }
+                                        None => Box::new(std::iter::empty()),
                                     }
                                 }
-                                None => Box::new(std::iter::empty()),
-                            }
-                        }
-                    };
-
-                    (ctx, neighbor)
-                })),
-                "Works" => Box::new(contexts.map(move |ctx| {
-                    let neighbors: VertexIterator<'static, Self::Vertex> = match ctx.active_vertex()
-                    {
-                        None => Box::new(std::iter::empty()),
-                        Some(vertex) => {
-                            let institution = vertex
-                                .as_institution()
-                                .expect("vertex was not an institution");
-                            let works_api_url = &institution.works_api_url;
-
-                            match fetch_vertices(works_api_url.clone(), VertexKind::Work) {
-                                Ok(work_vertices) => Box::new(work_vertices.into_iter()),
-                                Err(e) => {
-                                    eprintln!(
-                                        "API error while fetching or deserializing {works_api_url}: {e}"
-                                    );
-                                    Box::new(std::iter::empty())
-                                }
-                            }
-                        }
-                    };
-
-                    (ctx, neighbors)
-                })),
+                            };
+
+                        (ctx, neighbor)
+                    }))
+                }
+                "object_counts_by_year" => resolve_nested(contexts, year_counts, Vertex::YearCount),
+                "roles" => resolve_nested(
+                    contexts,
+                    |vertex| {
+                        let institution = vertex
+                            .as_institution()
+                            .expect("vertex was not an institution");
+                        institution.roles.clone()
+                    },
+                    Vertex::Role,
+                ),
                 _ => unreachable!("resolve_neighbors {type_name} {edge_name}"),
             },
 
             "Publisher" => match edge_name.as_ref() {
-                "Lineage" => Box::new(contexts.map(move |ctx| {
-                    let neighbors: VertexIterator<'static, Self::Vertex> = match ctx.active_vertex()
-                    {
-                        None => Box::new(std::iter::empty()),
-                        Some(vertex) => {
-                            let publisher =
-                                vertex.as_publisher().expect("vertex was not an publisher");
-                            let lineage_ids = publisher.lineage.clone().into_iter();
-
-                            let neighbors_iter = lineage_ids.filter_map(move |publisher_id| {
-                                match fetch_vertex(publisher_id.clone(), VertexKind::Publisher) {
-                                    Ok(publisher_vertex) => Some(publisher_vertex),
-                                    Err(e) => {
-                                        eprintln!(
-                                            "API error while fetching or deserializing {publisher_id}: {e}"
-                                        );
-                                        None
-                                    }
-                                }
-                            });
+                "lineage" => {
+                    let client = self.client.clone();
+                    Box::new(contexts.map(move |ctx| {
+                        let client = client.clone();
+                        let neighbors: VertexIterator<'static, Self::Vertex> = match ctx
+                            .active_vertex()
+                        {
+                            None => Box::new(std::iter::empty()),
+                            Some(vertex) => {
+                                let publisher =
+                                    vertex.as_publisher().expect("vertex was not an publisher");
+                                let lineage_ids = publisher.lineage.clone().into_iter();
+
+                                let neighbors_iter = lineage_ids.filter_map(move |publisher_id| {
+                                    client
+                                        .fetch_vertex(publisher_id.clone(), VertexKind::Publisher)
+                                        .ok()
+                                });
 
-                            Box::new(neighbors_iter)
-                        }
-                    };
+                                Box::new(neighbors_iter)
+                            }
+                        };
 
-                    (ctx, neighbors)
-                })),
-                "Institution" => Box::new(contexts.map(move |ctx| {
-                    let neighbor: VertexIterator<'static, Self::Vertex> = match ctx.active_vertex() {
-                        None => Box::new(std::iter::empty()),
-                        Some(vertex) => {
-                            let publisher =
-                                vertex.as_publisher().expect("vertex was not publisher");
-                            let institution_id_option =
-                                publisher.roles.clone().into_iter().find_map(|role_object| {
-                                    match role_object.role.as_str() {
-                                        "institution" => Some(role_object.id),
-                                        _ => {
-                                            eprintln!("Publisher has no linked institution");
-                                            None
+                        (ctx, neighbors)
+                    }))
+                }
+                "institution" => {
+                    let client = self.client.clone();
+                    Box::new(contexts.map(move |ctx| {
+                        let client = client.clone();
+                        let neighbor: VertexIterator<'static, Self::Vertex> = match ctx
+                            .active_vertex()
+                        {
+                            None => Box::new(std::iter::empty()),
+                            Some(vertex) => {
+                                let publisher =
+                                    vertex.as_publisher().expect("vertex was not publisher");
+                                let institution_id_option =
+                                    publisher.roles.clone().into_iter().find_map(|role_object| {
+                                        match role_object.role.as_deref() {
+                                            Some("institution") => Some(role_object.id),
+                                            _ => {
+                                                eprintln!("Publisher has no linked institution");
+                                                None
+                                            }
                                         }
-                                    }
-                                });
+                                    });
 
-                            match institution_id_option {
-                                Some(institution_id) => {
-                                    match fetch_vertex(institution_id.clone(), VertexKind::Institution) {
-                                        Ok(institution_vertex) => {
-                                            Box::new(std::iter::once(institution_vertex))
-                                        }
-                                        Err(e) => {
-                                            eprintln!(
-                                                "API error when fetching or deserializing {institution_id}: {e}"
-                                            );
-                                            Box::new(std::iter::empty())
+                                match institution_id_option {
+                                    Some(institution_id) => {
+                                        match client.fetch_vertex(
+                                            institution_id.clone(),
+                                            VertexKind::Institution,
+                                        ) {
+                                            Ok(institution_vertex) => {
+                                                Box::new(std::iter::once(institution_vertex))
+                                            }
+                                            Err(_) => Box::new(std::iter::empty()),
                                         }
                                     }
+                                    None => Box::new(std::iter::empty()),
                                 }
-                                None => Box::new(std::iter::empty()),
                             }
-                        }
-                    };
+                        };
 
-                    (ctx, neighbor)
-                })),
-                "Funder" => Box::new(contexts.map(move |ctx| {
-                    let neighbor: VertexIterator<'static, Self::Vertex> = match ctx.active_vertex() {
-                        None => Box::new(std::iter::empty()),
-                        Some(vertex) => {
-                            let publisher =
-                                vertex.as_publisher().expect("vertex was not publisher");
-                            let funder_id_option = publisher.roles.clone().into_iter().find_map(|role_object| {
-                                match role_object.role.as_str() {
-                                    "funder" => Some(role_object.id),
-                                    _ => {
-                                        eprintln!("Publisher has no linked funder");
-                                        None
-                                    }
-                                }
-                            });
+                        (ctx, neighbor)
+                    }))
+                }
+                "funder" => {
+                    let client = self.client.clone();
+                    Box::new(contexts.map(move |ctx| {
+                        let client = client.clone();
+                        let neighbor: VertexIterator<'static, Self::Vertex> =
+                            match ctx.active_vertex() {
+                                None => Box::new(std::iter::empty()),
+                                Some(vertex) => {
+                                    let publisher =
+                                        vertex.as_publisher().expect("vertex was not publisher");
+                                    let funder_id_option =
+                                        publisher.roles.clone().into_iter().find_map(
+                                            |role_object| match role_object.role.as_deref() {
+                                                Some("funder") => Some(role_object.id),
+                                                _ => {
+                                                    eprintln!("Publisher has no linked funder");
+                                                    None
+                                                }
+                                            },
+                                        );
 
-                            match funder_id_option {
-                                Some(funder_id) => {
-                                    match fetch_vertex(funder_id.clone(), VertexKind::Funder) {
-                                        Ok(funder_vertex) => {
-                                            Box::new(std::iter::once(funder_vertex))
+                                    match funder_id_option {
+                                        Some(funder_id) => {
+                                            match client
+                                                .fetch_vertex(funder_id.clone(), VertexKind::Funder)
+                                            {
+                                                Ok(funder_vertex) => {
+                                                    Box::new(std::iter::once(funder_vertex))
+                                                }
+                                                Err(_) => Box::new(std::iter::empty()),
+                                            }
                                         }
-                                        Err(e) => {
-                                            eprintln!(
-                                                "API error when fetching or deserializing {funder_id}: {e}"
-                                            );
-                                            Box::new(std::iter::empty())
+                                        None => Box::new(std::iter::empty()),
+                                    }
+                                }
+                            };
+
+                        (ctx, neighbor)
+                    }))
+                }
+                "object_counts_by_year" => resolve_nested(contexts, year_counts, Vertex::YearCount),
+                "roles" => resolve_nested(
+                    contexts,
+                    |vertex| {
+                        let publisher = vertex.as_publisher().expect("vertex was not a publisher");
+                        publisher.roles.clone()
+                    },
+                    Vertex::Role,
+                ),
+                "parent_publisher" => resolve_neighbors_by_id(
+                    &self.client,
+                    contexts,
+                    VertexKind::Publisher,
+                    |vertex| {
+                        let publisher = vertex.as_publisher().expect("vertex was not a publisher");
+                        publisher
+                            .parent_publisher
+                            .iter()
+                            .map(|parent| parent.id.clone())
+                            .collect()
+                    },
+                ),
+                _ => unreachable!("resolve_neighbors {type_name} {edge_name}"),
+            },
+            "Funder" => match edge_name.as_ref() {
+                "institution" => {
+                    let client = self.client.clone();
+                    Box::new(contexts.map(move |ctx| {
+                        let client = client.clone();
+                        let neighbor: VertexIterator<'static, Self::Vertex> =
+                            match ctx.active_vertex() {
+                                None => Box::new(std::iter::empty()),
+                                Some(vertex) => {
+                                    let funder = vertex.as_funder().expect("vertex was not funder");
+                                    let institution_id_option =
+                                        funder.roles.clone().into_iter().find_map(|role_object| {
+                                            match role_object.role.as_deref() {
+                                                Some("institution") => Some(role_object.id),
+                                                _ => {
+                                                    eprintln!("Publisher has no linked funder");
+                                                    None
+                                                }
+                                            }
+                                        });
+
+                                    match institution_id_option {
+                                        Some(institution_id) => {
+                                            match client.fetch_vertex(
+                                                institution_id.clone(),
+                                                VertexKind::Institution,
+                                            ) {
+                                                Ok(institution_vertex) => {
+                                                    Box::new(std::iter::once(institution_vertex))
+                                                }
+                                                Err(_) => Box::new(std::iter::empty()),
+                                            }
                                         }
+                                        None => Box::new(std::iter::empty()),
                                     }
                                 }
+                            };
+
+                        (ctx, neighbor)
+                    }))
+                }
+                "publisher" => {
+                    let client = self.client.clone();
+                    Box::new(contexts.map(move |ctx| {
+                        let client = client.clone();
+                        let neighbor: VertexIterator<'static, Self::Vertex> =
+                            match ctx.active_vertex() {
                                 None => Box::new(std::iter::empty()),
-                            }
-                        }
-                    };
+                                Some(vertex) => {
+                                    let funder = vertex.as_funder().expect("vertex was not funder");
+                                    let publisher_id_option =
+                                        funder.roles.clone().into_iter().find_map(|role_object| {
+                                            match role_object.role.as_deref() {
+                                                Some("publisher") => Some(role_object.id),
+                                                _ => {
+                                                    eprintln!("Publisher has no linked funder");
+                                                    None
+                                                }
+                                            }
+                                        });
+
+                                    match publisher_id_option {
+                                        Some(publisher_id) => {
+                                            match client.fetch_vertex(
+                                                publisher_id.clone(),
+                                                VertexKind::Publisher,
+                                            ) {
+                                                Ok(publisher_vertex) => {
+                                                    Box::new(std::iter::once(publisher_vertex))
+                                                }
+                                                Err(_) => Box::new(std::iter::empty()),
+                                            }
+                                        }
+                                        None => Box::new(std::iter::empty()),
+                                    }
+                                }
+                            };
+
+                        (ctx, neighbor)
+                    }))
+                }
+                "object_counts_by_year" => resolve_nested(contexts, year_counts, Vertex::YearCount),
+                "roles" => resolve_nested(
+                    contexts,
+                    |vertex| {
+                        let funder = vertex.as_funder().expect("vertex was not a funder");
+                        funder.roles.clone()
+                    },
+                    Vertex::Role,
+                ),
+                _ => unreachable!("resolve_neighbors {type_name} {edge_name}"),
+            },
 
-                    (ctx, neighbor)
-                })),
-                "Sources" => Box::new(contexts.map(move |ctx| {
+            "Topic" => match edge_name.as_ref() {
+                "subfield" => resolve_neighbors_by_id(
+                    &self.client,
+                    contexts,
+                    VertexKind::Subfield,
+                    |vertex| {
+                        let topic = vertex.as_topic().expect("vertex was not a topic");
+                        topic
+                            .subfield
+                            .iter()
+                            .map(|subfield| subfield.id.clone())
+                            .collect()
+                    },
+                ),
+                "field" => {
+                    resolve_neighbors_by_id(&self.client, contexts, VertexKind::Field, |vertex| {
+                        let topic = vertex.as_topic().expect("vertex was not a topic");
+                        topic.field.iter().map(|field| field.id.clone()).collect()
+                    })
+                }
+                "domain" => {
+                    resolve_neighbors_by_id(&self.client, contexts, VertexKind::Domain, |vertex| {
+                        let topic = vertex.as_topic().expect("vertex was not a topic");
+                        topic
+                            .domain
+                            .iter()
+                            .map(|domain| domain.id.clone())
+                            .collect()
+                    })
+                }
+                _ => unreachable!("resolve_neighbors {type_name} {edge_name}"),
+            },
+
+            "Subfield" => match edge_name.as_ref() {
+                "topics" => {
+                    resolve_neighbors_by_id(&self.client, contexts, VertexKind::Topic, |vertex| {
+                        let subfield = vertex.as_subfield().expect("vertex was not a subfield");
+                        subfield
+                            .topics
+                            .iter()
+                            .map(|topic| topic.id.clone())
+                            .collect()
+                    })
+                }
+                "field" => {
+                    resolve_neighbors_by_id(&self.client, contexts, VertexKind::Field, |vertex| {
+                        let subfield = vertex.as_subfield().expect("vertex was not a subfield");
+                        subfield
+                            .field
+                            .iter()
+                            .map(|field| field.id.clone())
+                            .collect()
+                    })
+                }
+                "domain" => {
+                    resolve_neighbors_by_id(&self.client, contexts, VertexKind::Domain, |vertex| {
+                        let subfield = vertex.as_subfield().expect("vertex was not a subfield");
+                        subfield
+                            .domain
+                            .iter()
+                            .map(|domain| domain.id.clone())
+                            .collect()
+                    })
+                }
+                _ => unreachable!("resolve_neighbors {type_name} {edge_name}"),
+            },
+
+            "Field" => match edge_name.as_ref() {
+                "subfields" => resolve_neighbors_by_id(
+                    &self.client,
+                    contexts,
+                    VertexKind::Subfield,
+                    |vertex| {
+                        let field = vertex.as_field().expect("vertex was not a field");
+                        field
+                            .subfields
+                            .iter()
+                            .map(|subfield| subfield.id.clone())
+                            .collect()
+                    },
+                ),
+                "domain" => {
+                    resolve_neighbors_by_id(&self.client, contexts, VertexKind::Domain, |vertex| {
+                        let field = vertex.as_field().expect("vertex was not a field");
+                        field
+                            .domain
+                            .iter()
+                            .map(|domain| domain.id.clone())
+                            .collect()
+                    })
+                }
+                _ => unreachable!("resolve_neighbors {type_name} {edge_name}"),
+            },
+
+            "Domain" => match edge_name.as_ref() {
+                "fields" => {
+                    resolve_neighbors_by_id(&self.client, contexts, VertexKind::Field, |vertex| {
+                        let domain = vertex.as_domain().expect("vertex was not a domain");
+                        domain.fields.iter().map(|field| field.id.clone()).collect()
+                    })
+                }
+                _ => unreachable!("resolve_neighbors {type_name} {edge_name}"),
+            },
+
+            "Affiliation" => match edge_name.as_ref() {
+                "institution" => resolve_neighbors_by_id(
+                    &self.client,
+                    contexts,
+                    VertexKind::Institution,
+                    |vertex| {
+                        let affiliation = vertex
+                            .as_affiliation()
+                            .expect("vertex was not an affiliation");
+                        vec![affiliation.institution.id.clone()]
+                    },
+                ),
+                _ => unreachable!("resolve_neighbors {type_name} {edge_name}"),
+            },
+
+            "Collaboration" => match edge_name.as_ref() {
+                "coauthor" => {
+                    resolve_neighbors_by_id(&self.client, contexts, VertexKind::Author, |vertex| {
+                        let collaboration = vertex
+                            .as_collaboration()
+                            .expect("vertex was not a collaboration");
+                        vec![collaboration.coauthor.id.clone()]
+                    })
+                }
+                _ => unreachable!("resolve_neighbors {type_name} {edge_name}"),
+            },
+
+            "WorkOverlap" => match edge_name.as_ref() {
+                "work" => {
+                    resolve_neighbors_by_id(&self.client, contexts, VertexKind::Work, |vertex| {
+                        let overlap = vertex
+                            .as_work_overlap()
+                            .expect("vertex was not a work overlap");
+                        vec![overlap.work_id.clone()]
+                    })
+                }
+                _ => unreachable!("resolve_neighbors {type_name} {edge_name}"),
+            },
+
+            "Citation" => match edge_name.as_ref() {
+                "cited_work" => Box::new(contexts.map(move |ctx| {
                     let neighbors: VertexIterator<'static, Self::Vertex> = match ctx.active_vertex()
                     {
                         None => Box::new(std::iter::empty()),
                         Some(vertex) => {
-                            let publisher =
-                                vertex.as_publisher().expect("vertex was not an publisher");
-                            let sources_api_url = &publisher.sources_api_url;
-
-                            match fetch_vertices(sources_api_url.clone(), VertexKind::Source) {
-                                Ok(sources_vertices) => Box::new(sources_vertices.into_iter()),
-                                Err(e) => {
-                                    eprintln!(
-                                        "API error while fetching or deserializing {sources_api_url}: {e}"
-                                    );
-                                    Box::new(std::iter::empty())
-                                }
-                            }
+                            let citation = vertex.as_citation().expect("vertex was not a citation");
+                            Box::new(std::iter::once(Vertex::Work((*citation.cited).clone())))
                         }
                     };
 
                     (ctx, neighbors)
                 })),
-                _ => unreachable!("resolve_neighbors {type_name} {edge_name}"),
-            },
-            "Funder" => match edge_name.as_ref() {
-                "Institution" => Box::new(contexts.map(move |ctx| {
-                    let neighbor: VertexIterator<'static, Self::Vertex> = match ctx.active_vertex() {
+                "citing_work" => Box::new(contexts.map(move |ctx| {
+                    let neighbors: VertexIterator<'static, Self::Vertex> = match ctx.active_vertex()
+                    {
                         None => Box::new(std::iter::empty()),
                         Some(vertex) => {
-                            let funder = vertex.as_funder().expect("vertex was not funder");
-                            let institution_id_option =
-                                funder.roles.clone().into_iter().find_map(|role_object| {
-                                    match role_object.role.as_str() {
-                                        "institution" => Some(role_object.id),
-                                        _ => {
-                                            eprintln!("Publisher has no linked funder");
-                                            None
-                                        }
-                                    }
-                                });
-
-                            match institution_id_option {
-                                Some(institution_id) => {
-                                    match fetch_vertex(institution_id.clone(), VertexKind::Institution) {
-                                        Ok(institution_vertex) => {
-                                            Box::new(std::iter::once(institution_vertex))
-                                        }
-                                        Err(e) => {
-                                            eprintln!(
-                        "API error when fetching or deserializing {institution_id}: {e}"
-                      );
-                                            Box::new(std::iter::empty())
-                                        }
-                                    }
-                                }
-                                None => Box::new(std::iter::empty()),
-                            }
+                            let citation = vertex.as_citation().expect("vertex was not a citation");
+                            Box::new(std::iter::once(Vertex::Work(*citation.citing.clone())))
                         }
                     };
 
-                    (ctx, neighbor)
+                    (ctx, neighbors)
                 })),
-                "Publisher" => Box::new(contexts.map(move |ctx| {
-                    let neighbor: VertexIterator<'static, Self::Vertex> = match ctx.active_vertex() {
+                _ => unreachable!("resolve_neighbors {type_name} {edge_name}"),
+            },
+
+            "GroupBucket" | "AutocompleteResult" => {
+                let client = self.client.clone();
+                let kind = match edge_name.as_ref() {
+                    "work" => VertexKind::Work,
+                    "author" => VertexKind::Author,
+                    "source" => VertexKind::Source,
+                    "institution" => VertexKind::Institution,
+                    "concept" => VertexKind::Concept,
+                    "publisher" => VertexKind::Publisher,
+                    "funder" => VertexKind::Funder,
+                    "topic" => VertexKind::Topic,
+                    "subfield" => VertexKind::Subfield,
+                    "field" => VertexKind::Field,
+                    "domain" => VertexKind::Domain,
+                    _ => unreachable!("resolve_neighbors {type_name} {edge_name}"),
+                };
+
+                // Only buckets keyed by (or results naming) an entity of the edge's kind
+                // have a neighbor.
+                Box::new(contexts.map(move |ctx| {
+                    let client = client.clone();
+                    let neighbors: VertexIterator<'static, Self::Vertex> = match ctx.active_vertex()
+                    {
                         None => Box::new(std::iter::empty()),
                         Some(vertex) => {
-                            let funder = vertex.as_funder().expect("vertex was not funder");
-                            let publisher_id_option = funder.roles.clone().into_iter().find_map(|role_object| {
-                                match role_object.role.as_str() {
-                                    "publisher" => Some(role_object.id),
-                                    _ => {
-                                        eprintln!("Publisher has no linked funder");
-                                        None
-                                    }
-                                }
-                            });
-
-                            match publisher_id_option {
-                                Some(publisher_id) => {
-                                    match fetch_vertex(publisher_id.clone(), VertexKind::Publisher) {
-                                        Ok(publisher_vertex) => {
-                                            Box::new(std::iter::once(publisher_vertex))
-                                        }
-                                        Err(e) => {
-                                            eprintln!(
-                                                "API error when fetching or deserializing {publisher_id}: {e}"
-                                            );
-                                            Box::new(std::iter::empty())
-                                        }
-                                    }
+                            let entity_id = match vertex {
+                                Vertex::GroupBucket(bucket) => bucket.key.clone(),
+                                Vertex::AutocompleteResult(result) => result.id.clone(),
+                                _ => unreachable!(
+                                    "vertex was not a group bucket or autocomplete result"
+                                ),
+                            };
+                            match VertexKind::from_id(&entity_id) {
+                                Some(entity_kind) if entity_kind == kind => {
+                                    fetch_neighbors_by_id(&client, vec![entity_id], kind)
                                 }
-                                None => Box::new(std::iter::empty()),
+                                _ => Box::new(std::iter::empty()),
                             }
                         }
                     };
 
-                    (ctx, neighbor)
-                })),
-                _ => unreachable!("resolve_neighbors {type_name} {edge_name}"),
-            },
+                    (ctx, neighbors)
+                }))
+            }
 
             _ => unreachable!("{type_name} doesn't fall under a vertex type with edge connections"),
         }
@@ -1380,7 +2969,7 @@ impl Adapter<'static> for OpenAlexAdapter {
                             .roles
                             .clone()
                             .into_iter()
-                            .map(|role_object| role_object.role)
+                            .filter_map(|role_object| role_object.role)
                             .collect();
                         institution_roles.contains(&String::from("funder"))
                     }
@@ -1395,7 +2984,7 @@ impl Adapter<'static> for OpenAlexAdapter {
                             .roles
                             .clone()
                             .into_iter()
-                            .map(|role_object| role_object.role)
+                            .filter_map(|role_object| role_object.role)
                             .collect();
                         institution_roles.contains(&String::from("publisher"))
                     }
@@ -1410,7 +2999,7 @@ impl Adapter<'static> for OpenAlexAdapter {
                             .roles
                             .clone()
                             .into_iter()
-                            .map(|role_object| role_object.role)
+                            .filter_map(|role_object| role_object.role)
                             .collect();
                         funder_roles.contains(&String::from("institution"))
                     }
@@ -1425,7 +3014,7 @@ impl Adapter<'static> for OpenAlexAdapter {
                             .roles
                             .clone()
                             .into_iter()
-                            .map(|role_object| role_object.role)
+                            .filter_map(|role_object| role_object.role)
                             .collect();
                         funder_roles.contains(&String::from("publisher"))
                     }
@@ -1440,7 +3029,7 @@ impl Adapter<'static> for OpenAlexAdapter {
                             .roles
                             .clone()
                             .into_iter()
-                            .map(|role_object| role_object.role)
+                            .filter_map(|role_object| role_object.role)
                             .collect();
                         publisher_roles.contains(&String::from("institution"))
                     }
@@ -1455,7 +3044,7 @@ impl Adapter<'static> for OpenAlexAdapter {
                             .roles
                             .clone()
                             .into_iter()
-                            .map(|role_object| role_object.role)
+                            .filter_map(|role_object| role_object.role)
                             .collect();
                         publisher_roles.contains(&String::from("funder"))
                     }
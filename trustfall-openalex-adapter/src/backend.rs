@@ -0,0 +1,352 @@
+use std::{
+    fmt,
+    path::PathBuf,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use lazy_static::lazy_static;
+use reqwest::{
+    blocking::Client,
+    header::{ACCEPT, HOST, USER_AGENT},
+    Url,
+};
+
+use crate::budget::RequestBudget;
+use crate::fetch::FetchError;
+use crate::vertex::VertexKind;
+
+lazy_static! {
+    static ref OPEN_ALEX_CLIENT: Client = Client::new();
+}
+
+/// Where OpenAlex responses come from: the API, or something standing in for it.
+///
+/// Every operation returns the JSON body the OpenAlex API would have sent for it, so backends
+/// can be layered: a cassette or mirror answers what it can and passes the rest on to the
+/// backend it wraps. Deserializing and paging are left to the adapter.
+pub trait Backend: fmt::Debug + Send + Sync {
+    /// A single entity, by OpenAlex ID, `https://openalex.org/` URL, external ID such as
+    /// `doi:10.1234/abc`, or `random`.
+    fn get(&self, kind: VertexKind, id: &str) -> Result<String, FetchError>;
+
+    /// Several entities by ID, in the order they were asked for.
+    fn get_many(&self, kind: VertexKind, ids: &[String]) -> Result<Vec<String>, FetchError> {
+        ids.iter().map(|id| self.get(kind, id)).collect()
+    }
+
+    /// One page of a list endpoint.
+    fn list(&self, kind: VertexKind, query: &ListQuery) -> Result<String, FetchError>;
+
+    /// The buckets of a list endpoint grouped by `key`, e.g. `publication_year`.
+    fn group_by(
+        &self,
+        kind: VertexKind,
+        query: &ListQuery,
+        key: &str,
+    ) -> Result<String, FetchError>;
+
+    /// Entities whose names start with `q`, across every entity type unless `entity` names
+    /// an endpoint such as `institutions`.
+    fn autocomplete(&self, entity: Option<&str>, q: &str) -> Result<String, FetchError>;
+
+    /// The number of requests sent to the OpenAlex API so far.
+    fn requests(&self) -> u64 {
+        0
+    }
+}
+
+impl<B: Backend + ?Sized> Backend for Box<B> {
+    fn get(&self, kind: VertexKind, id: &str) -> Result<String, FetchError> {
+        (**self).get(kind, id)
+    }
+
+    fn get_many(&self, kind: VertexKind, ids: &[String]) -> Result<Vec<String>, FetchError> {
+        (**self).get_many(kind, ids)
+    }
+
+    fn list(&self, kind: VertexKind, query: &ListQuery) -> Result<String, FetchError> {
+        (**self).list(kind, query)
+    }
+
+    fn group_by(
+        &self,
+        kind: VertexKind,
+        query: &ListQuery,
+        key: &str,
+    ) -> Result<String, FetchError> {
+        (**self).group_by(kind, query, key)
+    }
+
+    fn autocomplete(&self, entity: Option<&str>, q: &str) -> Result<String, FetchError> {
+        (**self).autocomplete(entity, q)
+    }
+
+    fn requests(&self) -> u64 {
+        (**self).requests()
+    }
+}
+
+impl<B: Backend + ?Sized> Backend for Arc<B> {
+    fn get(&self, kind: VertexKind, id: &str) -> Result<String, FetchError> {
+        (**self).get(kind, id)
+    }
+
+    fn get_many(&self, kind: VertexKind, ids: &[String]) -> Result<Vec<String>, FetchError> {
+        (**self).get_many(kind, ids)
+    }
+
+    fn list(&self, kind: VertexKind, query: &ListQuery) -> Result<String, FetchError> {
+        (**self).list(kind, query)
+    }
+
+    fn group_by(
+        &self,
+        kind: VertexKind,
+        query: &ListQuery,
+        key: &str,
+    ) -> Result<String, FetchError> {
+        (**self).group_by(kind, query, key)
+    }
+
+    fn autocomplete(&self, entity: Option<&str>, q: &str) -> Result<String, FetchError> {
+        (**self).autocomplete(entity, q)
+    }
+
+    fn requests(&self) -> u64 {
+        (**self).requests()
+    }
+}
+
+/// The parameters of a list request, e.g. `/works?filter=cites:W2741809807&sort=publication_date`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ListQuery {
+    pub filter: Option<String>,
+    pub search: Option<String>,
+    pub sort: Option<String>,
+    pub sample: Option<u64>,
+    /// Every page of a sample must use the same seed, or pages overlap. Without one,
+    /// each page is drawn on its own.
+    pub seed: Option<u64>,
+    pub per_page: Option<u32>,
+    pub page: Option<Page>,
+}
+
+/// Which page of a list to fetch. OpenAlex requires numbered pages for `sample` requests.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Page {
+    Cursor(String),
+    Number(u32),
+}
+
+impl ListQuery {
+    pub fn filtered(filter: impl Into<String>) -> Self {
+        ListQuery {
+            filter: Some(filter.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Orders the results by `sort`, if given, e.g. `cited_by_count:desc`.
+    pub fn with_sort(mut self, sort: Option<String>) -> Self {
+        if sort.is_some() {
+            self.sort = sort;
+        }
+        self
+    }
+
+    /// Reads a list URL handed out by OpenAlex, such as an author's `works_api_url`.
+    pub fn from_url(url: &str) -> Option<Self> {
+        let mut query = ListQuery::default();
+        for (name, value) in Url::parse(url).ok()?.query_pairs() {
+            match name.as_ref() {
+                "filter" => query.filter = Some(value.into_owned()),
+                "search" => query.search = Some(value.into_owned()),
+                "sort" => query.sort = Some(value.into_owned()),
+                "sample" => query.sample = value.parse().ok(),
+                "seed" => query.seed = value.parse().ok(),
+                "per-page" | "per_page" => query.per_page = value.parse().ok(),
+                "cursor" => query.page = Some(Page::Cursor(value.into_owned())),
+                "page" => query.page = value.parse().ok().map(Page::Number),
+                _ => {}
+            }
+        }
+        Some(query)
+    }
+
+    /// The API URL of this page of `kind`'s list endpoint.
+    pub fn url(&self, kind: VertexKind) -> String {
+        self.url_with(kind, None)
+    }
+
+    /// The API URL grouping `kind`'s list endpoint by `key`.
+    pub fn group_url(&self, kind: VertexKind, key: &str) -> String {
+        self.url_with(kind, Some(key))
+    }
+
+    fn url_with(&self, kind: VertexKind, group_by: Option<&str>) -> String {
+        let mut params = vec![];
+        for (name, value) in [
+            ("filter", &self.filter),
+            ("search", &self.search),
+            ("sort", &self.sort),
+        ] {
+            if let Some(value) = value {
+                params.push((name, value.clone()));
+            }
+        }
+        if let Some(sample) = self.sample {
+            params.push(("sample", sample.to_string()));
+        }
+        if let Some(seed) = self.seed {
+            params.push(("seed", seed.to_string()));
+        }
+        if let Some(group_by) = group_by {
+            params.push(("group_by", group_by.to_string()));
+        }
+        if let Some(per_page) = self.per_page {
+            params.push(("per-page", per_page.to_string()));
+        }
+        match &self.page {
+            Some(Page::Cursor(cursor)) => params.push(("cursor", cursor.clone())),
+            Some(Page::Number(page)) => params.push(("page", page.to_string())),
+            None => {}
+        }
+
+        let url = format!("https://api.openalex.org/{}", kind.endpoint());
+        if params.is_empty() {
+            return url;
+        }
+        Url::parse_with_params(&url, &params)
+            .expect("list URL was not valid")
+            .to_string()
+    }
+}
+
+/// The API URL of a single entity. `https://openalex.org/` IDs are already URLs the API
+/// answers, by redirecting to the entity.
+pub(crate) fn entity_url(kind: VertexKind, id: &str) -> String {
+    if id.starts_with("https://") {
+        id.to_string()
+    } else {
+        format!("https://api.openalex.org/{}/{id}", kind.endpoint())
+    }
+}
+
+pub(crate) fn autocomplete_url(entity: Option<&str>, q: &str) -> String {
+    let endpoint = match entity {
+        Some(entity) => format!("https://api.openalex.org/autocomplete/{entity}"),
+        None => "https://api.openalex.org/autocomplete".to_string(),
+    };
+
+    Url::parse_with_params(&endpoint, &[("q", q)])
+        .expect("autocomplete URL was not valid")
+        .to_string()
+}
+
+/// Sends every request to the OpenAlex API, charging each one to a shared request budget.
+#[derive(Clone, Debug, Default)]
+pub struct HttpBackend {
+    budget: Arc<Mutex<RequestBudget>>,
+    base_url: Option<String>,
+}
+
+impl HttpBackend {
+    /// Sends requests to `base_url`, e.g. `http://127.0.0.1:8080`, instead of
+    /// `https://api.openalex.org`: a proxy, or a local server standing in for the API.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into().trim_end_matches('/').to_string());
+        self
+    }
+
+    /// Stops sending requests once `max_requests` have been sent through this backend.
+    pub fn with_max_requests(self, max_requests: u64) -> Self {
+        self.budget().max_requests = Some(max_requests);
+        self
+    }
+
+    /// Stops sending requests once `max_daily_requests` have been sent today (UTC), counting
+    /// earlier runs too: the day's count is kept in the file at `usage_path`.
+    pub fn with_max_daily_requests(
+        self,
+        max_daily_requests: u64,
+        usage_path: impl Into<PathBuf>,
+    ) -> Self {
+        {
+            let mut budget = self.budget();
+            budget.max_daily_requests = Some(max_daily_requests);
+            budget.usage_path = Some(usage_path.into());
+        }
+        self
+    }
+
+    /// Counts the requests sent each day (UTC) in the file at `usage_path`, across runs,
+    /// without capping them.
+    pub fn with_usage_path(self, usage_path: impl Into<PathBuf>) -> Self {
+        self.budget().usage_path = Some(usage_path.into());
+        self
+    }
+
+    pub fn budget(&self) -> MutexGuard<'_, RequestBudget> {
+        self.budget
+            .lock()
+            .expect("request budget lock was poisoned")
+    }
+
+    /// `url` moved onto the base URL, if one was given. Entity URLs such as
+    /// `https://openalex.org/W2741809807` move too, as the API answers them at `/W2741809807`.
+    fn rebase(&self, url: &str) -> String {
+        let Some(base_url) = &self.base_url else {
+            return url.to_string();
+        };
+
+        match ["https://api.openalex.org", "https://openalex.org"]
+            .into_iter()
+            .find_map(|origin| url.strip_prefix(origin))
+        {
+            Some(path) => format!("{base_url}{path}"),
+            None => url.to_string(),
+        }
+    }
+
+    fn fetch(&self, url: &str) -> Result<String, FetchError> {
+        self.budget().spend()?;
+
+        let mut request = OPEN_ALEX_CLIENT
+            .get(self.rebase(url))
+            .header(USER_AGENT, "curl/7.81.0")
+            .header(ACCEPT, "*/*");
+        if self.base_url.is_none() {
+            request = request.header(HOST, "api.openalex.org");
+        }
+
+        // Rate limiting (429) and server errors come with bodies that aren't results.
+        Ok(request.send()?.error_for_status()?.text()?)
+    }
+}
+
+impl Backend for HttpBackend {
+    fn get(&self, kind: VertexKind, id: &str) -> Result<String, FetchError> {
+        self.fetch(&entity_url(kind, id))
+    }
+
+    fn list(&self, kind: VertexKind, query: &ListQuery) -> Result<String, FetchError> {
+        self.fetch(&query.url(kind))
+    }
+
+    fn group_by(
+        &self,
+        kind: VertexKind,
+        query: &ListQuery,
+        key: &str,
+    ) -> Result<String, FetchError> {
+        self.fetch(&query.group_url(kind, key))
+    }
+
+    fn autocomplete(&self, entity: Option<&str>, q: &str) -> Result<String, FetchError> {
+        self.fetch(&autocomplete_url(entity, q))
+    }
+
+    fn requests(&self) -> u64 {
+        self.budget().requests()
+    }
+}
@@ -0,0 +1,141 @@
+use std::{fmt, fs, path::PathBuf};
+
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Caps on the number of HTTP requests sent to OpenAlex.
+#[derive(Clone, Debug, Default)]
+pub struct RequestBudget {
+    pub max_requests: Option<u64>,
+    pub max_daily_requests: Option<u64>,
+    /// Where the day's request count is kept, so the daily cap holds across runs. Requests
+    /// are counted there whenever it's set, with or without a daily cap; with a daily cap
+    /// and no path, they're counted in `openalex_usage.json`.
+    pub usage_path: Option<PathBuf>,
+    requests: u64,
+}
+
+/// The requests sent on one UTC day, the granularity of OpenAlex's daily quota.
+#[derive(Debug, Deserialize, Serialize)]
+struct DailyUsage {
+    date: NaiveDate,
+    requests: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BudgetError {
+    MaxRequests {
+        limit: u64,
+    },
+    MaxDailyRequests {
+        limit: u64,
+        date: NaiveDate,
+    },
+    /// The usage file couldn't be read or written, so the daily cap can't be enforced.
+    Usage {
+        path: PathBuf,
+        message: String,
+    },
+}
+
+impl fmt::Display for BudgetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BudgetError::MaxRequests { limit } => {
+                write!(f, "request budget exhausted: max_requests is {limit}")
+            }
+            BudgetError::MaxDailyRequests { limit, date } => write!(
+                f,
+                "daily request budget exhausted: {limit} requests already sent on {date}"
+            ),
+            BudgetError::Usage { path, message } => write!(
+                f,
+                "could not track request usage in {}: {message}",
+                path.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BudgetError {}
+
+impl RequestBudget {
+    /// The number of requests this budget has allowed so far.
+    pub fn requests(&self) -> u64 {
+        self.requests
+    }
+
+    /// The number of requests sent today (UTC) according to the usage file, counting
+    /// earlier runs.
+    pub fn daily_requests(&self) -> Result<u64, BudgetError> {
+        Ok(self.read_usage()?.requests)
+    }
+
+    /// Accounts for one more request, or refuses it if that would exceed a cap.
+    pub fn spend(&mut self) -> Result<(), BudgetError> {
+        if let Some(limit) = self.max_requests {
+            if self.requests >= limit {
+                return Err(BudgetError::MaxRequests { limit });
+            }
+        }
+
+        if self.max_daily_requests.is_some() || self.usage_path.is_some() {
+            let mut usage = self.read_usage()?;
+            if let Some(limit) = self.max_daily_requests {
+                if usage.requests >= limit {
+                    return Err(BudgetError::MaxDailyRequests {
+                        limit,
+                        date: usage.date,
+                    });
+                }
+            }
+            usage.requests += 1;
+            self.write_usage(&usage)?;
+        }
+
+        self.requests += 1;
+        Ok(())
+    }
+
+    fn usage_path(&self) -> PathBuf {
+        self.usage_path
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("openalex_usage.json"))
+    }
+
+    fn usage_error(&self, message: impl ToString) -> BudgetError {
+        BudgetError::Usage {
+            path: self.usage_path(),
+            message: message.to_string(),
+        }
+    }
+
+    fn read_usage(&self) -> Result<DailyUsage, BudgetError> {
+        let today = Utc::now().date_naive();
+        let usage = match fs::read_to_string(self.usage_path()) {
+            Ok(content) => {
+                serde_json::from_str::<DailyUsage>(&content).map_err(|e| self.usage_error(e))?
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => DailyUsage {
+                date: today,
+                requests: 0,
+            },
+            Err(e) => return Err(self.usage_error(e)),
+        };
+
+        // A new day starts a new quota.
+        if usage.date == today {
+            Ok(usage)
+        } else {
+            Ok(DailyUsage {
+                date: today,
+                requests: 0,
+            })
+        }
+    }
+
+    fn write_usage(&self, usage: &DailyUsage) -> Result<(), BudgetError> {
+        let content = serde_json::to_string(usage).map_err(|e| self.usage_error(e))?;
+        fs::write(self.usage_path(), content).map_err(|e| self.usage_error(e))
+    }
+}
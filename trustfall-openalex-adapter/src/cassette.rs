@@ -0,0 +1,229 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    backend::{autocomplete_url, entity_url, Backend, ListQuery},
+    fetch::FetchError,
+    vertex::VertexKind,
+};
+
+/// One request and the response body OpenAlex sent back, a line of a cassette file.
+#[derive(Debug, Deserialize, Serialize)]
+struct Interaction {
+    url: String,
+    body: String,
+}
+
+/// A file of recorded OpenAlex responses, either being written by a live run
+/// or being served back in place of the API.
+#[derive(Debug)]
+pub enum Cassette {
+    Record {
+        path: PathBuf,
+        file: Mutex<BufWriter<File>>,
+    },
+    Replay {
+        path: PathBuf,
+        /// Responses in recorded order, per URL. The last one keeps being served once
+        /// the rest are used up, so rerunning a query replays the same data.
+        responses: Mutex<HashMap<String, VecDeque<String>>>,
+    },
+}
+
+#[derive(Debug)]
+pub enum CassetteError {
+    /// Replay mode was asked for a URL the cassette has no response for.
+    Unrecorded {
+        path: PathBuf,
+        url: String,
+    },
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+}
+
+impl fmt::Display for CassetteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CassetteError::Unrecorded { path, url } => write!(
+                f,
+                "cassette {} has no recorded response for {url}",
+                path.display()
+            ),
+            CassetteError::Io { path, error } => {
+                write!(f, "could not use cassette {}: {error}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for CassetteError {}
+
+impl Cassette {
+    /// Starts a new cassette at `path`, replacing any cassette already there.
+    pub fn record(path: impl Into<PathBuf>) -> Result<Self, CassetteError> {
+        let path = path.into();
+        let file = File::create(&path).map_err(|error| CassetteError::Io {
+            path: path.clone(),
+            error,
+        })?;
+
+        Ok(Cassette::Record {
+            path,
+            file: Mutex::new(BufWriter::new(file)),
+        })
+    }
+
+    /// Loads a cassette written by [`Cassette::record`] to serve its responses back.
+    pub fn replay(path: impl Into<PathBuf>) -> Result<Self, CassetteError> {
+        let path = path.into();
+        let io_error = |error| CassetteError::Io {
+            path: path.clone(),
+            error,
+        };
+
+        let mut responses: HashMap<String, VecDeque<String>> = HashMap::new();
+        for line in BufReader::new(File::open(&path).map_err(io_error)?).lines() {
+            let line = line.map_err(io_error)?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let interaction: Interaction =
+                serde_json::from_str(&line).map_err(|e| io_error(e.into()))?;
+            responses
+                .entry(interaction.url)
+                .or_default()
+                .push_back(interaction.body);
+        }
+
+        Ok(Cassette::Replay {
+            path,
+            responses: Mutex::new(responses),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        match self {
+            Cassette::Record { path, .. } => path,
+            Cassette::Replay { path, .. } => path,
+        }
+    }
+
+    /// The recorded response for `url`, if this cassette is being replayed.
+    pub(crate) fn replayed(&self, url: &str) -> Option<Result<String, CassetteError>> {
+        match self {
+            Cassette::Record { .. } => None,
+            Cassette::Replay { path, responses } => {
+                let mut responses = responses.lock().expect("cassette lock was poisoned");
+                let body = match responses.get_mut(url) {
+                    Some(bodies) if bodies.len() > 1 => bodies.pop_front(),
+                    Some(bodies) => bodies.front().cloned(),
+                    None => None,
+                };
+
+                Some(body.ok_or_else(|| CassetteError::Unrecorded {
+                    path: path.clone(),
+                    url: url.to_string(),
+                }))
+            }
+        }
+    }
+
+    /// Saves a live response, if this cassette is being recorded. Each response is written out
+    /// as soon as it arrives, so the cassette holds the response that made a query fail.
+    pub(crate) fn record_response(&self, url: &str, body: &str) -> Result<(), CassetteError> {
+        match self {
+            Cassette::Replay { .. } => Ok(()),
+            Cassette::Record { path, file } => {
+                let interaction = Interaction {
+                    url: url.to_string(),
+                    body: body.to_string(),
+                };
+                let line = serde_json::to_string(&interaction).map_err(|e| CassetteError::Io {
+                    path: path.clone(),
+                    error: e.into(),
+                })?;
+
+                let mut file = file.lock().expect("cassette lock was poisoned");
+                writeln!(file, "{line}")
+                    .and_then(|_| file.flush())
+                    .map_err(|error| CassetteError::Io {
+                        path: path.clone(),
+                        error,
+                    })
+            }
+        }
+    }
+}
+
+/// A backend that records the responses of the backend it wraps to a cassette, or, with a
+/// cassette being replayed, serves them back without asking the wrapped backend at all.
+#[derive(Debug)]
+pub struct CassetteBackend<B> {
+    cassette: Cassette,
+    inner: B,
+}
+
+impl<B: Backend> CassetteBackend<B> {
+    pub fn new(cassette: Cassette, inner: B) -> Self {
+        CassetteBackend { cassette, inner }
+    }
+
+    pub fn cassette(&self) -> &Cassette {
+        &self.cassette
+    }
+
+    fn respond(
+        &self,
+        url: String,
+        inner: impl FnOnce() -> Result<String, FetchError>,
+    ) -> Result<String, FetchError> {
+        if let Some(replayed) = self.cassette.replayed(&url) {
+            return Ok(replayed?);
+        }
+
+        let body = inner()?;
+        self.cassette.record_response(&url, &body)?;
+        Ok(body)
+    }
+}
+
+impl<B: Backend> Backend for CassetteBackend<B> {
+    fn get(&self, kind: VertexKind, id: &str) -> Result<String, FetchError> {
+        self.respond(entity_url(kind, id), || self.inner.get(kind, id))
+    }
+
+    fn list(&self, kind: VertexKind, query: &ListQuery) -> Result<String, FetchError> {
+        self.respond(query.url(kind), || self.inner.list(kind, query))
+    }
+
+    fn group_by(
+        &self,
+        kind: VertexKind,
+        query: &ListQuery,
+        key: &str,
+    ) -> Result<String, FetchError> {
+        self.respond(query.group_url(kind, key), || {
+            self.inner.group_by(kind, query, key)
+        })
+    }
+
+    fn autocomplete(&self, entity: Option<&str>, q: &str) -> Result<String, FetchError> {
+        self.respond(autocomplete_url(entity, q), || {
+            self.inner.autocomplete(entity, q)
+        })
+    }
+
+    fn requests(&self) -> u64 {
+        self.inner.requests()
+    }
+}
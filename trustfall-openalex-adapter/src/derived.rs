@@ -0,0 +1,158 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use crate::vertex::{Citation, Collaboration, Vertex, Work, WorkOverlap};
+
+/// Tallies every coauthor across an author's works, most frequent collaborators first. A
+/// coauthor listed more than once on a work, e.g. once per affiliation, counts once.
+pub fn collaborations(author_id: &str, works: impl Iterator<Item = Vertex>) -> Vec<Collaboration> {
+    let mut collaborations: HashMap<String, Collaboration> = HashMap::new();
+
+    for vertex in works {
+        let work = vertex.as_work().expect("vertex was not a work");
+        let year = work.publication_year;
+
+        let mut coauthors = HashSet::new();
+        for authorship in &work.authorships {
+            if authorship.author.id == author_id || !coauthors.insert(&authorship.author.id) {
+                continue;
+            }
+
+            let collaboration = collaborations
+                .entry(authorship.author.id.clone())
+                .or_insert_with(|| Collaboration {
+                    coauthor: authorship.author.clone(),
+                    shared_works_count: 0,
+                    first_year: None,
+                    last_year: None,
+                });
+            collaboration.shared_works_count += 1;
+            if let Some(year) = year {
+                collaboration.first_year = Some(
+                    collaboration
+                        .first_year
+                        .map_or(year, |first| first.min(year)),
+                );
+                collaboration.last_year =
+                    Some(collaboration.last_year.map_or(year, |last| last.max(year)));
+            }
+        }
+    }
+
+    let mut collaborations: Vec<_> = collaborations.into_values().collect();
+    collaborations.sort_by(|a, b| {
+        b.shared_works_count
+            .cmp(&a.shared_works_count)
+            .then_with(|| a.coauthor.id.cmp(&b.coauthor.id))
+    });
+    collaborations
+}
+
+/// Works that share references with the given work, weighted by how many references they share.
+/// `citing_works` are works citing at least one of `references`, possibly with duplicates.
+pub fn bibliographic_coupling(
+    work_id: &str,
+    references: &[String],
+    citing_works: impl Iterator<Item = Vertex>,
+) -> Vec<WorkOverlap> {
+    let references: HashSet<&str> = references.iter().map(String::as_str).collect();
+    let mut overlaps: HashMap<String, u32> = HashMap::new();
+
+    for vertex in citing_works {
+        let work = vertex.as_work().expect("vertex was not a work");
+        if work.object.id == work_id || overlaps.contains_key(&work.object.id) {
+            continue;
+        }
+
+        let shared = work
+            .referenced_works
+            .iter()
+            .filter(|reference| references.contains(reference.as_str()))
+            .count() as u32;
+        if shared > 0 {
+            overlaps.insert(work.object.id.clone(), shared);
+        }
+    }
+
+    into_sorted_overlaps(overlaps)
+}
+
+/// Works cited alongside the given work, weighted by how many works cite both.
+pub fn co_citation(work_id: &str, citing_works: impl Iterator<Item = Vertex>) -> Vec<WorkOverlap> {
+    let mut overlaps: HashMap<String, u32> = HashMap::new();
+    let mut seen: HashSet<String> = HashSet::new();
+
+    for vertex in citing_works {
+        let work = vertex.as_work().expect("vertex was not a work");
+        if !seen.insert(work.object.id.clone()) {
+            continue;
+        }
+
+        for reference in &work.referenced_works {
+            if reference != work_id {
+                *overlaps.entry(reference.clone()).or_default() += 1;
+            }
+        }
+    }
+
+    into_sorted_overlaps(overlaps)
+}
+
+fn into_sorted_overlaps(overlaps: HashMap<String, u32>) -> Vec<WorkOverlap> {
+    let mut overlaps: Vec<_> = overlaps
+        .into_iter()
+        .map(|(work_id, strength)| WorkOverlap { work_id, strength })
+        .collect();
+    overlaps.sort_by(|a, b| {
+        b.strength
+            .cmp(&a.strength)
+            .then_with(|| a.work_id.cmp(&b.work_id))
+    });
+    overlaps
+}
+
+/// Links a cited work to one of its citing works, flagging whether the citation comes from
+/// a shared author, a shared institution or the same source.
+pub fn citation(cited: &Arc<Work>, citing: Work) -> Citation {
+    let cited_authors: HashSet<&str> = cited
+        .authorships
+        .iter()
+        .map(|authorship| authorship.author.id.as_str())
+        .collect();
+    let cited_institutions: HashSet<&str> = cited
+        .authorships
+        .iter()
+        .flat_map(|authorship| &authorship.institutions)
+        .map(|institution| institution.id.as_str())
+        .collect();
+    let cited_source = primary_source_id(cited);
+
+    let is_author_self_citation = citing
+        .authorships
+        .iter()
+        .any(|authorship| cited_authors.contains(authorship.author.id.as_str()));
+    let is_institution_self_citation = citing
+        .authorships
+        .iter()
+        .flat_map(|authorship| &authorship.institutions)
+        .any(|institution| cited_institutions.contains(institution.id.as_str()));
+    let is_source_self_citation =
+        cited_source.is_some() && cited_source == primary_source_id(&citing);
+
+    Citation {
+        cited: cited.clone(),
+        citing: Box::new(citing),
+        is_author_self_citation,
+        is_institution_self_citation,
+        is_source_self_citation,
+    }
+}
+
+fn primary_source_id(work: &Work) -> Option<&str> {
+    work.primary_location
+        .as_ref()
+        .and_then(|location| location.source.as_ref())
+        .map(|source| source.id.as_str())
+}
@@ -0,0 +1,84 @@
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use crate::budget::BudgetError;
+use crate::filter::FilterError;
+use crate::json_path::JsonPathError;
+
+/// Something that didn't fail the query, but that its results don't show on their own.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Diagnostic {
+    /// A list edge stopped paging before it ran out of results.
+    Truncated {
+        url: String,
+        fetched: usize,
+        total: Option<u32>,
+        reason: TruncationReason,
+    },
+    /// A request the request budget refused, leaving its edge empty. Recorded once per
+    /// distinct error, since every later request is refused the same way.
+    OverBudget(BudgetError),
+    /// A request that failed or whose response couldn't be read, leaving its edge empty
+    /// or cutting its list short.
+    Failed { url: String, message: String },
+    /// A `filter` parameter naming a key the endpoint doesn't support, or not of the form
+    /// `key:value`. Its edge has no vertices, as no request is sent.
+    InvalidFilter(FilterError),
+    /// A `json_path` that doesn't parse. The edge selects nothing from any vertex.
+    InvalidJsonPath(JsonPathError),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TruncationReason {
+    Limit(usize),
+    MaxPages(u32),
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Diagnostic::Truncated {
+                url,
+                fetched,
+                total,
+                reason,
+            } => {
+                write!(f, "truncated {url} after {fetched}")?;
+                if let Some(total) = total {
+                    write!(f, " of {total}")?;
+                }
+                match reason {
+                    TruncationReason::Limit(limit) => write!(f, " results (limit: {limit})"),
+                    TruncationReason::MaxPages(max_pages) => {
+                        write!(f, " results (max_pages: {max_pages})")
+                    }
+                }
+            }
+            Diagnostic::OverBudget(e) => write!(f, "{e}"),
+            Diagnostic::Failed { url, message } => write!(f, "request to {url} failed: {message}"),
+            Diagnostic::InvalidFilter(e) => write!(f, "{e}"),
+            Diagnostic::InvalidJsonPath(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+/// A shared log of diagnostics, cloned into every iterator that may need to report one.
+#[derive(Clone, Debug, Default)]
+pub struct Diagnostics(Arc<Mutex<Vec<Diagnostic>>>);
+
+impl Diagnostics {
+    pub fn record(&self, diagnostic: Diagnostic) {
+        let mut diagnostics = self.0.lock().expect("diagnostics lock was poisoned");
+        if matches!(diagnostic, Diagnostic::OverBudget(_)) && diagnostics.contains(&diagnostic) {
+            return;
+        }
+        diagnostics.push(diagnostic);
+    }
+
+    /// Removes and returns everything recorded so far.
+    pub fn take(&self) -> Vec<Diagnostic> {
+        std::mem::take(&mut *self.0.lock().expect("diagnostics lock was poisoned"))
+    }
+}
@@ -0,0 +1,561 @@
+use std::{fmt, sync::Arc};
+
+use trustfall_core::ir::{
+    Argument, EdgeParameters, FieldValue, IRQuery, IRQueryComponent, IRVertex, LocalField,
+    Operation, Vid,
+};
+
+use crate::{
+    adapter::{
+        autocomplete_parameters, edge_request, explicit_page_limits, group_query, list_query,
+        page_limits, sample_query, tagged_works_query, EdgeRequest,
+    },
+    backend::{autocomplete_url, Backend, ListQuery},
+    fetch::{OpenAlexClient, PAGE_SIZE},
+    vertex::VertexKind,
+};
+
+/// How resolving an edge turns into HTTP requests, for each vertex the edge is expanded from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RequestPattern {
+    /// Read from data already fetched along with the vertex.
+    NoRequests,
+    /// One request, returning at most `max_results` vertices.
+    Single { max_results: u64 },
+    /// One request per neighbor ID listed on the vertex, of which there are at most `max_ids`.
+    PerNeighborId { max_ids: Option<u64> },
+    /// A cursor-paginated list of at most `max_pages` pages, or `max_results` results.
+    Paginated {
+        max_pages: Option<u64>,
+        max_results: Option<u64>,
+    },
+    /// Pages through a list like `Paginated`, then derives any number of vertices from it.
+    Derived {
+        max_pages: Option<u64>,
+        max_results: Option<u64>,
+    },
+    /// Pages through an entire list before yielding anything, e.g. every citing work.
+    Exhaustive,
+    /// An edge the adapter doesn't resolve.
+    Unknown,
+}
+
+impl RequestPattern {
+    /// Upper bounds on the requests sent and the neighbors produced per expanded vertex.
+    fn bounds(&self) -> (Option<u64>, Option<u64>) {
+        match self {
+            RequestPattern::NoRequests => (Some(0), None),
+            RequestPattern::Single { max_results } => (Some(1), Some(*max_results)),
+            RequestPattern::PerNeighborId { max_ids } => (*max_ids, *max_ids),
+            RequestPattern::Paginated {
+                max_pages,
+                max_results,
+            } => {
+                let pages = match (max_pages, max_results) {
+                    (Some(pages), Some(results)) => Some((*pages).min(pages_for(*results))),
+                    (Some(pages), None) => Some(*pages),
+                    (None, Some(results)) => Some(pages_for(*results)),
+                    (None, None) => None,
+                };
+                let results = match (pages, max_results) {
+                    (Some(pages), Some(results)) => {
+                        Some((pages * u64::from(PAGE_SIZE)).min(*results))
+                    }
+                    (Some(pages), None) => Some(pages * u64::from(PAGE_SIZE)),
+                    (None, results) => *results,
+                };
+                (pages, results)
+            }
+            RequestPattern::Derived {
+                max_pages,
+                max_results,
+            } => {
+                let list = RequestPattern::Paginated {
+                    max_pages: *max_pages,
+                    max_results: *max_results,
+                };
+                (list.bounds().0, None)
+            }
+            RequestPattern::Exhaustive | RequestPattern::Unknown => (None, None),
+        }
+    }
+}
+
+impl fmt::Display for RequestPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequestPattern::NoRequests => write!(f, "no requests, read from the parent vertex"),
+            RequestPattern::Single { max_results: 1 } => write!(f, "1 request for 1 result"),
+            RequestPattern::Single { max_results } => {
+                write!(f, "1 request for at most {max_results} results")
+            }
+            RequestPattern::PerNeighborId { max_ids: Some(1) } => write!(f, "at most 1 request"),
+            RequestPattern::PerNeighborId { max_ids: _ } => {
+                write!(f, "1 request per neighbor ID listed on the parent vertex")
+            }
+            RequestPattern::Paginated { .. } => match self.bounds() {
+                (Some(pages), _) => write!(f, "at most {pages} pages of {PAGE_SIZE}"),
+                (None, _) => write!(f, "every page of {PAGE_SIZE}, unbounded"),
+            },
+            RequestPattern::Derived { .. } => match self.bounds() {
+                (Some(pages), _) => write!(
+                    f,
+                    "at most {pages} pages of {PAGE_SIZE}, deriving any number of results"
+                ),
+                (None, _) => write!(f, "every page of {PAGE_SIZE}, unbounded"),
+            },
+            RequestPattern::Exhaustive => {
+                write!(
+                    f,
+                    "pages through the entire list before yielding, unbounded"
+                )
+            }
+            RequestPattern::Unknown => write!(f, "unknown, the adapter doesn't resolve this edge"),
+        }
+    }
+}
+
+/// What the adapter will do to resolve one edge of a query.
+#[derive(Clone, Debug)]
+pub struct EdgePlan {
+    /// How deeply the edge is nested: 0 for the starting edge.
+    pub depth: usize,
+    /// The type the edge is expanded from, or `None` for the starting edge.
+    pub from_type: Option<Arc<str>>,
+    pub edge_name: Arc<str>,
+    pub pattern: RequestPattern,
+    /// The URL requested, or a template for it with the parent vertex's ID as `{id}`.
+    pub url: Option<String>,
+    /// Edge parameters sent to OpenAlex, e.g. `filter` and `sort`.
+    pub pushed_down: Vec<String>,
+    /// `@filter` directives on the destination vertex, applied only after fetching.
+    pub local_filters: Vec<String>,
+    pub optional: bool,
+    pub folded: bool,
+    /// The `meta.count` of the starting edge's list, when counts were fetched.
+    pub matches: Option<u32>,
+    /// An upper bound on the requests this edge sends over the whole query.
+    pub estimated_requests: Option<u64>,
+}
+
+#[derive(Clone, Debug)]
+pub struct QueryPlan {
+    pub edges: Vec<EdgePlan>,
+    /// An upper bound on the requests the whole query sends, if every edge is bounded.
+    pub estimated_requests: Option<u64>,
+}
+
+impl fmt::Display for QueryPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for edge in &self.edges {
+            let indent = "  ".repeat(edge.depth);
+            match &edge.from_type {
+                Some(from_type) => write!(f, "{indent}{from_type}.{}", edge.edge_name)?,
+                None => write!(f, "{indent}{}", edge.edge_name)?,
+            }
+            if edge.folded {
+                write!(f, " @fold")?;
+            }
+            if edge.optional {
+                write!(f, " @optional")?;
+            }
+            writeln!(f, ": {}", edge.pattern)?;
+
+            if let Some(url) = &edge.url {
+                writeln!(f, "{indent}  url: {url}")?;
+            }
+            if !edge.pushed_down.is_empty() {
+                writeln!(f, "{indent}  pushed down: {}", edge.pushed_down.join(", "))?;
+            }
+            if !edge.local_filters.is_empty() {
+                writeln!(
+                    f,
+                    "{indent}  filtered locally after fetching: {}",
+                    edge.local_filters.join(", ")
+                )?;
+            }
+            if let Some(matches) = edge.matches {
+                writeln!(f, "{indent}  matches: {matches}")?;
+            }
+            match edge.estimated_requests {
+                Some(0) => writeln!(f, "{indent}  requests: none")?,
+                Some(requests) => writeln!(f, "{indent}  requests: at most {requests}")?,
+                None => writeln!(f, "{indent}  requests: unbounded")?,
+            }
+        }
+
+        match self.estimated_requests {
+            Some(requests) => write!(f, "Estimated requests: at most {requests}"),
+            None => write!(
+                f,
+                "Estimated requests: unbounded; add `limit:` or `max_pages:` to the unbounded edges"
+            ),
+        }
+    }
+}
+
+/// Walks a query's IR and describes the requests each edge will send, without running it.
+/// With a client, the starting edge's list is counted with a single `per-page=1` request.
+pub fn explain_query<B: Backend>(query: &IRQuery, client: Option<&OpenAlexClient<B>>) -> QueryPlan {
+    let component = &query.root_component;
+    let root_vertex = &component.vertices[&component.root];
+
+    let (pattern, url) = starting_edge(&query.root_name, &query.root_parameters);
+    let mut pattern = pattern;
+    let mut matches = None;
+    if let (Some(client), Some((kind, list)), RequestPattern::Paginated { .. }) = (
+        client,
+        starting_list(&query.root_name, &query.root_parameters),
+        &pattern,
+    ) {
+        // A failed count is recorded as a diagnostic, and the plan goes without it.
+        if let Ok(count) = client.fetch_count(kind, &list) {
+            matches = Some(count);
+            pattern = narrowed_by_count(pattern, count);
+        }
+    }
+    let (requests, rows) = pattern.bounds();
+
+    let mut edges = vec![EdgePlan {
+        depth: 0,
+        from_type: None,
+        edge_name: query.root_name.clone(),
+        pushed_down: pushed_down(&query.root_parameters),
+        local_filters: local_filters(root_vertex),
+        pattern,
+        url,
+        optional: false,
+        folded: false,
+        matches,
+        estimated_requests: requests,
+    }];
+    explain_component(component, component.root, 1, rows, &mut edges);
+
+    let estimated_requests = edges
+        .iter()
+        .map(|edge| edge.estimated_requests)
+        .sum::<Option<u64>>();
+    QueryPlan {
+        edges,
+        estimated_requests,
+    }
+}
+
+/// Explains the edges leaving `vid`, reached by at most `rows` vertices, then their subtrees.
+fn explain_component(
+    component: &IRQueryComponent,
+    vid: Vid,
+    depth: usize,
+    rows: Option<u64>,
+    edges: &mut Vec<EdgePlan>,
+) {
+    let from_type = &component.vertices[&vid].type_name;
+
+    for edge in component.edges.values().filter(|edge| edge.from_vid == vid) {
+        let mut pattern = neighbor_edge(from_type, &edge.edge_name, &edge.parameters);
+        if let Some(recursive) = &edge.recursive {
+            // Only a chain of single neighbors stays bounded as it recurses.
+            pattern = match pattern {
+                RequestPattern::PerNeighborId { max_ids: Some(1) } => {
+                    RequestPattern::PerNeighborId {
+                        max_ids: Some(recursive.depth.get() as u64),
+                    }
+                }
+                RequestPattern::NoRequests => RequestPattern::NoRequests,
+                _ => RequestPattern::Exhaustive,
+            };
+        }
+        let (requests, neighbors) = pattern.bounds();
+
+        edges.push(EdgePlan {
+            depth,
+            from_type: Some(from_type.clone()),
+            edge_name: edge.edge_name.clone(),
+            url: neighbor_url(from_type, &edge.edge_name, &edge.parameters),
+            pushed_down: pushed_down(&edge.parameters),
+            local_filters: local_filters(&component.vertices[&edge.to_vid]),
+            pattern,
+            optional: edge.optional,
+            folded: false,
+            matches: None,
+            estimated_requests: times(rows, requests),
+        });
+        explain_component(
+            component,
+            edge.to_vid,
+            depth + 1,
+            times(rows, neighbors),
+            edges,
+        );
+    }
+
+    for fold in component.folds.values().filter(|fold| fold.from_vid == vid) {
+        let pattern = neighbor_edge(from_type, &fold.edge_name, &fold.parameters);
+        let (requests, neighbors) = pattern.bounds();
+
+        edges.push(EdgePlan {
+            depth,
+            from_type: Some(from_type.clone()),
+            edge_name: fold.edge_name.clone(),
+            url: neighbor_url(from_type, &fold.edge_name, &fold.parameters),
+            pushed_down: pushed_down(&fold.parameters),
+            local_filters: local_filters(&fold.component.vertices[&fold.to_vid]),
+            pattern,
+            optional: false,
+            folded: true,
+            matches: None,
+            estimated_requests: times(rows, requests),
+        });
+        explain_component(
+            &fold.component,
+            fold.to_vid,
+            depth + 1,
+            times(rows, neighbors),
+            edges,
+        );
+    }
+}
+
+fn times(rows: Option<u64>, per_row: Option<u64>) -> Option<u64> {
+    match (rows, per_row) {
+        (Some(0), _) | (_, Some(0)) => Some(0),
+        (Some(rows), Some(per_row)) => Some(rows.saturating_mul(per_row)),
+        _ => None,
+    }
+}
+
+fn pages_for(results: u64) -> u64 {
+    results.div_ceil(u64::from(PAGE_SIZE))
+}
+
+/// Tightens a paginated starting edge's bounds to the number of results that exist.
+fn narrowed_by_count(pattern: RequestPattern, count: u32) -> RequestPattern {
+    match pattern {
+        RequestPattern::Paginated {
+            max_pages,
+            max_results,
+        } => RequestPattern::Paginated {
+            max_pages: Some(
+                max_pages
+                    .unwrap_or(u64::MAX)
+                    .min(pages_for(count as u64).max(1)),
+            ),
+            max_results: Some(max_results.unwrap_or(u64::MAX).min(count as u64)),
+        },
+        pattern => pattern,
+    }
+}
+
+fn paginated(parameters: &EdgeParameters) -> RequestPattern {
+    let (limit, max_pages) = page_limits(parameters);
+    RequestPattern::Paginated {
+        max_pages: max_pages.map(u64::from),
+        max_results: limit.map(|limit| limit as u64),
+    }
+}
+
+fn derived(parameters: &EdgeParameters) -> RequestPattern {
+    let (limit, max_pages) = page_limits(parameters);
+    RequestPattern::Derived {
+        max_pages: max_pages.map(u64::from),
+        max_results: limit.map(|limit| limit as u64),
+    }
+}
+
+/// Which kind of entity a starting edge like `ListWorks` or `OpenAlexRandomWork` is about.
+pub(crate) fn starting_kind(edge_name: &str, prefix: &str) -> Option<VertexKind> {
+    let suffix = edge_name.strip_prefix(prefix)?;
+    VertexKind::ALL.into_iter().find(|kind| {
+        suffix == kind.type_name() || suffix.strip_suffix('s') == Some(kind.type_name())
+    })
+}
+
+/// The list a starting edge pages through, if it pages through one.
+fn starting_list(edge_name: &str, parameters: &EdgeParameters) -> Option<(VertexKind, ListQuery)> {
+    if let Some(kind) = starting_kind(edge_name, "List") {
+        return Some((kind, list_query(kind, parameters).ok()?));
+    }
+    if let Some(kind) = starting_kind(edge_name, "Sample") {
+        return Some((kind, sample_query(kind, parameters).ok()?));
+    }
+    let query = tagged_works_query(edge_name, parameters)?.ok()?;
+    Some((VertexKind::Work, query))
+}
+
+fn starting_edge(edge_name: &str, parameters: &EdgeParameters) -> (RequestPattern, Option<String>) {
+    // The adapter doesn't send a filter it knows to be invalid; the edge is just empty.
+    let invalid_filter = (RequestPattern::NoRequests, None);
+
+    if let Some(kind) = starting_kind(edge_name, "List") {
+        return match list_query(kind, parameters) {
+            Ok(query) => (paginated(parameters), Some(query.url(kind))),
+            Err(_) => invalid_filter,
+        };
+    }
+    if let Some(kind) = starting_kind(edge_name, "Sample") {
+        return match sample_query(kind, parameters) {
+            // An empty sample isn't requested at all.
+            Ok(ListQuery {
+                sample: Some(0), ..
+            }) => (RequestPattern::NoRequests, None),
+            Ok(query) => {
+                let size = query.sample.unwrap_or(0);
+                let (limit, max_pages) = explicit_page_limits(parameters);
+                (
+                    RequestPattern::Paginated {
+                        max_pages: Some(max_pages.map_or(pages_for(size), |max_pages| {
+                            pages_for(size).min(u64::from(max_pages))
+                        })),
+                        max_results: Some(limit.map_or(size, |limit| size.min(limit as u64))),
+                    },
+                    Some(query.url(kind)),
+                )
+            }
+            Err(_) => invalid_filter,
+        };
+    }
+    if let Some(kind) = starting_kind(edge_name, "Group") {
+        return match group_query(kind, parameters) {
+            Ok((query, group_by)) => (
+                RequestPattern::Single { max_results: 200 },
+                Some(query.group_url(kind, &group_by)),
+            ),
+            Err(_) => invalid_filter,
+        };
+    }
+    if let Some(kind) = starting_kind(edge_name, "OpenAlexRandom") {
+        return (
+            RequestPattern::Single { max_results: 1 },
+            Some(format!(
+                "https://api.openalex.org/{}/random",
+                kind.endpoint()
+            )),
+        );
+    }
+    if starting_kind(edge_name, "OpenAlexIDSearch").is_some() {
+        return (
+            RequestPattern::Single { max_results: 1 },
+            parameters
+                .get("id")
+                .and_then(|v| v.as_str())
+                .map(|id| id.to_string()),
+        );
+    }
+
+    match edge_name {
+        "OpenAlexSDGWorks" | "OpenAlexKeywordWorks" => {
+            match tagged_works_query(edge_name, parameters) {
+                Some(Ok(query)) => (paginated(parameters), Some(query.url(VertexKind::Work))),
+                Some(Err(_)) => invalid_filter,
+                None => (paginated(parameters), None),
+            }
+        }
+        "Autocomplete" => {
+            let (entity, q) = autocomplete_parameters(parameters);
+            (
+                RequestPattern::Single { max_results: 10 },
+                Some(autocomplete_url(entity.as_deref(), &q)),
+            )
+        }
+        _ => (RequestPattern::Unknown, None),
+    }
+}
+
+fn neighbor_edge(type_name: &str, edge_name: &str, parameters: &EdgeParameters) -> RequestPattern {
+    match edge_request(type_name, edge_name, parameters) {
+        Some(EdgeRequest::Nested | EdgeRequest::Invalid(_)) => RequestPattern::NoRequests,
+        Some(EdgeRequest::SingleId) => RequestPattern::PerNeighborId { max_ids: Some(1) },
+        Some(EdgeRequest::PerId) => RequestPattern::PerNeighborId { max_ids: None },
+        Some(EdgeRequest::List { .. }) => paginated(parameters),
+        Some(EdgeRequest::Count { .. }) => RequestPattern::Single { max_results: 1 },
+        Some(EdgeRequest::Derived { .. } | EdgeRequest::CitingReferences) => derived(parameters),
+        None => RequestPattern::Unknown,
+    }
+}
+
+/// The list URL a neighbor edge requests, with `{id}` standing in for the parent vertex's ID.
+fn neighbor_url(type_name: &str, edge_name: &str, parameters: &EdgeParameters) -> Option<String> {
+    let (kind, filter) = match edge_request(type_name, edge_name, parameters)? {
+        EdgeRequest::List {
+            kind,
+            filter_key,
+            extra_filter,
+        } => match extra_filter {
+            Some(extra_filter) => (kind, format!("{filter_key}:{{id}},{extra_filter}")),
+            None => (kind, format!("{filter_key}:{{id}}")),
+        },
+        EdgeRequest::Count { filter_key } | EdgeRequest::Derived { filter_key } => {
+            (VertexKind::Work, format!("{filter_key}:{{id}}"))
+        }
+        EdgeRequest::CitingReferences => {
+            (VertexKind::Work, "cites:{referenced work IDs}".to_string())
+        }
+        EdgeRequest::Nested
+        | EdgeRequest::SingleId
+        | EdgeRequest::PerId
+        | EdgeRequest::Invalid(_) => return None,
+    };
+
+    Some(format!(
+        "https://api.openalex.org/{}?filter={filter}",
+        kind.endpoint()
+    ))
+}
+
+/// Edge parameters that change what OpenAlex returns, rather than how the adapter pages.
+fn pushed_down(parameters: &EdgeParameters) -> Vec<String> {
+    parameters
+        .iter()
+        .filter(|(name, _)| !matches!(name.as_ref(), "limit" | "max_pages"))
+        .filter_map(|(name, value)| match value {
+            FieldValue::Null => None,
+            FieldValue::String(value) => Some(format!("{name}={value}")),
+            FieldValue::Int64(value) => Some(format!("{name}={value}")),
+            FieldValue::Uint64(value) => Some(format!("{name}={value}")),
+            FieldValue::Boolean(value) => Some(format!("{name}={value}")),
+            value => Some(format!("{name}={value:?}")),
+        })
+        .collect()
+}
+
+fn local_filters(vertex: &IRVertex) -> Vec<String> {
+    vertex.filters.iter().map(describe_filter).collect()
+}
+
+fn describe_filter(filter: &Operation<LocalField, Argument>) -> String {
+    let (operation, field, argument) = match filter {
+        Operation::IsNull(field) => ("is_null", field, None),
+        Operation::IsNotNull(field) => ("is_not_null", field, None),
+        Operation::Equals(field, argument) => ("=", field, Some(argument)),
+        Operation::NotEquals(field, argument) => ("!=", field, Some(argument)),
+        Operation::LessThan(field, argument) => ("<", field, Some(argument)),
+        Operation::LessThanOrEqual(field, argument) => ("<=", field, Some(argument)),
+        Operation::GreaterThan(field, argument) => (">", field, Some(argument)),
+        Operation::GreaterThanOrEqual(field, argument) => (">=", field, Some(argument)),
+        Operation::Contains(field, argument) => ("contains", field, Some(argument)),
+        Operation::NotContains(field, argument) => ("not_contains", field, Some(argument)),
+        Operation::OneOf(field, argument) => ("one_of", field, Some(argument)),
+        Operation::NotOneOf(field, argument) => ("not_one_of", field, Some(argument)),
+        Operation::HasPrefix(field, argument) => ("has_prefix", field, Some(argument)),
+        Operation::NotHasPrefix(field, argument) => ("not_has_prefix", field, Some(argument)),
+        Operation::HasSuffix(field, argument) => ("has_suffix", field, Some(argument)),
+        Operation::NotHasSuffix(field, argument) => ("not_has_suffix", field, Some(argument)),
+        Operation::HasSubstring(field, argument) => ("has_substring", field, Some(argument)),
+        Operation::NotHasSubstring(field, argument) => ("not_has_substring", field, Some(argument)),
+        Operation::RegexMatches(field, argument) => ("regex", field, Some(argument)),
+        Operation::NotRegexMatches(field, argument) => ("not_regex", field, Some(argument)),
+        // Operations added by later Trustfall versions.
+        _ => return format!("{filter:?}"),
+    };
+
+    match argument {
+        Some(Argument::Variable(variable)) => {
+            format!(
+                "{} {operation} ${}",
+                field.field_name, variable.variable_name
+            )
+        }
+        Some(Argument::Tag(_)) => format!("{} {operation} %tag", field.field_name),
+        None => format!("{} {operation}", field.field_name),
+    }
+}
@@ -1,88 +1,472 @@
+use std::{collections::VecDeque, fmt, sync::Arc};
+
+use crate::backend::{autocomplete_url, entity_url, Backend, HttpBackend, ListQuery, Page};
+use crate::budget::BudgetError;
+use crate::cassette::CassetteError;
+use crate::diagnostics::{Diagnostic, Diagnostics, TruncationReason};
+use crate::mirror::MirrorError;
+use crate::snapshot::SnapshotError;
 use crate::vertex::{
-    Author, Concept, FilteredVertices, Funder, Institution, Publisher, Source, Vertex, VertexKind,
-    Work,
-};
-use lazy_static::lazy_static;
-use reqwest::{
-    blocking::Client,
-    header::{ACCEPT, HOST, USER_AGENT},
-    Error,
+    AutocompleteResult, FilteredVertices, GroupBucket, GroupedVertices, MetaData, Vertex,
+    VertexKind,
 };
+use serde::de::{DeserializeOwned, IgnoredAny};
+use serde_json::Value;
+
+// The largest page size the OpenAlex API accepts.
+pub(crate) const PAGE_SIZE: u32 = 200;
+
+// How many pages a list edge fetches when it's given neither `limit` nor `max_pages`.
+pub(crate) const DEFAULT_MAX_PAGES: u32 = 1;
+
+#[derive(Debug)]
+pub enum FetchError {
+    Http(reqwest::Error),
+    Budget(BudgetError),
+    Cassette(CassetteError),
+    Snapshot(SnapshotError),
+    Mirror(MirrorError),
+    /// The response didn't match the types in `vertex.rs`.
+    Json {
+        url: String,
+        error: serde_json::Error,
+    },
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::Http(e) => write!(f, "{e}"),
+            FetchError::Budget(e) => write!(f, "{e}"),
+            FetchError::Cassette(e) => write!(f, "{e}"),
+            FetchError::Snapshot(e) => write!(f, "{e}"),
+            FetchError::Mirror(e) => write!(f, "{e}"),
+            FetchError::Json { url, error } => {
+                write!(f, "could not deserialize the response from {url}: {error}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+impl From<reqwest::Error> for FetchError {
+    fn from(e: reqwest::Error) -> Self {
+        FetchError::Http(e)
+    }
+}
+
+impl From<BudgetError> for FetchError {
+    fn from(e: BudgetError) -> Self {
+        FetchError::Budget(e)
+    }
+}
+
+impl From<CassetteError> for FetchError {
+    fn from(e: CassetteError) -> Self {
+        FetchError::Cassette(e)
+    }
+}
+
+impl From<SnapshotError> for FetchError {
+    fn from(e: SnapshotError) -> Self {
+        FetchError::Snapshot(e)
+    }
+}
+
+impl From<MirrorError> for FetchError {
+    fn from(e: MirrorError) -> Self {
+        FetchError::Mirror(e)
+    }
+}
+
+/// Turns a backend's responses into vertices, paging through lists as they're iterated.
+#[derive(Debug)]
+pub struct OpenAlexClient<B = HttpBackend> {
+    backend: Arc<B>,
+    /// Where failed requests are reported, since the edges they fail just come up empty.
+    diagnostics: Diagnostics,
+}
+
+impl<B> Clone for OpenAlexClient<B> {
+    fn clone(&self) -> Self {
+        Self {
+            backend: self.backend.clone(),
+            diagnostics: self.diagnostics.clone(),
+        }
+    }
+}
+
+impl<B: Backend> OpenAlexClient<B> {
+    pub fn new(backend: B) -> Self {
+        Self {
+            backend: Arc::new(backend),
+            diagnostics: Diagnostics::default(),
+        }
+    }
+
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    pub fn diagnostics(&self) -> &Diagnostics {
+        &self.diagnostics
+    }
+
+    /// Records a failed request as a diagnostic, then hands the result back unchanged.
+    fn reported<T>(
+        &self,
+        result: Result<T, FetchError>,
+        url: impl FnOnce() -> String,
+    ) -> Result<T, FetchError> {
+        if let Err(e) = &result {
+            self.diagnostics.record(match e {
+                FetchError::Budget(e) => Diagnostic::OverBudget(e.clone()),
+                e => Diagnostic::Failed {
+                    url: url(),
+                    message: e.to_string(),
+                },
+            });
+        }
+        result
+    }
+
+    fn parse<T: DeserializeOwned>(
+        body: &str,
+        url: impl FnOnce() -> String,
+    ) -> Result<T, FetchError> {
+        serde_json::from_str(body).map_err(|error| FetchError::Json { url: url(), error })
+    }
+
+    pub fn fetch_vertex(&self, id: String, kind: VertexKind) -> Result<Vertex, FetchError> {
+        let url = || entity_url(kind, &id);
+        let result = self.backend.get(kind, &id).and_then(|body| {
+            let json = Self::parse::<Value>(&body, url)?;
+            Vertex::from_json(kind, json).map_err(|error| FetchError::Json { url: url(), error })
+        });
+        self.reported(result, url)
+    }
+
+    /// Reads the total number of matches for a list request from `meta.count`,
+    /// asking for a single result so the response stays small.
+    pub fn fetch_count(&self, kind: VertexKind, query: &ListQuery) -> Result<u32, FetchError> {
+        let query = ListQuery {
+            per_page: Some(1),
+            ..query.clone()
+        };
+        let result = self.backend.list(kind, &query).and_then(|body| {
+            Ok(
+                Self::parse::<FilteredVertices<IgnoredAny>>(&body, || query.url(kind))?
+                    .meta
+                    .count,
+            )
+        });
+        self.reported(result, || query.url(kind))
+    }
+
+    /// Fetches the buckets of a `group_by` request. OpenAlex returns at most 200 groups.
+    pub fn fetch_groups(
+        &self,
+        kind: VertexKind,
+        query: &ListQuery,
+        key: &str,
+    ) -> Result<Vec<GroupBucket>, FetchError> {
+        let query = ListQuery {
+            per_page: Some(PAGE_SIZE),
+            ..query.clone()
+        };
+        let result = self.backend.group_by(kind, &query, key).and_then(|body| {
+            Ok(Self::parse::<GroupedVertices>(&body, || query.group_url(kind, key))?.group_by)
+        });
+        self.reported(result, || query.group_url(kind, key))
+    }
+
+    pub fn fetch_autocomplete(
+        &self,
+        entity: Option<&str>,
+        q: &str,
+    ) -> Result<Vec<AutocompleteResult>, FetchError> {
+        let result = self.backend.autocomplete(entity, q).and_then(|body| {
+            Ok(
+                Self::parse::<FilteredVertices<AutocompleteResult>>(&body, || {
+                    autocomplete_url(entity, q)
+                })?
+                .results,
+            )
+        });
+        self.reported(result, || autocomplete_url(entity, q))
+    }
+
+    /// Iterates over every result of a list endpoint, following OpenAlex cursor pagination
+    /// until the results or the budget run out.
+    pub fn fetch_paginated(
+        &self,
+        kind: VertexKind,
+        query: ListQuery,
+        budget: PageBudget,
+    ) -> PaginatedVertices<B> {
+        self.fetch_paginated_lists(kind, vec![query], budget)
+    }
+
+    /// Pages through several lists one after the other, e.g. one filter split into batches,
+    /// with a single `budget` for all of them.
+    pub fn fetch_paginated_lists(
+        &self,
+        kind: VertexKind,
+        queries: Vec<ListQuery>,
+        budget: PageBudget,
+    ) -> PaginatedVertices<B> {
+        PaginatedVertices {
+            client: self.clone(),
+            url: String::new(),
+            query: ListQuery::default(),
+            queued: queries.into(),
+            kind,
+            budget,
+            cursor: None,
+            buffer: VecDeque::new(),
+            pages: 0,
+            fetched: 0,
+            earlier_total: 0,
+            total: None,
+        }
+    }
+
+    /// Iterates over every result of a list endpoint using basic `page=` paging, which OpenAlex
+    /// requires for `sample=` requests. Stops once `meta.count` results have been seen, or the
+    /// budget runs out.
+    pub fn fetch_numbered_pages(
+        &self,
+        kind: VertexKind,
+        query: ListQuery,
+        budget: PageBudget,
+    ) -> NumberedPages<B> {
+        NumberedPages {
+            client: self.clone(),
+            url: query.url(kind),
+            query,
+            kind,
+            budget,
+            page: 1,
+            remaining: None,
+            buffer: VecDeque::new(),
+            fetched: 0,
+            total: None,
+        }
+    }
+
+    fn fetch_page(
+        &self,
+        kind: VertexKind,
+        query: &ListQuery,
+    ) -> Result<(Vec<Vertex>, MetaData), FetchError> {
+        let url = || query.url(kind);
+        let result = self.backend.list(kind, query).and_then(|body| {
+            let page = Self::parse::<FilteredVertices<Value>>(&body, url)?;
+            let vertices = page
+                .results
+                .into_iter()
+                .map(|json| Vertex::from_json(kind, json))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|error| FetchError::Json { url: url(), error })?;
+            Ok((vertices, page.meta))
+        });
+        self.reported(result, url)
+    }
+}
+
+/// How far a list edge may page before giving up on the rest of its results.
+#[derive(Clone, Debug, Default)]
+pub struct PageBudget {
+    pub limit: Option<usize>,
+    pub max_pages: Option<u32>,
+    /// Where to report that the budget cut paging short.
+    pub diagnostics: Diagnostics,
+}
+
+pub struct PaginatedVertices<B> {
+    client: OpenAlexClient<B>,
+    /// The list being paged through.
+    url: String,
+    query: ListQuery,
+    /// The lists still to page through once this one runs out.
+    queued: VecDeque<ListQuery>,
+    kind: VertexKind,
+    budget: PageBudget,
+    cursor: Option<String>,
+    buffer: VecDeque<Vertex>,
+    /// Pages and results so far, over every list.
+    pages: u32,
+    fetched: usize,
+    /// How many results the lists before this one had.
+    earlier_total: u32,
+    total: Option<u32>,
+}
+
+impl<B> PaginatedVertices<B> {
+    /// Stops paging, noting the truncation if there were results left to fetch.
+    fn stop(&mut self, reason: TruncationReason) {
+        let total = match self.total {
+            Some(total) if self.queued.is_empty() => Some(self.earlier_total + total),
+            _ => None,
+        };
+        let more_results = match total {
+            Some(total) => total as usize > self.fetched,
+            None => !self.buffer.is_empty() || self.cursor.is_some() || !self.queued.is_empty(),
+        };
+        if more_results {
+            self.budget.diagnostics.record(Diagnostic::Truncated {
+                url: self.url.clone(),
+                fetched: self.fetched,
+                total,
+                reason,
+            });
+        }
+
+        self.cursor = None;
+        self.queued.clear();
+        self.buffer.clear();
+    }
+
+    /// Moves on to the next queued list, if there is one.
+    fn next_list(&mut self) -> bool {
+        let Some(query) = self.queued.pop_front() else {
+            return false;
+        };
+        self.earlier_total += self.total.take().unwrap_or(0);
+        self.url = query.url(self.kind);
+        self.query = query;
+        self.cursor = Some("*".to_string());
+        true
+    }
+}
+
+impl<B: Backend> Iterator for PaginatedVertices<B> {
+    type Item = Result<Vertex, FetchError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(limit) = self.budget.limit {
+            if self.fetched >= limit {
+                self.stop(TruncationReason::Limit(limit));
+                return None;
+            }
+        }
+
+        while self.buffer.is_empty() {
+            if self.cursor.is_none() && !self.next_list() {
+                return None;
+            }
+            if let Some(max_pages) = self.budget.max_pages {
+                if self.pages >= max_pages {
+                    self.stop(TruncationReason::MaxPages(max_pages));
+                    return None;
+                }
+            }
+
+            let query = ListQuery {
+                per_page: Some(PAGE_SIZE),
+                page: Some(Page::Cursor(self.cursor.take()?)),
+                ..self.query.clone()
+            };
+
+            match self.client.fetch_page(self.kind, &query) {
+                Ok((vertices, meta)) => {
+                    // OpenAlex keeps handing out cursors past the last page, so stop on an empty one.
+                    if !vertices.is_empty() {
+                        self.cursor = meta.next_cursor;
+                    }
+                    self.pages += 1;
+                    self.total.get_or_insert(meta.count);
+                    self.buffer.extend(vertices);
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+
+        self.fetched += 1;
+        self.buffer.pop_front().map(Ok)
+    }
+}
+
+pub struct NumberedPages<B> {
+    client: OpenAlexClient<B>,
+    url: String,
+    query: ListQuery,
+    kind: VertexKind,
+    budget: PageBudget,
+    page: u32,
+    remaining: Option<u32>,
+    buffer: VecDeque<Vertex>,
+    fetched: usize,
+    total: Option<u32>,
+}
+
+impl<B> NumberedPages<B> {
+    /// Stops paging, noting the truncation if there were results left to fetch.
+    fn stop(&mut self, reason: TruncationReason) {
+        if !self.buffer.is_empty() || self.remaining != Some(0) {
+            self.budget.diagnostics.record(Diagnostic::Truncated {
+                url: self.url.clone(),
+                fetched: self.fetched,
+                total: self.total,
+                reason,
+            });
+        }
+
+        self.remaining = Some(0);
+        self.buffer.clear();
+    }
+}
+
+impl<B: Backend> Iterator for NumberedPages<B> {
+    type Item = Result<Vertex, FetchError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(limit) = self.budget.limit {
+            if self.fetched >= limit {
+                self.stop(TruncationReason::Limit(limit));
+                return None;
+            }
+        }
+
+        if self.buffer.is_empty() {
+            if self.remaining == Some(0) {
+                return None;
+            }
+            if let Some(max_pages) = self.budget.max_pages {
+                if self.page > max_pages {
+                    self.stop(TruncationReason::MaxPages(max_pages));
+                    return None;
+                }
+            }
+
+            let query = ListQuery {
+                per_page: Some(PAGE_SIZE),
+                page: Some(Page::Number(self.page)),
+                ..self.query.clone()
+            };
+
+            match self.client.fetch_page(self.kind, &query) {
+                Ok((vertices, meta)) => {
+                    let remaining = self.remaining.unwrap_or(meta.count);
+                    self.remaining = Some(if vertices.is_empty() {
+                        0
+                    } else {
+                        remaining.saturating_sub(vertices.len() as u32)
+                    });
+                    self.page += 1;
+                    self.total.get_or_insert(meta.count);
+                    self.buffer.extend(vertices);
+                }
+                Err(e) => {
+                    self.remaining = Some(0);
+                    return Some(Err(e));
+                }
+            }
+        }
 
-lazy_static! {
-    static ref OPEN_ALEX_CLIENT: Client = Client::new();
-}
-
-pub fn fetch_vertex(url: String, kind: VertexKind) -> Result<Vertex, Error> {
-    let response = OPEN_ALEX_CLIENT
-        .get(url.clone())
-        .header(USER_AGENT, "curl/7.81.0")
-        .header(HOST, "api.openalex.org")
-        .header(ACCEPT, "*/*")
-        .send()?;
-    println!("{:?}", response.text());
-    let request = OPEN_ALEX_CLIENT
-        .get(url.clone())
-        .header(USER_AGENT, "curl/7.81.0")
-        .header(HOST, "api.openalex.org")
-        .header(ACCEPT, "*/*");
-    let json_response = request.send()?;
-    match kind {
-        VertexKind::Work => Ok(Vertex::Work(json_response.json::<Work>()?)),
-        VertexKind::Author => Ok(Vertex::Author(json_response.json::<Author>()?)),
-        VertexKind::Source => Ok(Vertex::Source(json_response.json::<Source>()?)),
-        VertexKind::Concept => Ok(Vertex::Concept(json_response.json::<Concept>()?)),
-        VertexKind::Institution => Ok(Vertex::Institution(json_response.json::<Institution>()?)),
-        VertexKind::Publisher => Ok(Vertex::Publisher(json_response.json::<Publisher>()?)),
-        VertexKind::Funder => Ok(Vertex::Funder(json_response.json::<Funder>()?)),
-    }
-}
-
-pub fn fetch_vertices(url: String, kind: VertexKind) -> Result<Vec<Vertex>, Error> {
-    let json_response = OPEN_ALEX_CLIENT.get(url).send()?;
-
-    match kind {
-        VertexKind::Work => Ok(json_response
-            .json::<FilteredVertices<Work>>()?
-            .results
-            .into_iter()
-            .map(|work| Vertex::Work(work))
-            .collect::<Vec<_>>()),
-        VertexKind::Author => Ok(json_response
-            .json::<FilteredVertices<Author>>()?
-            .results
-            .into_iter()
-            .map(|author| Vertex::Author(author))
-            .collect::<Vec<_>>()),
-        VertexKind::Source => Ok(json_response
-            .json::<FilteredVertices<Source>>()?
-            .results
-            .into_iter()
-            .map(|source| Vertex::Source(source))
-            .collect::<Vec<_>>()),
-        VertexKind::Concept => Ok(json_response
-            .json::<FilteredVertices<Concept>>()?
-            .results
-            .into_iter()
-            .map(|concept| Vertex::Concept(concept))
-            .collect::<Vec<_>>()),
-        VertexKind::Institution => Ok(json_response
-            .json::<FilteredVertices<Institution>>()?
-            .results
-            .into_iter()
-            .map(|institution| Vertex::Institution(institution))
-            .collect::<Vec<_>>()),
-        VertexKind::Publisher => Ok(json_response
-            .json::<FilteredVertices<Publisher>>()?
-            .results
-            .into_iter()
-            .map(|publisher| Vertex::Publisher(publisher))
-            .collect::<Vec<_>>()),
-        VertexKind::Funder => Ok(json_response
-            .json::<FilteredVertices<Funder>>()?
-            .results
-            .into_iter()
-            .map(|funder| Vertex::Funder(funder))
-            .collect::<Vec<_>>()),
+        let vertex = self.buffer.pop_front()?;
+        self.fetched += 1;
+        Some(Ok(vertex))
     }
 }
@@ -0,0 +1,476 @@
+use std::fmt;
+
+use trustfall_core::ir::{EdgeParameters, IRQuery, IRQueryComponent};
+
+use crate::{explain::starting_kind, vertex::VertexKind};
+
+/// A raw OpenAlex filter expression that would be rejected, or silently match nothing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FilterError {
+    /// A comma-separated item that isn't of the form `key:value`.
+    Malformed { kind: VertexKind, item: String },
+    /// A filter key that the entity's endpoint doesn't support.
+    UnknownKey {
+        kind: VertexKind,
+        key: String,
+        suggestion: Option<&'static str>,
+    },
+    /// A value holding `,` or `|`, which OpenAlex reads as separators and has no way to escape.
+    Unescapable {
+        kind: VertexKind,
+        key: &'static str,
+        value: String,
+    },
+}
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FilterError::Malformed { kind, item } => write!(
+                f,
+                "invalid {} filter: expected `key:value`, got `{item}`",
+                kind.endpoint()
+            ),
+            FilterError::UnknownKey {
+                kind,
+                key,
+                suggestion,
+            } => {
+                write!(f, "invalid {} filter: unknown key `{key}`", kind.endpoint())?;
+                match suggestion {
+                    Some(suggestion) => write!(f, ", did you mean `{suggestion}`?"),
+                    None => Ok(()),
+                }
+            }
+            FilterError::Unescapable { kind, key, value } => write!(
+                f,
+                "invalid {} filter: `{key}` value `{value}` can't contain `,` or `|`",
+                kind.endpoint()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FilterError {}
+
+/// Checks every key of a filter expression like `publication_year:2023,is_oa:true`
+/// against the keys the entity's endpoint accepts.
+pub fn validate_filter(kind: VertexKind, filter: &str) -> Result<(), FilterError> {
+    for item in filter.split(',').filter(|item| !item.trim().is_empty()) {
+        let key = match item.split_once(':') {
+            Some((key, _)) if !key.trim().is_empty() => key.trim(),
+            _ => {
+                return Err(FilterError::Malformed {
+                    kind,
+                    item: item.to_string(),
+                })
+            }
+        };
+
+        if !is_allowed_key(kind, key) {
+            return Err(FilterError::UnknownKey {
+                kind,
+                key: key.to_string(),
+                suggestion: suggest_key(kind, key),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// The works filter key `Funder.works(award_id:)` narrows its list with.
+pub(crate) const AWARD_ID_KEY: &str = "grants.award_id";
+
+/// The works filter key and edge parameter of the `OpenAlexSDGWorks` and
+/// `OpenAlexKeywordWorks` starting edges.
+pub(crate) fn tag_filter(edge_name: &str) -> Option<(&'static str, &'static str)> {
+    match edge_name {
+        "OpenAlexSDGWorks" => Some(("sustainable_development_goals.id", "sdg")),
+        "OpenAlexKeywordWorks" => Some(("keywords.id", "keyword")),
+        _ => None,
+    }
+}
+
+/// The `key:value` filter item matching a single value exactly.
+pub(crate) fn filter_item(
+    kind: VertexKind,
+    key: &'static str,
+    value: &str,
+) -> Result<String, FilterError> {
+    if value.contains([',', '|']) {
+        return Err(FilterError::Unescapable {
+            kind,
+            key,
+            value: value.to_string(),
+        });
+    }
+    Ok(format!("{key}:{value}"))
+}
+
+/// Checks the `filter` parameter of every edge in a query, so a bad key is reported before
+/// any request is sent. Running the query anyway leaves those edges empty, with a diagnostic.
+pub fn validate_query(query: &IRQuery) -> Result<(), FilterError> {
+    validate_edge(&query.root_name, &query.root_parameters)?;
+    validate_component(&query.root_component)
+}
+
+fn validate_component(component: &IRQueryComponent) -> Result<(), FilterError> {
+    for edge in component.edges.values() {
+        validate_edge(&edge.edge_name, &edge.parameters)?;
+    }
+    for fold in component.folds.values() {
+        validate_edge(&fold.edge_name, &fold.parameters)?;
+        validate_component(&fold.component)?;
+    }
+    Ok(())
+}
+
+fn validate_edge(edge_name: &str, parameters: &EdgeParameters) -> Result<(), FilterError> {
+    // Only `Funder.works` takes an `award_id`.
+    if let Some(award_id) = parameters.get("award_id").and_then(|v| v.as_str()) {
+        filter_item(VertexKind::Work, AWARD_ID_KEY, award_id)?;
+    }
+    if let Some((key, parameter)) = tag_filter(edge_name) {
+        if let Some(tag) = parameters.get(parameter).and_then(|v| v.as_str()) {
+            filter_item(VertexKind::Work, key, tag)?;
+        }
+    }
+
+    let filter = parameters.get("filter").and_then(|v| v.as_str());
+    match (filter, filtered_kind(edge_name)) {
+        (Some(filter), Some(kind)) => validate_filter(kind, filter),
+        _ => Ok(()),
+    }
+}
+
+/// The kind of entity an edge's `filter` parameter filters.
+fn filtered_kind(edge_name: &str) -> Option<VertexKind> {
+    match edge_name {
+        "cited_by_count_filtered" | "works_count_filtered" => Some(VertexKind::Work),
+        _ => ["List", "Sample", "Group"]
+            .into_iter()
+            .find_map(|prefix| starting_kind(edge_name, prefix)),
+    }
+}
+
+fn is_allowed_key(kind: VertexKind, key: &str) -> bool {
+    allowed_keys(kind).any(|allowed| allowed == key)
+}
+
+fn suggest_key(kind: VertexKind, key: &str) -> Option<&'static str> {
+    allowed_keys(kind)
+        .map(|candidate| (edit_distance(key, candidate), candidate))
+        .filter(|(distance, _)| *distance <= 3)
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+fn allowed_keys(kind: VertexKind) -> impl Iterator<Item = &'static str> {
+    let summary_stats_keys = match kind {
+        VertexKind::Author
+        | VertexKind::Source
+        | VertexKind::Institution
+        | VertexKind::Concept
+        | VertexKind::Publisher
+        | VertexKind::Funder => SUMMARY_STATS_FILTER_KEYS,
+        _ => &[],
+    };
+
+    COMMON_FILTER_KEYS
+        .iter()
+        .chain(entity_filter_keys(kind))
+        .chain(summary_stats_keys)
+        .copied()
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+// Keys accepted by every entity endpoint.
+const COMMON_FILTER_KEYS: &[&str] = &[
+    "cited_by_count",
+    "default.search",
+    "display_name.search",
+    "from_created_date",
+    "from_updated_date",
+    "ids.openalex",
+    "openalex",
+    "to_created_date",
+    "to_updated_date",
+    "works_count",
+];
+
+const SUMMARY_STATS_FILTER_KEYS: &[&str] = &[
+    "summary_stats.2yr_mean_citedness",
+    "summary_stats.h_index",
+    "summary_stats.i10_index",
+];
+
+fn entity_filter_keys(kind: VertexKind) -> &'static [&'static str] {
+    match kind {
+        VertexKind::Work => WORK_FILTER_KEYS,
+        VertexKind::Author => AUTHOR_FILTER_KEYS,
+        VertexKind::Source => SOURCE_FILTER_KEYS,
+        VertexKind::Institution => INSTITUTION_FILTER_KEYS,
+        VertexKind::Concept => CONCEPT_FILTER_KEYS,
+        VertexKind::Publisher => PUBLISHER_FILTER_KEYS,
+        VertexKind::Funder => FUNDER_FILTER_KEYS,
+        VertexKind::Topic => TOPIC_FILTER_KEYS,
+        VertexKind::Subfield | VertexKind::Field | VertexKind::Domain => TOPIC_LEVEL_FILTER_KEYS,
+    }
+}
+
+const WORK_FILTER_KEYS: &[&str] = &[
+    "abstract.search",
+    "apc_list.currency",
+    "apc_list.provenance",
+    "apc_list.value",
+    "apc_list.value_usd",
+    "apc_paid.currency",
+    "apc_paid.provenance",
+    "apc_paid.value",
+    "apc_paid.value_usd",
+    "author.id",
+    "author.orcid",
+    "authors_count",
+    "authorships.author.id",
+    "authorships.author.orcid",
+    "authorships.countries",
+    "authorships.institutions.continent",
+    "authorships.institutions.country_code",
+    "authorships.institutions.id",
+    "authorships.institutions.is_global_south",
+    "authorships.institutions.lineage",
+    "authorships.institutions.ror",
+    "authorships.institutions.type",
+    "authorships.is_corresponding",
+    "best_oa_location.is_accepted",
+    "best_oa_location.is_published",
+    "best_oa_location.license",
+    "best_oa_location.source.host_organization",
+    "best_oa_location.source.id",
+    "best_oa_location.source.is_in_doaj",
+    "best_oa_location.source.is_oa",
+    "best_oa_location.source.issn",
+    "best_oa_location.version",
+    "best_open_version",
+    "biblio.first_page",
+    "biblio.issue",
+    "biblio.last_page",
+    "biblio.volume",
+    "cited_by",
+    "cites",
+    "concepts.id",
+    "concepts.wikidata",
+    "concepts_count",
+    "corresponding_author_ids",
+    "corresponding_institution_ids",
+    "countries_distinct_count",
+    "doi",
+    "from_publication_date",
+    "fulltext.search",
+    "fulltext_origin",
+    "grants.award_id",
+    "grants.funder",
+    "has_abstract",
+    "has_doi",
+    "has_fulltext",
+    "has_ngrams",
+    "has_orcid",
+    "has_pmcid",
+    "has_pmid",
+    "has_references",
+    "ids.mag",
+    "ids.pmcid",
+    "ids.pmid",
+    "indexed_in",
+    "institution.id",
+    "institutions.continent",
+    "institutions.country_code",
+    "institutions.id",
+    "institutions.is_global_south",
+    "institutions.ror",
+    "institutions.type",
+    "institutions_distinct_count",
+    "is_corresponding",
+    "is_global_south",
+    "is_oa",
+    "is_paratext",
+    "is_retracted",
+    "journal",
+    "keywords.id",
+    "language",
+    "locations.is_accepted",
+    "locations.is_oa",
+    "locations.is_published",
+    "locations.license",
+    "locations.source.host_institution_lineage",
+    "locations.source.host_organization",
+    "locations.source.id",
+    "locations.source.is_in_doaj",
+    "locations.source.is_oa",
+    "locations.source.issn",
+    "locations.source.publisher_lineage",
+    "locations.source.type",
+    "locations.version",
+    "locations_count",
+    "mag",
+    "mag_only",
+    "open_access.any_repository_has_fulltext",
+    "open_access.is_oa",
+    "open_access.oa_status",
+    "pmcid",
+    "pmid",
+    "primary_location.is_accepted",
+    "primary_location.is_oa",
+    "primary_location.is_published",
+    "primary_location.license",
+    "primary_location.source.has_issn",
+    "primary_location.source.host_organization",
+    "primary_location.source.host_organization_lineage",
+    "primary_location.source.id",
+    "primary_location.source.is_in_doaj",
+    "primary_location.source.is_oa",
+    "primary_location.source.issn",
+    "primary_location.source.publisher_lineage",
+    "primary_location.source.type",
+    "primary_location.version",
+    "primary_topic.domain.id",
+    "primary_topic.field.id",
+    "primary_topic.id",
+    "primary_topic.subfield.id",
+    "publication_date",
+    "publication_year",
+    "publisher",
+    "raw_affiliation_strings.search",
+    "related_to",
+    "repository",
+    "sustainable_development_goals.id",
+    "title.search",
+    "title_and_abstract.search",
+    "to_publication_date",
+    "topics.domain.id",
+    "topics.field.id",
+    "topics.id",
+    "topics.subfield.id",
+    "type",
+    "type_crossref",
+    "version",
+];
+
+const AUTHOR_FILTER_KEYS: &[&str] = &[
+    "affiliations.institution.country_code",
+    "affiliations.institution.id",
+    "affiliations.institution.lineage",
+    "affiliations.institution.ror",
+    "affiliations.institution.type",
+    "has_orcid",
+    "last_known_institution.continent",
+    "last_known_institution.country_code",
+    "last_known_institution.id",
+    "last_known_institution.is_global_south",
+    "last_known_institution.lineage",
+    "last_known_institution.ror",
+    "last_known_institution.type",
+    "last_known_institutions.continent",
+    "last_known_institutions.country_code",
+    "last_known_institutions.id",
+    "last_known_institutions.is_global_south",
+    "last_known_institutions.lineage",
+    "last_known_institutions.ror",
+    "last_known_institutions.type",
+    "orcid",
+    "scopus",
+    "topic_share.id",
+    "topics.id",
+    "x_concepts.id",
+];
+
+const SOURCE_FILTER_KEYS: &[&str] = &[
+    "apc_prices.currency",
+    "apc_prices.price",
+    "apc_usd",
+    "continent",
+    "country_code",
+    "has_issn",
+    "host_organization",
+    "host_organization_lineage",
+    "ids.mag",
+    "is_global_south",
+    "is_in_doaj",
+    "is_oa",
+    "issn",
+    "publisher",
+    "topics.id",
+    "type",
+    "x_concepts.id",
+];
+
+const INSTITUTION_FILTER_KEYS: &[&str] = &[
+    "continent",
+    "country_code",
+    "has_ror",
+    "is_global_south",
+    "lineage",
+    "repositories.host_organization",
+    "repositories.host_organization_lineage",
+    "repositories.id",
+    "roles.id",
+    "roles.role",
+    "roles.works_count",
+    "ror",
+    "topics.id",
+    "type",
+    "x_concepts.id",
+];
+
+const CONCEPT_FILTER_KEYS: &[&str] = &["ancestors.id", "level", "wikidata"];
+
+const PUBLISHER_FILTER_KEYS: &[&str] = &[
+    "ancestors.id",
+    "continent",
+    "country_codes",
+    "hierarchy_level",
+    "lineage",
+    "parent_publisher",
+    "roles.id",
+    "roles.role",
+    "roles.works_count",
+    "ror",
+];
+
+const FUNDER_FILTER_KEYS: &[&str] = &[
+    "continent",
+    "country_code",
+    "description.search",
+    "grants_count",
+    "is_global_south",
+    "roles.id",
+    "roles.role",
+    "roles.works_count",
+    "ror",
+];
+
+const TOPIC_FILTER_KEYS: &[&str] = &[
+    "description.search",
+    "domain.id",
+    "field.id",
+    "keywords.search",
+    "subfield.id",
+];
+
+const TOPIC_LEVEL_FILTER_KEYS: &[&str] = &["description.search", "domain.id", "field.id"];
@@ -0,0 +1,136 @@
+use std::fmt;
+
+use serde_json::Value;
+
+/// A path into a vertex's JSON, for fields the schema doesn't have yet: `summary_stats.h_index`,
+/// `authorships[0].author.id`, `topics[*].display_name` or `ids["mag"]`. A leading `$` is
+/// allowed; `[-1]` is the last element of an array.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JsonPath {
+    steps: Vec<Step>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Step {
+    Key(String),
+    Index(i64),
+    /// Every element of an array, or every value of an object.
+    Wildcard,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JsonPathError {
+    pub path: String,
+    /// The byte offset in `path` where it stopped making sense.
+    pub position: usize,
+    pub reason: &'static str,
+}
+
+impl fmt::Display for JsonPathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid JSON path {:?} at position {}: {}",
+            self.path, self.position, self.reason
+        )
+    }
+}
+
+impl std::error::Error for JsonPathError {}
+
+impl JsonPath {
+    pub fn parse(path: &str) -> Result<Self, JsonPathError> {
+        let error = |position, reason| JsonPathError {
+            path: path.to_string(),
+            position,
+            reason,
+        };
+
+        let start = usize::from(path.starts_with('$'));
+        let mut steps = vec![];
+        let mut position = start;
+        while position < path.len() {
+            let rest = &path[position..];
+            if let Some(rest) = rest.strip_prefix('[') {
+                let Some(end) = rest.find(']') else {
+                    return Err(error(position, "`[` is never closed"));
+                };
+                let inner = &rest[..end];
+                let quoted = ['"', '\''].into_iter().find_map(|quote| {
+                    inner
+                        .strip_prefix(quote)
+                        .and_then(|inner| inner.strip_suffix(quote))
+                });
+                steps.push(match (inner, quoted) {
+                    (_, Some(key)) => Step::Key(key.to_string()),
+                    ("*", None) => Step::Wildcard,
+                    (index, None) => match index.parse() {
+                        Ok(index) => Step::Index(index),
+                        Err(_) => {
+                            return Err(error(
+                                position + 1,
+                                "expected an index, `*` or a quoted field name",
+                            ))
+                        }
+                    },
+                });
+                position += end + 2;
+                continue;
+            }
+
+            // A bare field name is only allowed at the start, as in `summary_stats.h_index`.
+            let key_start = if rest.starts_with('.') {
+                position + 1
+            } else if position == start {
+                position
+            } else {
+                return Err(error(position, "expected `.` or `[`"));
+            };
+            let key_end = path[key_start..]
+                .find(['.', '['])
+                .map_or(path.len(), |end| key_start + end);
+            steps.push(match &path[key_start..key_end] {
+                "" => return Err(error(key_start, "expected a field name")),
+                "*" => Step::Wildcard,
+                key => Step::Key(key.to_string()),
+            });
+            position = key_end;
+        }
+
+        Ok(JsonPath { steps })
+    }
+
+    /// Everything in `json` the path leads to, in document order. Steps that don't match,
+    /// such as a field on an array, select nothing rather than failing.
+    pub fn select<'a>(&self, json: &'a Value) -> Vec<&'a Value> {
+        let mut selected = vec![json];
+        for step in &self.steps {
+            selected = selected
+                .into_iter()
+                .flat_map(|value| -> Vec<&'a Value> {
+                    match (step, value) {
+                        (Step::Key(key), Value::Object(object)) => {
+                            object.get(key).into_iter().collect()
+                        }
+                        (Step::Index(index), Value::Array(array)) => {
+                            let index = if *index < 0 {
+                                array.len() as i64 + index
+                            } else {
+                                *index
+                            };
+                            usize::try_from(index)
+                                .ok()
+                                .and_then(|index| array.get(index))
+                                .into_iter()
+                                .collect()
+                        }
+                        (Step::Wildcard, Value::Array(array)) => array.iter().collect(),
+                        (Step::Wildcard, Value::Object(object)) => object.values().collect(),
+                        _ => vec![],
+                    }
+                })
+                .collect();
+        }
+        selected
+    }
+}
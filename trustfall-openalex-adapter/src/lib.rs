@@ -1,4 +1,26 @@
 mod adapter;
+mod backend;
+mod budget;
+mod cassette;
+mod derived;
+mod diagnostics;
+mod explain;
 mod fetch;
+mod filter;
+mod json_path;
+mod local;
+mod mirror;
+mod snapshot;
 mod vertex;
 pub use adapter::OpenAlexAdapter;
+pub use backend::{Backend, HttpBackend, ListQuery, Page};
+pub use budget::{BudgetError, RequestBudget};
+pub use cassette::{Cassette, CassetteBackend, CassetteError};
+pub use diagnostics::{Diagnostic, TruncationReason};
+pub use explain::{EdgePlan, QueryPlan, RequestPattern};
+pub use fetch::FetchError;
+pub use filter::{validate_query, FilterError};
+pub use json_path::JsonPathError;
+pub use mirror::{Mirror, MirrorBackend, MirrorError, SyncReport};
+pub use snapshot::{Snapshot, SnapshotError};
+pub use vertex::VertexKind;
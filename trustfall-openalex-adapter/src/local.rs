@@ -0,0 +1,509 @@
+use std::{cmp::Ordering, collections::HashMap};
+
+use serde_json::{json, Value};
+
+use crate::vertex::VertexKind;
+
+// OpenAlex's default page size, used when a request doesn't set `per-page`.
+const DEFAULT_PAGE_SIZE: usize = 25;
+
+// OpenAlex returns at most this many `group_by` buckets and autocomplete results.
+const MAX_GROUPS: usize = 200;
+pub(crate) const MAX_AUTOCOMPLETE_RESULTS: usize = 10;
+
+/// The `filter`, `search`, `sort`, `sample` and `group_by` parameters of a list request,
+/// evaluated against entities' JSON to answer the request without the API.
+pub(crate) struct LocalQuery {
+    clauses: Vec<Clause>,
+    search: Option<String>,
+    sort: Option<(String, bool)>,
+    sample: Option<(usize, u64)>,
+    pub(crate) group_by: Option<String>,
+    pub(crate) per_page: usize,
+}
+
+/// One `key:value` of a filter. Values separated by `|` match if any of them does.
+struct Clause {
+    key: String,
+    path: String,
+    negated: bool,
+    conditions: Vec<Condition>,
+}
+
+enum Condition {
+    Equals(String),
+    AtLeast(String),
+    AtMost(String),
+    Contains(String),
+    NotNull,
+}
+
+impl LocalQuery {
+    /// Reads the query from a request's parameters, or explains why it can't be answered locally.
+    pub(crate) fn parse(params: &HashMap<String, String>) -> Result<Self, String> {
+        let mut clauses = vec![];
+        for item in params
+            .get("filter")
+            .map(|filter| filter.split(',').collect::<Vec<_>>())
+            .unwrap_or_default()
+        {
+            let (key, value) = item
+                .split_once(':')
+                .ok_or_else(|| format!("malformed filter `{item}`"))?;
+            clauses.push(Clause::parse(key, value)?);
+        }
+
+        let sort = params.get("sort").map(|sort| match sort.split_once(':') {
+            Some((key, order)) => (key.to_string(), order == "desc"),
+            None => (sort.clone(), false),
+        });
+        if sort
+            .as_ref()
+            .is_some_and(|(key, _)| key == "relevance_score")
+        {
+            return Err("sorting by relevance needs OpenAlex's search index".to_string());
+        }
+
+        let sample = match params.get("sample") {
+            Some(size) => Some((
+                size.parse()
+                    .map_err(|_| format!("`{size}` is not a sample size"))?,
+                params
+                    .get("seed")
+                    .and_then(|seed| seed.parse().ok())
+                    .unwrap_or(0),
+            )),
+            None => None,
+        };
+
+        Ok(LocalQuery {
+            clauses,
+            search: params.get("search").map(|search| search.to_lowercase()),
+            sort,
+            sample,
+            group_by: params.get("group_by").cloned(),
+            per_page: params
+                .get("per-page")
+                .and_then(|per_page| per_page.parse().ok())
+                .unwrap_or(DEFAULT_PAGE_SIZE),
+        })
+    }
+
+    /// Whether the entity matches the filter and search.
+    pub(crate) fn matches(&self, entity: &Value) -> bool {
+        if !self.clauses.iter().all(|clause| clause.matches(entity)) {
+            return false;
+        }
+
+        match &self.search {
+            None => true,
+            Some(search) => ["display_name", "title"].into_iter().any(|field| {
+                entity
+                    .get(field)
+                    .is_some_and(|v| value_string(v).to_lowercase().contains(search))
+            }),
+        }
+    }
+
+    /// The IDs a filter key must equal, e.g. `["w2741809807"]` for `cites:W2741809807`,
+    /// if the filter requires it. Lets a store narrow its candidates with an index.
+    pub(crate) fn required_ids(&self, key: &str) -> Option<Vec<String>> {
+        self.clauses
+            .iter()
+            .filter(|clause| clause.key == key && !clause.negated)
+            .find_map(|clause| {
+                clause
+                    .conditions
+                    .iter()
+                    .map(|condition| match condition {
+                        Condition::Equals(id) => Some(id.clone()),
+                        _ => None,
+                    })
+                    .collect()
+            })
+    }
+
+    /// Whether every match must be seen before the first page can be returned.
+    pub(crate) fn is_ordered(&self) -> bool {
+        self.sort.is_some() || self.sample.is_some()
+    }
+
+    /// Sorts or samples the matches, each an entity along with its JSON text.
+    pub(crate) fn order(&self, matches: &mut Vec<(Value, String)>) {
+        if let Some((size, seed)) = self.sample {
+            matches.sort_by_cached_key(|(value, _)| {
+                sample_rank(seed, &value.get("id").map(value_string).unwrap_or_default())
+            });
+            matches.truncate(size);
+        }
+
+        if let Some((key, descending)) = &self.sort {
+            let path = filter_path(key);
+            matches.sort_by(|(a, _), (b, _)| {
+                let a = values_at(a, &path).into_iter().next();
+                let b = values_at(b, &path).into_iter().next();
+                let ordering = match (a, b) {
+                    (Some(a), Some(b)) => compare_values(a, b),
+                    (Some(_), None) => return Ordering::Less,
+                    (None, Some(_)) => return Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                };
+                if *descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            });
+        }
+    }
+
+    /// A page of already ordered matches. Cursors are the index of the page's first match.
+    pub(crate) fn page(
+        &self,
+        matches: &[(Value, String)],
+        cursor: Option<&str>,
+        page: Option<usize>,
+    ) -> Result<String, String> {
+        let start = match (cursor, page) {
+            (Some("*") | None, None) => 0,
+            (Some(cursor), _) => cursor
+                .parse::<usize>()
+                .map_err(|_| format!("`{cursor}` is not a cursor from this store"))?,
+            (None, Some(page)) => page.saturating_sub(1) * self.per_page,
+        };
+        let end = (start + self.per_page).min(matches.len());
+        let next_cursor = match cursor {
+            Some(_) if end < matches.len() => Some(end.to_string()),
+            _ => None,
+        };
+
+        Ok(page_body(
+            matches
+                .get(start..end)
+                .unwrap_or_default()
+                .iter()
+                .map(|(_, line)| line.as_str()),
+            matches.len(),
+            page,
+            self.per_page,
+            next_cursor,
+        ))
+    }
+
+    /// The `group_by` response over the matching entities.
+    pub(crate) fn group(&self, group_by: &str, matches: impl IntoIterator<Item = Value>) -> String {
+        let mut counts: HashMap<String, (Option<String>, usize)> = HashMap::new();
+        for entity in matches {
+            let mut keys = values_at(&entity, &filter_path(group_by))
+                .into_iter()
+                .map(|value| match value {
+                    Value::Object(object) => (
+                        object.get("id").map(value_string).unwrap_or_default(),
+                        object.get("display_name").map(value_string),
+                    ),
+                    value => (value_string(value), None),
+                })
+                .collect::<Vec<_>>();
+            keys.sort();
+            keys.dedup();
+            if keys.is_empty() {
+                keys.push(("unknown".to_string(), None));
+            }
+            for (key, display_name) in keys {
+                let entry = counts.entry(key).or_insert((display_name, 0));
+                entry.1 += 1;
+            }
+        }
+
+        let mut buckets = counts.into_iter().collect::<Vec<_>>();
+        buckets.sort_by(|(a_key, (_, a_count)), (b_key, (_, b_count))| {
+            b_count.cmp(a_count).then_with(|| a_key.cmp(b_key))
+        });
+        let group_by = buckets
+            .into_iter()
+            .take(MAX_GROUPS)
+            .map(|(key, (display_name, count))| {
+                json!({
+                    "key": key,
+                    "key_display_name": display_name.unwrap_or_else(|| key.clone()),
+                    "count": count,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "meta": meta(group_by.len(), None, MAX_GROUPS, None),
+            "results": [],
+            "group_by": group_by,
+        })
+        .to_string()
+    }
+}
+
+impl Clause {
+    fn parse(key: &str, value: &str) -> Result<Self, String> {
+        let (negated, value) = match value.strip_prefix('!') {
+            Some(value) => (true, value),
+            None => (false, value),
+        };
+        let alternatives = value.split('|');
+
+        let (path, conditions) = match key {
+            "cited_by" | "related_to" => {
+                return Err(format!(
+                    "the `{key}` filter needs the lists of other entities"
+                ))
+            }
+            "has_doi" => (
+                "doi".to_string(),
+                vec![match value {
+                    "false" => return Clause::parse("doi", "null"),
+                    _ => Condition::NotNull,
+                }],
+            ),
+            "from_publication_date" | "from_created_date" | "from_updated_date" => (
+                key.trim_start_matches("from_").to_string(),
+                alternatives
+                    .map(|v| Condition::AtLeast(v.to_string()))
+                    .collect(),
+            ),
+            "to_publication_date" | "to_created_date" | "to_updated_date" => (
+                key.trim_start_matches("to_").to_string(),
+                alternatives
+                    .map(|v| Condition::AtMost(v.to_string()))
+                    .collect(),
+            ),
+            "default.search" => (
+                "display_name".to_string(),
+                vec![Condition::Contains(value.to_lowercase())],
+            ),
+            _ if key.ends_with(".search") => (
+                filter_path(key.trim_end_matches(".search")),
+                vec![Condition::Contains(value.to_lowercase())],
+            ),
+            _ => (
+                filter_path(key),
+                alternatives
+                    .map(|v| match (v.strip_prefix('>'), v.strip_prefix('<')) {
+                        (Some(v), _) => Condition::AtLeast(v.to_string()),
+                        (_, Some(v)) => Condition::AtMost(v.to_string()),
+                        _ => Condition::Equals(normalize(v)),
+                    })
+                    .collect(),
+            ),
+        };
+
+        Ok(Clause {
+            key: key.to_string(),
+            path,
+            negated,
+            conditions,
+        })
+    }
+
+    fn matches(&self, entity: &Value) -> bool {
+        let values = values_at(entity, &self.path);
+        let matched = self.conditions.iter().any(|condition| match condition {
+            Condition::NotNull => values.iter().any(|v| !v.is_null()),
+            Condition::Equals(expected) if expected == "null" => {
+                values.is_empty() || values.iter().any(|v| v.is_null())
+            }
+            Condition::Equals(expected) => values
+                .iter()
+                .any(|v| &normalize(&value_string(v)) == expected),
+            Condition::Contains(expected) => values
+                .iter()
+                .any(|v| value_string(v).to_lowercase().contains(expected)),
+            // `>` and `<` are exclusive in OpenAlex, `from_` and `to_` dates inclusive;
+            // both are treated as inclusive here.
+            Condition::AtLeast(bound) => values
+                .iter()
+                .any(|v| compare_values(v, &Value::String(bound.clone())) != Ordering::Less),
+            Condition::AtMost(bound) => values
+                .iter()
+                .any(|v| compare_values(v, &Value::String(bound.clone())) != Ordering::Greater),
+        });
+        matched != self.negated
+    }
+}
+
+/// The autocomplete result for an entity whose name contains `q`, which must be lowercase.
+pub(crate) fn autocomplete_result(kind: VertexKind, entity: &Value, q: &str) -> Option<Value> {
+    let display_name = entity
+        .get("display_name")
+        .map(value_string)
+        .unwrap_or_default();
+    if !display_name.to_lowercase().contains(q) {
+        return None;
+    }
+
+    let external_id = ["doi", "orcid", "ror", "issn_l"]
+        .into_iter()
+        .find_map(|field| entity.get(field).filter(|v| !v.is_null()));
+    Some(json!({
+        "id": entity.get("id"),
+        "display_name": display_name,
+        "cited_by_count": entity.get("cited_by_count"),
+        "entity_type": kind.type_name().to_lowercase(),
+        "external_id": external_id,
+        "hint": null,
+        "works_count": entity.get("works_count"),
+    }))
+}
+
+pub(crate) fn autocomplete_body(results: Vec<Value>) -> String {
+    json!({
+        "meta": meta(results.len(), None, MAX_AUTOCOMPLETE_RESULTS, None),
+        "results": results,
+    })
+    .to_string()
+}
+
+/// Where the value a filter key refers to lives in an entity's JSON, when that differs
+/// from the key itself.
+pub(crate) fn filter_path(key: &str) -> String {
+    match key {
+        "openalex" | "openalex_id" | "ids.openalex" => "id",
+        "cites" => "referenced_works",
+        "is_oa" => "open_access.is_oa",
+        "oa_status" => "open_access.oa_status",
+        "author.id" => "authorships.author.id",
+        "author.orcid" => "authorships.author.orcid",
+        "institutions.id" => "authorships.institutions.id",
+        "institutions.ror" => "authorships.institutions.ror",
+        "institutions.country_code" => "authorships.institutions.country_code",
+        "host_organization.id" => "host_organization",
+        "primary_location.source.publisher_lineage" => {
+            "primary_location.source.host_organization_lineage"
+        }
+        key => key,
+    }
+    .to_string()
+}
+
+/// Every value at a dotted path, looking inside arrays along the way.
+pub(crate) fn values_at<'a>(value: &'a Value, path: &str) -> Vec<&'a Value> {
+    let mut values = vec![value];
+    for field in path.split('.') {
+        values = values
+            .into_iter()
+            .flat_map(|value| match value {
+                Value::Array(items) => items.iter().collect::<Vec<_>>(),
+                value => vec![value],
+            })
+            .filter_map(|value| value.get(field))
+            .collect();
+    }
+
+    values
+        .into_iter()
+        .flat_map(|value| match value {
+            Value::Array(items) => items.iter().collect::<Vec<_>>(),
+            // A dehydrated entity stands for its ID, e.g. `parent_publisher`.
+            Value::Object(object) if object.contains_key("id") => vec![&object["id"]],
+            value => vec![value],
+        })
+        .collect()
+}
+
+pub(crate) fn value_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => "null".to_string(),
+        value => value.to_string(),
+    }
+}
+
+/// Compares numerically when both sides are numbers, e.g. `cited_by_count:>100`, and as
+/// strings otherwise, which also orders ISO dates.
+fn compare_values(a: &Value, b: &Value) -> Ordering {
+    let a = value_string(a);
+    let b = value_string(b);
+    match (a.parse::<f64>(), b.parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        _ => a.cmp(&b),
+    }
+}
+
+/// IDs compare equal in any of the forms OpenAlex accepts, e.g. `W2741809807` and
+/// `https://openalex.org/W2741809807`.
+pub(crate) fn normalize(value: &str) -> String {
+    let value = value.to_lowercase();
+    [
+        "https://openalex.org/",
+        "https://doi.org/",
+        "https://orcid.org/",
+        "https://ror.org/",
+    ]
+    .into_iter()
+    .find_map(|prefix| value.strip_prefix(prefix))
+    .unwrap_or(&value)
+    .to_string()
+}
+
+/// A kind of ID other than OpenAlex's that entities can be looked up by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ExternalScheme {
+    Doi,
+    Orcid,
+    Ror,
+    Issn,
+}
+
+/// The kind of external ID `id` is, from its `scheme:` or URL prefix, e.g. `Doi` for both
+/// `doi:10.7717/peerj.4375` and `https://doi.org/10.7717/peerj.4375`.
+pub(crate) fn external_scheme(id: &str) -> Option<ExternalScheme> {
+    [
+        (ExternalScheme::Doi, &["doi:", "https://doi.org/"][..]),
+        (ExternalScheme::Orcid, &["orcid:", "https://orcid.org/"]),
+        (ExternalScheme::Ror, &["ror:", "https://ror.org/"]),
+        (ExternalScheme::Issn, &["issn:"]),
+    ]
+    .into_iter()
+    .find(|(_, prefixes)| prefixes.iter().any(|prefix| id.starts_with(prefix)))
+    .map(|(scheme, _)| scheme)
+}
+
+/// An external ID without its URL or `scheme:` prefix, e.g. `10.7717/peerj.4375` for a DOI.
+pub(crate) fn external_id(id: &str) -> String {
+    let id = normalize(id);
+    ["doi:", "orcid:", "ror:", "issn:"]
+        .into_iter()
+        .find_map(|prefix| id.strip_prefix(prefix))
+        .map(normalize)
+        .unwrap_or(id)
+}
+
+/// A stable pseudo-random rank, so that the same seed always samples the same entities.
+fn sample_rank(seed: u64, id: &str) -> u64 {
+    // FNV-1a, seeded.
+    id.bytes().fold(
+        0xcbf2_9ce4_8422_2325 ^ seed.wrapping_mul(0x9e37_79b9_7f4a_7c15),
+        |hash, byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3),
+    )
+}
+
+fn meta(count: usize, page: Option<usize>, per_page: usize, next_cursor: Option<String>) -> Value {
+    json!({
+        "count": count,
+        "db_response_time_ms": 0,
+        "page": page,
+        "per_page": per_page,
+        "next_cursor": next_cursor,
+    })
+}
+
+/// A page of results, spliced in as the JSON text they're stored as.
+pub(crate) fn page_body<'a>(
+    results: impl Iterator<Item = &'a str>,
+    count: usize,
+    page: Option<usize>,
+    per_page: usize,
+    next_cursor: Option<String>,
+) -> String {
+    let results = results.map(str::trim_end).collect::<Vec<_>>().join(",");
+    format!(
+        r#"{{"meta":{},"results":[{results}]}}"#,
+        meta(count, page, per_page, next_cursor)
+    )
+}
@@ -5,7 +5,10 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use once_cell::sync::Lazy;
-use openalex_adapter::OpenAlexAdapter;
+use openalex_adapter::{
+    validate_query, Backend, Cassette, CassetteBackend, HttpBackend, Mirror, MirrorBackend,
+    OpenAlexAdapter, Snapshot,
+};
 use serde::Deserialize;
 use trustfall::{execute_query, FieldValue, Schema, TransparentValue};
 
@@ -19,15 +22,40 @@ struct InputQuery<'a> {
     args: BTreeMap<Arc<str>, FieldValue>,
 }
 
-fn run_query(path: &str) {
+enum Source {
+    Api,
+    Cassette(Box<Cassette>),
+    Snapshot(Box<Snapshot>),
+    Mirror(Box<Mirror>),
+}
+
+fn run_query(path: &str, source: Source) {
     let content = fs::read_to_string(path).unwrap();
     let input_query: InputQuery = ron::from_str(&content).unwrap();
-    let adapter = Arc::new(OpenAlexAdapter::new());
     let max_results = 20usize;
+    let max_requests = 1000u64;
+    // OpenAlex allows 100,000 requests per day, across every run.
+    let http = HttpBackend::default()
+        .with_max_requests(max_requests)
+        .with_max_daily_requests(100_000, "openalex_usage.json");
+    let backend: Box<dyn Backend> = match source {
+        Source::Api => Box::new(http),
+        Source::Cassette(cassette) => Box::new(CassetteBackend::new(*cassette, http)),
+        Source::Snapshot(snapshot) => snapshot,
+        Source::Mirror(mirror) => Box::new(MirrorBackend::new(*mirror, http)),
+    };
+    let adapter = Arc::new(OpenAlexAdapter::with_backend(backend));
 
     println!("Executing query:");
     println!("{}", input_query.query.trim());
 
+    let query =
+        trustfall_core::frontend::parse(&SCHEMA, input_query.query).expect("not a valid query");
+    if let Err(e) = validate_query(&query.ir_query) {
+        eprintln!("error: {e}");
+        return;
+    }
+
     println!("\nQuery args:");
     println!(
         "{:?}",
@@ -42,14 +70,22 @@ fn run_query(path: &str) {
             .collect::<BTreeMap<_, _>>()
     );
 
-    println!("\nGetting max {max_results} results to avoid exhausting rate limit budgets.");
+    println!(
+        "\nGetting max {max_results} results, sending at most {max_requests} requests, \
+        to avoid exhausting rate limit budgets."
+    );
 
     let mut total_query_duration: Duration = Default::default();
     let mut current_instant = Instant::now();
 
-    for (index, data_item) in execute_query(&SCHEMA, adapter, input_query.query, input_query.args)
-        .expect("not a valid query")
-        .enumerate()
+    for (index, data_item) in execute_query(
+        &SCHEMA,
+        adapter.clone(),
+        input_query.query,
+        input_query.args,
+    )
+    .expect("not a valid query")
+    .enumerate()
     {
         let next_item_duration = current_instant.elapsed();
         total_query_duration += next_item_duration;
@@ -81,6 +117,46 @@ fn run_query(path: &str) {
 
         current_instant = Instant::now()
     }
+
+    println!("\nSent {} requests.", adapter.requests());
+    for diagnostic in adapter.take_diagnostics() {
+        eprintln!("warning: {diagnostic}");
+    }
+}
+
+fn explain_query(path: &str, count_matches: bool) {
+    let content = fs::read_to_string(path).unwrap();
+    let input_query: InputQuery = ron::from_str(&content).unwrap();
+    let adapter = OpenAlexAdapter::new().with_max_daily_requests(100_000, "openalex_usage.json");
+
+    println!("Explaining query:");
+    println!("{}", input_query.query.trim());
+
+    let query =
+        trustfall_core::frontend::parse(&SCHEMA, input_query.query).expect("not a valid query");
+    if let Err(e) = validate_query(&query.ir_query) {
+        eprintln!("error: {e}");
+        return;
+    }
+    println!("\n{}", adapter.explain(&query.ir_query, count_matches));
+    for diagnostic in adapter.take_diagnostics() {
+        eprintln!("warning: {diagnostic}");
+    }
+}
+
+fn sync_mirror(path: &str) {
+    let http = HttpBackend::default().with_max_daily_requests(100_000, "openalex_usage.json");
+    let adapter =
+        OpenAlexAdapter::with_backend(MirrorBackend::new(Mirror::open(path).unwrap(), http));
+
+    println!("Syncing mirror {path}...");
+    let report = adapter.sync_mirror().unwrap();
+    println!(
+        "Checked {} entities, refreshed {} that changed, in {} requests.",
+        report.checked,
+        report.refreshed,
+        adapter.requests()
+    );
 }
 
 fn main() {
@@ -97,8 +173,53 @@ fn main() {
             None => panic!("No filename provided"),
             Some(path) => {
                 println!("{path}");
+                let source = match reversed_args.pop() {
+                    None => Source::Api,
+                    Some("--record") => Source::Cassette(Box::new(
+                        Cassette::record(reversed_args.pop().expect("No cassette file provided"))
+                            .unwrap(),
+                    )),
+                    Some("--replay") => Source::Cassette(Box::new(
+                        Cassette::replay(reversed_args.pop().expect("No cassette file provided"))
+                            .unwrap(),
+                    )),
+                    Some("--snapshot") => Source::Snapshot(Box::new(
+                        Snapshot::open(
+                            reversed_args.pop().expect("No snapshot directory provided"),
+                        )
+                        .unwrap(),
+                    )),
+                    Some("--mirror") => Source::Mirror(Box::new(
+                        Mirror::open(reversed_args.pop().expect("No mirror database provided"))
+                            .unwrap(),
+                    )),
+                    Some("--offline-mirror") => Source::Mirror(Box::new(
+                        Mirror::open(reversed_args.pop().expect("No mirror database provided"))
+                            .unwrap()
+                            .offline(),
+                    )),
+                    Some(flag) => panic!("Unrecognized flag given: {flag}"),
+                };
+                assert!(reversed_args.is_empty());
+                run_query(path, source)
+            }
+        },
+        Some("explain") => match reversed_args.pop() {
+            None => panic!("No filename provided"),
+            Some(path) => match reversed_args.pop() {
+                None => explain_query(path, true),
+                Some("--offline") => {
+                    assert!(reversed_args.is_empty());
+                    explain_query(path, false)
+                }
+                Some(flag) => panic!("Unrecognized flag given: {flag}"),
+            },
+        },
+        Some("sync") => match reversed_args.pop() {
+            None => panic!("No mirror database provided"),
+            Some(path) => {
                 assert!(reversed_args.is_empty());
-                run_query(path)
+                sync_mirror(path)
             }
         },
         Some(cmd) => panic!("Unrecognized command given: {cmd}"),
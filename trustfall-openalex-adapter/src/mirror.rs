@@ -0,0 +1,646 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    path::PathBuf,
+    sync::{Mutex, MutexGuard},
+};
+
+use chrono::Utc;
+use reqwest::Url;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde::de::IgnoredAny;
+use serde_json::Value;
+
+use crate::{
+    backend::{autocomplete_url, entity_url, Backend, ListQuery, Page},
+    fetch::{FetchError, PAGE_SIZE},
+    local::{
+        autocomplete_body, autocomplete_result, external_id, external_scheme, filter_path,
+        normalize, values_at, ExternalScheme, LocalQuery, MAX_AUTOCOMPLETE_RESULTS,
+    },
+    vertex::{FilteredVertices, VertexKind},
+};
+
+// How many IDs to OR together in one `openalex:` filter while syncing a mirror.
+const SYNC_BATCH_SIZE: usize = 50;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS entities (
+        id TEXT PRIMARY KEY,
+        kind TEXT NOT NULL,
+        body TEXT NOT NULL,
+        updated_date TEXT,
+        synced_date TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS entities_kind ON entities (kind, id);
+
+    CREATE TABLE IF NOT EXISTS edges (
+        from_id TEXT NOT NULL REFERENCES entities (id) ON DELETE CASCADE,
+        edge TEXT NOT NULL,
+        to_id TEXT NOT NULL,
+        PRIMARY KEY (from_id, edge, to_id)
+    );
+    CREATE INDEX IF NOT EXISTS edges_to ON edges (edge, to_id);
+";
+
+// External IDs are kept as edges too, so entities can be looked up by them.
+const EXTERNAL_IDS: [&str; 4] = ["doi", "orcid", "ror", "issn"];
+
+/// The filter keys whose values are kept in the `edges` table, so that e.g. `cites:` and
+/// `author.id:` filters only read the works that match instead of every work.
+fn edge_keys(kind: VertexKind) -> &'static [&'static str] {
+    match kind {
+        VertexKind::Work => &[
+            "cites",
+            "author.id",
+            "institutions.id",
+            "primary_location.source.id",
+            "primary_location.source.publisher_lineage",
+            "concepts.id",
+            "grants.funder",
+            "topics.id",
+            "keywords.id",
+            "sustainable_development_goals.id",
+        ],
+        VertexKind::Author => &["last_known_institutions.id", "affiliations.institution.id"],
+        VertexKind::Source => &["host_organization.id"],
+        VertexKind::Institution => &["lineage"],
+        VertexKind::Publisher => &["parent_publisher", "lineage"],
+        VertexKind::Topic => &["subfield.id", "field.id", "domain.id"],
+        VertexKind::Subfield => &["field.id", "domain.id"],
+        VertexKind::Field => &["domain.id"],
+        VertexKind::Concept | VertexKind::Funder | VertexKind::Domain => &[],
+    }
+}
+
+#[derive(Debug)]
+pub enum MirrorError {
+    Sqlite {
+        path: PathBuf,
+        error: rusqlite::Error,
+    },
+    /// A stored body isn't the JSON it should be.
+    Corrupt {
+        url: String,
+        message: String,
+    },
+    NotFound {
+        url: String,
+    },
+    /// A request the mirror can't answer offline, e.g. a filter that needs OpenAlex's search index.
+    Unsupported {
+        url: String,
+        reason: String,
+    },
+}
+
+impl fmt::Display for MirrorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MirrorError::Sqlite { path, error } => {
+                write!(f, "could not use mirror {}: {error}", path.display())
+            }
+            MirrorError::Corrupt { url, message } => {
+                write!(f, "mirrored response for {url} is corrupt: {message}")
+            }
+            MirrorError::NotFound { url } => write!(f, "{url} is not in the mirror"),
+            MirrorError::Unsupported { url, reason } => {
+                write!(f, "the mirror can't answer {url}: {reason}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MirrorError {}
+
+/// What a sync did: how many mirrored entities it checked and how many had changed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    pub checked: usize,
+    pub refreshed: usize,
+}
+
+/// The mirrored entities of one kind, and the date they were all last brought up to date.
+pub(crate) struct StaleEntities {
+    pub(crate) kind: VertexKind,
+    /// An ISO date, e.g. `2024-01-31`.
+    pub(crate) since: String,
+    pub(crate) ids: Vec<String>,
+}
+
+/// A local SQLite copy of the OpenAlex entities fetched so far.
+///
+/// Every entity the API returns is stored, as its JSON body keyed by OpenAlex ID, along with
+/// the IDs it refers to, e.g. the works it cites. Entities already in the mirror are then
+/// served from it. An offline mirror also answers list requests, from the entities it holds,
+/// and never sends requests. [`MirrorBackend::sync`] refreshes entities that changed.
+#[derive(Debug)]
+pub struct Mirror {
+    path: PathBuf,
+    connection: Mutex<Connection>,
+    offline: bool,
+}
+
+impl Mirror {
+    /// Opens the mirror database at `path`, creating it if it doesn't exist.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, MirrorError> {
+        let path = path.into();
+        let sqlite_error = |error| MirrorError::Sqlite {
+            path: path.clone(),
+            error,
+        };
+
+        let connection = Connection::open(&path).map_err(sqlite_error)?;
+        connection
+            .execute_batch(&format!("PRAGMA foreign_keys = ON; {SCHEMA}"))
+            .map_err(sqlite_error)?;
+
+        Ok(Mirror {
+            path,
+            connection: Mutex::new(connection),
+            offline: false,
+        })
+    }
+
+    /// Answers every request from the mirror, including list requests, instead of the API.
+    pub fn offline(mut self) -> Self {
+        self.offline = true;
+        self
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    /// The number of entities in the mirror.
+    pub fn len(&self) -> Result<usize, MirrorError> {
+        self.connection()
+            .query_row("SELECT COUNT(*) FROM entities", [], |row| row.get(0))
+            .map_err(|error| self.sqlite_error(error))
+    }
+
+    pub fn is_empty(&self) -> Result<bool, MirrorError> {
+        Ok(self.len()? == 0)
+    }
+
+    fn connection(&self) -> MutexGuard<'_, Connection> {
+        self.connection
+            .lock()
+            .expect("mirror connection lock was poisoned")
+    }
+
+    fn sqlite_error(&self, error: rusqlite::Error) -> MirrorError {
+        MirrorError::Sqlite {
+            path: self.path.clone(),
+            error,
+        }
+    }
+
+    /// Stores the entities in an API response: a single entity, or a page of a list.
+    /// Returns how many were stored.
+    pub(crate) fn store(&self, url: &str, body: &str) -> Result<usize, MirrorError> {
+        let Ok(parsed) = Url::parse(url) else {
+            return Ok(0);
+        };
+        // Autocomplete results and groups look like entities, but only hold a few fields.
+        if parsed.path().starts_with("/autocomplete")
+            || parsed.query_pairs().any(|(name, _)| name == "group_by")
+        {
+            return Ok(0);
+        }
+
+        // Error responses, like a rate limit page, leave the mirror as it was.
+        let Ok(response) = serde_json::from_str::<Value>(body) else {
+            return Ok(0);
+        };
+        let entities = match response.get("results") {
+            Some(Value::Array(results)) => results.iter().collect(),
+            _ => vec![&response],
+        };
+
+        let today = Utc::now().date_naive().to_string();
+        let mut connection = self.connection();
+        let transaction = connection
+            .transaction()
+            .map_err(|error| self.sqlite_error(error))?;
+        let mut stored = 0;
+        for entity in entities {
+            let Some(id) = entity.get("id").and_then(|id| id.as_str()) else {
+                continue;
+            };
+            let Some(kind) = VertexKind::from_id(id) else {
+                continue;
+            };
+
+            transaction
+                .execute(
+                    "INSERT INTO entities (id, kind, body, updated_date, synced_date)
+                     VALUES (?1, ?2, ?3, ?4, ?5)
+                     ON CONFLICT (id) DO UPDATE SET
+                        body = excluded.body,
+                        updated_date = excluded.updated_date,
+                        synced_date = excluded.synced_date",
+                    params![
+                        id,
+                        kind.endpoint(),
+                        entity.to_string(),
+                        entity.get("updated_date").and_then(|date| date.as_str()),
+                        today,
+                    ],
+                )
+                .map_err(|error| self.sqlite_error(error))?;
+            transaction
+                .execute("DELETE FROM edges WHERE from_id = ?1", params![id])
+                .map_err(|error| self.sqlite_error(error))?;
+
+            let edges = edge_keys(kind)
+                .iter()
+                .flat_map(|key| {
+                    values_at(entity, &filter_path(key))
+                        .into_iter()
+                        .filter_map(|value| value.as_str())
+                        .map(|to_id| (*key, normalize(to_id)))
+                })
+                .chain(EXTERNAL_IDS.into_iter().flat_map(|key| {
+                    values_at(entity, key)
+                        .into_iter()
+                        .filter_map(|value| value.as_str())
+                        .map(move |to_id| (key, external_id(to_id)))
+                }));
+            for (edge, to_id) in edges {
+                transaction
+                    .execute(
+                        "INSERT OR IGNORE INTO edges (from_id, edge, to_id) VALUES (?1, ?2, ?3)",
+                        params![id, edge, to_id],
+                    )
+                    .map_err(|error| self.sqlite_error(error))?;
+            }
+            stored += 1;
+        }
+
+        transaction
+            .commit()
+            .map_err(|error| self.sqlite_error(error))?;
+        Ok(stored)
+    }
+
+    /// Answers an API request with the JSON body the API would have sent, if the mirror can.
+    /// Online, that's only single entities it holds; offline, it's every request, or an error.
+    pub(crate) fn respond(&self, url: &str) -> Result<Option<String>, MirrorError> {
+        let unsupported = |reason: &str| MirrorError::Unsupported {
+            url: url.to_string(),
+            reason: reason.to_string(),
+        };
+        let parsed = Url::parse(url).map_err(|e| unsupported(&e.to_string()))?;
+        let params: HashMap<String, String> = parsed.query_pairs().into_owned().collect();
+
+        let response = if parsed.host_str() == Some("openalex.org") {
+            self.get(url)?
+        } else {
+            let path = parsed.path().trim_start_matches('/');
+            let (endpoint, rest) = match path.split_once('/') {
+                Some((endpoint, rest)) => (endpoint, Some(rest)),
+                None => (path, None),
+            };
+            let kind = VertexKind::ALL
+                .into_iter()
+                .find(|kind| kind.endpoint() == endpoint);
+
+            match (kind, rest) {
+                (None, _) if endpoint == "autocomplete" && self.offline => {
+                    Some(self.autocomplete(rest, &params)?)
+                }
+                (Some(kind), Some(id)) if !id.is_empty() && id != "random" => {
+                    self.get(&self.entity_id(kind, id)?)?
+                }
+                _ if !self.offline => None,
+                (Some(kind), Some("random")) => self.random(kind)?,
+                (Some(kind), _) => Some(self.list(url, kind, &params)?),
+                (None, _) => return Err(unsupported("unknown endpoint")),
+            }
+        };
+
+        match response {
+            None if self.offline => Err(MirrorError::NotFound {
+                url: url.to_string(),
+            }),
+            response => Ok(response),
+        }
+    }
+
+    /// The OpenAlex ID of an entity, from any of the forms of ID the API accepts.
+    fn entity_id(&self, kind: VertexKind, id: &str) -> Result<String, MirrorError> {
+        if let Some(scheme) = external_scheme(id) {
+            let edge = match scheme {
+                ExternalScheme::Doi => "doi",
+                ExternalScheme::Orcid => "orcid",
+                ExternalScheme::Ror => "ror",
+                ExternalScheme::Issn => "issn",
+            };
+            return Ok(self
+                .connection()
+                .query_row(
+                    "SELECT from_id FROM edges WHERE edge = ?1 AND to_id = ?2",
+                    params![edge, external_id(id)],
+                    |row| row.get(0),
+                )
+                .optional()
+                .map_err(|error| self.sqlite_error(error))?
+                .unwrap_or_default());
+        }
+
+        let short_id = id.trim_start_matches("https://openalex.org/");
+        Ok(match short_id.split_once('/') {
+            Some(_) => format!("https://openalex.org/{short_id}"),
+            None if matches!(
+                kind,
+                VertexKind::Subfield | VertexKind::Field | VertexKind::Domain
+            ) =>
+            {
+                format!("https://openalex.org/{}/{short_id}", kind.endpoint())
+            }
+            None => format!("https://openalex.org/{}", short_id.to_uppercase()),
+        })
+    }
+
+    fn get(&self, id: &str) -> Result<Option<String>, MirrorError> {
+        self.connection()
+            .query_row(
+                "SELECT body FROM entities WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|error| self.sqlite_error(error))
+    }
+
+    fn random(&self, kind: VertexKind) -> Result<Option<String>, MirrorError> {
+        self.connection()
+            .query_row(
+                "SELECT body FROM entities WHERE kind = ?1 ORDER BY RANDOM() LIMIT 1",
+                params![kind.endpoint()],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|error| self.sqlite_error(error))
+    }
+
+    /// The bodies of every mirrored entity of a kind, narrowed through the `edges` table
+    /// when the query filters on one of its keys.
+    fn candidates(&self, kind: VertexKind, query: &LocalQuery) -> Result<Vec<String>, MirrorError> {
+        let connection = self.connection();
+        let narrowed = edge_keys(kind)
+            .iter()
+            .find_map(|key| Some((*key, query.required_ids(key)?)));
+
+        let result = match narrowed {
+            Some((edge, ids)) => {
+                let placeholders = vec!["?"; ids.len()].join(", ");
+                let mut statement = connection
+                    .prepare(&format!(
+                        "SELECT DISTINCT e.id, e.body FROM entities e
+                         JOIN edges g ON g.from_id = e.id
+                         WHERE e.kind = ? AND g.edge = ? AND g.to_id IN ({placeholders})
+                         ORDER BY e.id"
+                    ))
+                    .map_err(|error| self.sqlite_error(error))?;
+                let parameters = [kind.endpoint().to_string(), edge.to_string()]
+                    .into_iter()
+                    .chain(ids);
+                let rows = statement
+                    .query_map(params_from_iter(parameters), |row| row.get(1))
+                    .map_err(|error| self.sqlite_error(error))?;
+                rows.collect::<Result<Vec<String>, _>>()
+            }
+            None => {
+                let mut statement = connection
+                    .prepare("SELECT body FROM entities WHERE kind = ?1 ORDER BY id")
+                    .map_err(|error| self.sqlite_error(error))?;
+                let rows = statement
+                    .query_map(params![kind.endpoint()], |row| row.get(0))
+                    .map_err(|error| self.sqlite_error(error))?;
+                rows.collect::<Result<Vec<String>, _>>()
+            }
+        };
+        result.map_err(|error| self.sqlite_error(error))
+    }
+
+    fn list(
+        &self,
+        url: &str,
+        kind: VertexKind,
+        params: &HashMap<String, String>,
+    ) -> Result<String, MirrorError> {
+        let unsupported = |reason: String| MirrorError::Unsupported {
+            url: url.to_string(),
+            reason,
+        };
+        let query = LocalQuery::parse(params).map_err(unsupported)?;
+
+        let mut matches = vec![];
+        for body in self.candidates(kind, &query)? {
+            let entity = self.parse(url, &body)?;
+            if query.matches(&entity) {
+                matches.push((entity, body));
+            }
+        }
+
+        if let Some(group_by) = &query.group_by {
+            return Ok(query.group(group_by, matches.into_iter().map(|(entity, _)| entity)));
+        }
+
+        query.order(&mut matches);
+        let page = params
+            .get("page")
+            .and_then(|page| page.parse::<usize>().ok());
+        query
+            .page(&matches, params.get("cursor").map(|c| c.as_str()), page)
+            .map_err(unsupported)
+    }
+
+    fn autocomplete(
+        &self,
+        entity: Option<&str>,
+        params: &HashMap<String, String>,
+    ) -> Result<String, MirrorError> {
+        let url = format!("autocomplete/{}", entity.unwrap_or_default());
+        let q = params
+            .get("q")
+            .map(|q| q.to_lowercase())
+            .unwrap_or_default();
+        let query =
+            LocalQuery::parse(&HashMap::new()).map_err(|reason| MirrorError::Unsupported {
+                url: url.clone(),
+                reason,
+            })?;
+
+        let mut results = vec![];
+        for kind in VertexKind::ALL
+            .into_iter()
+            .filter(|kind| entity.is_none_or(|entity| entity == kind.endpoint()))
+        {
+            for body in self.candidates(kind, &query)? {
+                if let Some(result) = autocomplete_result(kind, &self.parse(&url, &body)?, &q) {
+                    results.push(result);
+                }
+            }
+        }
+
+        results.truncate(MAX_AUTOCOMPLETE_RESULTS);
+        Ok(autocomplete_body(results))
+    }
+
+    fn parse(&self, url: &str, body: &str) -> Result<Value, MirrorError> {
+        serde_json::from_str(body).map_err(|e| MirrorError::Corrupt {
+            url: url.to_string(),
+            message: e.to_string(),
+        })
+    }
+
+    /// Every mirrored entity, by kind, with the oldest date any of them was synced.
+    pub(crate) fn stale_entities(&self) -> Result<Vec<StaleEntities>, MirrorError> {
+        let connection = self.connection();
+        let mut stale = vec![];
+        for kind in VertexKind::ALL {
+            let since: Option<String> = connection
+                .query_row(
+                    "SELECT MIN(synced_date) FROM entities WHERE kind = ?1",
+                    params![kind.endpoint()],
+                    |row| row.get(0),
+                )
+                .map_err(|error| self.sqlite_error(error))?;
+            let Some(since) = since else {
+                continue;
+            };
+
+            let mut statement = connection
+                .prepare("SELECT id FROM entities WHERE kind = ?1 ORDER BY id")
+                .map_err(|error| self.sqlite_error(error))?;
+            let ids = statement
+                .query_map(params![kind.endpoint()], |row| row.get(0))
+                .map_err(|error| self.sqlite_error(error))?
+                .collect::<Result<Vec<String>, _>>()
+                .map_err(|error| self.sqlite_error(error))?;
+            stale.push(StaleEntities { kind, since, ids });
+        }
+        Ok(stale)
+    }
+
+    /// Records that the given entities are up to date as of today, changed or not.
+    pub(crate) fn mark_synced(&self, ids: &[String]) -> Result<(), MirrorError> {
+        let today = Utc::now().date_naive().to_string();
+        let connection = self.connection();
+        for id in ids {
+            connection
+                .execute(
+                    "UPDATE entities SET synced_date = ?1 WHERE id = ?2",
+                    params![today, id],
+                )
+                .map_err(|error| self.sqlite_error(error))?;
+        }
+        Ok(())
+    }
+}
+
+/// A backend that stores every entity the backend it wraps returns in a mirror, and
+/// answers from the mirror the requests it can.
+#[derive(Debug)]
+pub struct MirrorBackend<B> {
+    mirror: Mirror,
+    inner: B,
+}
+
+impl<B: Backend> MirrorBackend<B> {
+    pub fn new(mirror: Mirror, inner: B) -> Self {
+        MirrorBackend { mirror, inner }
+    }
+
+    pub fn mirror(&self) -> &Mirror {
+        &self.mirror
+    }
+
+    fn respond(
+        &self,
+        url: String,
+        inner: impl FnOnce() -> Result<String, FetchError>,
+    ) -> Result<String, FetchError> {
+        if let Some(body) = self.mirror.respond(&url)? {
+            return Ok(body);
+        }
+
+        let body = inner()?;
+        self.mirror.store(&url, &body)?;
+        Ok(body)
+    }
+
+    /// Refreshes every mirrored entity that changed since it was last synced, asking
+    /// the wrapped backend for the mirrored IDs with a `from_updated_date` filter.
+    pub fn sync(&self) -> Result<SyncReport, FetchError> {
+        let mut report = SyncReport::default();
+        for stale in self.mirror.stale_entities()? {
+            for batch in stale.ids.chunks(SYNC_BATCH_SIZE) {
+                let ids = batch
+                    .iter()
+                    .map(|id| id.trim_start_matches("https://openalex.org/"))
+                    .collect::<Vec<_>>()
+                    .join("|");
+                let mut query = ListQuery::filtered(format!(
+                    "from_updated_date:{},openalex:{ids}",
+                    stale.since
+                ));
+                query.per_page = Some(PAGE_SIZE);
+
+                // Changed entities are stored as their pages arrive.
+                let mut cursor = Some("*".to_string());
+                while let Some(current) = cursor.take() {
+                    query.page = Some(Page::Cursor(current));
+                    let url = query.url(stale.kind);
+                    let body = self.inner.list(stale.kind, &query)?;
+                    self.mirror.store(&url, &body)?;
+
+                    let page = serde_json::from_str::<FilteredVertices<IgnoredAny>>(&body)
+                        .map_err(|error| FetchError::Json { url, error })?;
+                    report.refreshed += page.results.len();
+                    if !page.results.is_empty() {
+                        cursor = page.meta.next_cursor;
+                    }
+                }
+
+                self.mirror.mark_synced(batch)?;
+                report.checked += batch.len();
+            }
+        }
+        Ok(report)
+    }
+}
+
+impl<B: Backend> Backend for MirrorBackend<B> {
+    fn get(&self, kind: VertexKind, id: &str) -> Result<String, FetchError> {
+        self.respond(entity_url(kind, id), || self.inner.get(kind, id))
+    }
+
+    fn list(&self, kind: VertexKind, query: &ListQuery) -> Result<String, FetchError> {
+        self.respond(query.url(kind), || self.inner.list(kind, query))
+    }
+
+    fn group_by(
+        &self,
+        kind: VertexKind,
+        query: &ListQuery,
+        key: &str,
+    ) -> Result<String, FetchError> {
+        self.respond(query.group_url(kind, key), || {
+            self.inner.group_by(kind, query, key)
+        })
+    }
+
+    fn autocomplete(&self, entity: Option<&str>, q: &str) -> Result<String, FetchError> {
+        self.respond(autocomplete_url(entity, q), || {
+            self.inner.autocomplete(entity, q)
+        })
+    }
+
+    fn requests(&self) -> u64 {
+        self.inner.requests()
+    }
+}
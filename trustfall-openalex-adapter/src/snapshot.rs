@@ -0,0 +1,696 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    fs::{self, File},
+    io::{self, BufRead, BufReader, BufWriter, Read},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use flate2::read::MultiGzDecoder;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    backend::{autocomplete_url, entity_url, Backend, ListQuery},
+    fetch::FetchError,
+    local::{
+        autocomplete_body, autocomplete_result, external_id, external_scheme, page_body,
+        ExternalScheme, LocalQuery, MAX_AUTOCOMPLETE_RESULTS,
+    },
+    vertex::VertexKind,
+};
+
+// Written next to the snapshot's `data` directory, and rebuilt whenever the parts change.
+const INDEX_FILE: &str = "trustfall_index.json";
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+    /// A line of a part file, or the index, isn't the JSON it should be.
+    Corrupt {
+        path: PathBuf,
+        message: String,
+    },
+    NotFound {
+        url: String,
+    },
+    /// A request the snapshot can't answer, e.g. a filter that needs OpenAlex's search index.
+    Unsupported {
+        url: String,
+        reason: String,
+    },
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Io { path, error } => {
+                write!(
+                    f,
+                    "could not read snapshot file {}: {error}",
+                    path.display()
+                )
+            }
+            SnapshotError::Corrupt { path, message } => {
+                write!(f, "snapshot file {} is corrupt: {message}", path.display())
+            }
+            SnapshotError::NotFound { url } => write!(f, "{url} is not in the snapshot"),
+            SnapshotError::Unsupported { url, reason } => {
+                write!(f, "the snapshot can't answer {url}: {reason}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+/// One `part_NNN.gz` file of JSON lines, all of a single entity type.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+struct Part {
+    endpoint: String,
+    /// Relative to the snapshot directory.
+    path: PathBuf,
+    len: u64,
+}
+
+/// Where an entity's line starts, as an offset into the decompressed part.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+struct Location {
+    part: u32,
+    offset: u64,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct SnapshotIndex {
+    parts: Vec<Part>,
+    /// Keyed by `{endpoint}/{short ID}`, e.g. `works/W2741809807` or `fields/17`.
+    entities: HashMap<String, Location>,
+    /// External IDs, normalized by `external_id`, to the key of the entity in `entities`.
+    dois: HashMap<String, String>,
+    orcids: HashMap<String, String>,
+    rors: HashMap<String, String>,
+    issns: HashMap<String, String>,
+}
+
+/// The fields of an entity needed to index it.
+#[derive(Deserialize)]
+struct IndexedFields {
+    id: String,
+    doi: Option<String>,
+    orcid: Option<String>,
+    ror: Option<String>,
+    issn: Option<Vec<String>>,
+}
+
+struct PartReader {
+    part: u32,
+    position: u64,
+    lines: BufReader<MultiGzDecoder<File>>,
+}
+
+/// A local copy of the OpenAlex snapshot, answering API requests from disk.
+///
+/// The snapshot is a directory laid out like the one OpenAlex publishes, with gzipped
+/// JSON-lines parts under `data/{endpoint}/updated_date=*/`. Entities are looked up by
+/// OpenAlex ID, DOI, ORCID, ROR or ISSN through an index built on first use; list requests
+/// scan every part of their entity type, so they cost time instead of rate limit budget.
+#[derive(Debug)]
+pub struct Snapshot {
+    dir: PathBuf,
+    index: SnapshotIndex,
+    // The reader of the last lookup, reused when the next one is further into the same part.
+    reader: Mutex<Option<PartReader>>,
+}
+
+impl fmt::Debug for PartReader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PartReader")
+            .field("part", &self.part)
+            .field("position", &self.position)
+            .finish()
+    }
+}
+
+impl Snapshot {
+    /// Opens the snapshot in `dir`, indexing it first if it has no index or its parts changed.
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self, SnapshotError> {
+        let dir = dir.into();
+        let parts = discover_parts(&dir)?;
+
+        let index_path = dir.join(INDEX_FILE);
+        let index = match fs::read_to_string(&index_path) {
+            Ok(content) => serde_json::from_str::<SnapshotIndex>(&content)
+                .ok()
+                .filter(|index| index.parts == parts),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(error) => {
+                return Err(SnapshotError::Io {
+                    path: index_path,
+                    error,
+                })
+            }
+        };
+
+        let mut snapshot = Snapshot {
+            dir,
+            index: index.unwrap_or_default(),
+            reader: Mutex::new(None),
+        };
+        if snapshot.index.parts != parts {
+            snapshot.build_index(parts)?;
+        }
+        Ok(snapshot)
+    }
+
+    /// The number of entities in the snapshot.
+    pub fn len(&self) -> usize {
+        self.index.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.entities.is_empty()
+    }
+
+    fn build_index(&mut self, parts: Vec<Part>) -> Result<(), SnapshotError> {
+        self.index = SnapshotIndex {
+            parts,
+            ..Default::default()
+        };
+
+        for part in 0..self.index.parts.len() as u32 {
+            let path = self.index.parts[part as usize].path.clone();
+            let mut lines = self.open_part(part)?;
+            let mut offset = 0;
+            let mut line = String::new();
+            loop {
+                line.clear();
+                let read = lines
+                    .read_line(&mut line)
+                    .map_err(|error| self.io_error(&path, error))?;
+                if read == 0 {
+                    break;
+                }
+                let location = Location { part, offset };
+                offset += read as u64;
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                let fields: IndexedFields =
+                    serde_json::from_str(&line).map_err(|e| SnapshotError::Corrupt {
+                        path: self.dir.join(&path),
+                        message: format!("line at offset {}: {e}", location.offset),
+                    })?;
+                let Some(key) = entity_key(&fields.id) else {
+                    continue;
+                };
+
+                if let Some(doi) = fields.doi {
+                    self.index.dois.insert(external_id(&doi), key.clone());
+                }
+                if let Some(orcid) = fields.orcid {
+                    self.index.orcids.insert(external_id(&orcid), key.clone());
+                }
+                if let Some(ror) = fields.ror {
+                    self.index.rors.insert(external_id(&ror), key.clone());
+                }
+                for issn in fields.issn.into_iter().flatten() {
+                    self.index.issns.insert(external_id(&issn), key.clone());
+                }
+                self.index.entities.insert(key, location);
+            }
+        }
+
+        let index_path = self.dir.join(INDEX_FILE);
+        let file = File::create(&index_path).map_err(|error| self.io_error(&index_path, error))?;
+        serde_json::to_writer(BufWriter::new(file), &self.index).map_err(|e| {
+            SnapshotError::Corrupt {
+                path: index_path.clone(),
+                message: e.to_string(),
+            }
+        })
+    }
+
+    fn io_error(&self, path: &Path, error: io::Error) -> SnapshotError {
+        SnapshotError::Io {
+            path: self.dir.join(path),
+            error,
+        }
+    }
+
+    fn open_part(&self, part: u32) -> Result<BufReader<MultiGzDecoder<File>>, SnapshotError> {
+        let path = &self.index.parts[part as usize].path;
+        let file = File::open(self.dir.join(path)).map_err(|error| self.io_error(path, error))?;
+        Ok(BufReader::new(MultiGzDecoder::new(file)))
+    }
+
+    /// Reads the line at `location`, skipping ahead in the last part read when possible.
+    fn read_at(&self, location: Location) -> Result<String, SnapshotError> {
+        let mut cached = self
+            .reader
+            .lock()
+            .expect("snapshot reader lock was poisoned");
+        let mut reader = match cached.take() {
+            Some(reader) if reader.part == location.part && reader.position <= location.offset => {
+                reader
+            }
+            _ => PartReader {
+                part: location.part,
+                position: 0,
+                lines: self.open_part(location.part)?,
+            },
+        };
+
+        let path = &self.index.parts[location.part as usize].path;
+        io::copy(
+            &mut (&mut reader.lines).take(location.offset - reader.position),
+            &mut io::sink(),
+        )
+        .map_err(|error| self.io_error(path, error))?;
+        let mut line = String::new();
+        let read = reader
+            .lines
+            .read_line(&mut line)
+            .map_err(|error| self.io_error(path, error))?;
+        reader.position = location.offset + read as u64;
+
+        *cached = Some(reader);
+        Ok(line)
+    }
+
+    /// Every line of an entity type, starting from `from`.
+    fn scan(&self, endpoint: &str, from: Location) -> Scan<'_> {
+        Scan {
+            snapshot: self,
+            parts: (0..self.index.parts.len() as u32)
+                .filter(|part| {
+                    self.index.parts[*part as usize].endpoint == endpoint && *part >= from.part
+                })
+                .collect(),
+            next_part: 0,
+            from,
+            current: None,
+        }
+    }
+
+    /// Answers an OpenAlex API request with the JSON body the API would have sent.
+    pub(crate) fn respond(&self, url: &str) -> Result<String, SnapshotError> {
+        let unsupported = |reason: &str| SnapshotError::Unsupported {
+            url: url.to_string(),
+            reason: reason.to_string(),
+        };
+        let parsed = Url::parse(url).map_err(|e| unsupported(&e.to_string()))?;
+        let params: HashMap<String, String> = parsed.query_pairs().into_owned().collect();
+
+        if parsed.host_str() == Some("openalex.org") {
+            let kind = VertexKind::from_id(url).ok_or_else(|| unsupported("not an entity ID"))?;
+            return self.get(url, kind.endpoint(), url);
+        }
+
+        let path = parsed.path().trim_start_matches('/');
+        let (endpoint, rest) = match path.split_once('/') {
+            Some((endpoint, rest)) => (endpoint, Some(rest)),
+            None => (path, None),
+        };
+        if endpoint == "autocomplete" {
+            return self.autocomplete(rest, &params);
+        }
+        if !VertexKind::ALL
+            .iter()
+            .any(|kind| kind.endpoint() == endpoint)
+        {
+            return Err(unsupported("unknown endpoint"));
+        }
+
+        match rest {
+            None | Some("") => self.list(url, endpoint, &params),
+            Some("random") => self.random(url, endpoint),
+            Some(id) => self.get(url, endpoint, id),
+        }
+    }
+
+    fn get(&self, url: &str, endpoint: &str, id: &str) -> Result<String, SnapshotError> {
+        let key = self.lookup(endpoint, id);
+        match key.and_then(|key| self.index.entities.get(&key)) {
+            Some(location) => self.read_at(*location),
+            None => Err(SnapshotError::NotFound {
+                url: url.to_string(),
+            }),
+        }
+    }
+
+    /// The index key of an entity, from any of the forms of ID the API accepts.
+    fn lookup(&self, endpoint: &str, id: &str) -> Option<String> {
+        if let Some(scheme) = external_scheme(id) {
+            let index = match scheme {
+                ExternalScheme::Doi => &self.index.dois,
+                ExternalScheme::Orcid => &self.index.orcids,
+                ExternalScheme::Ror => &self.index.rors,
+                ExternalScheme::Issn => &self.index.issns,
+            };
+            return index.get(&external_id(id)).cloned();
+        }
+
+        let short_id = id.trim_start_matches("https://openalex.org/");
+        if short_id.contains('/') {
+            Some(short_id.to_string())
+        } else {
+            Some(format!("{endpoint}/{}", short_id.to_uppercase()))
+        }
+    }
+
+    fn random(&self, url: &str, endpoint: &str) -> Result<String, SnapshotError> {
+        let prefix = format!("{endpoint}/");
+        let mut locations = self
+            .index
+            .entities
+            .iter()
+            .filter(|(key, _)| key.starts_with(&prefix))
+            .map(|(_, location)| *location)
+            .collect::<Vec<_>>();
+        if locations.is_empty() {
+            return Err(SnapshotError::NotFound {
+                url: url.to_string(),
+            });
+        }
+
+        locations.sort_by_key(|location| (location.part, location.offset));
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system clock is before the Unix epoch")
+            .subsec_nanos() as usize;
+        self.read_at(locations[nanos % locations.len()])
+    }
+
+    fn list(
+        &self,
+        url: &str,
+        endpoint: &str,
+        params: &HashMap<String, String>,
+    ) -> Result<String, SnapshotError> {
+        let unsupported = |reason: String| SnapshotError::Unsupported {
+            url: url.to_string(),
+            reason,
+        };
+        let query = LocalQuery::parse(params).map_err(unsupported)?;
+
+        if let Some(group_by) = &query.group_by {
+            let mut matches = vec![];
+            for scanned in self.scan(endpoint, Location::default()) {
+                let entity = self.parse(&scanned?)?;
+                if query.matches(&entity) {
+                    matches.push(entity);
+                }
+            }
+            return Ok(query.group(group_by, matches));
+        }
+
+        let page = params
+            .get("page")
+            .and_then(|page| page.parse::<usize>().ok());
+        let cursor = params.get("cursor").map(|cursor| cursor.as_str());
+
+        // Sorted and sampled lists need every match before they can return the first one.
+        if query.is_ordered() {
+            let mut matches = vec![];
+            for scanned in self.scan(endpoint, Location::default()) {
+                let scanned = scanned?;
+                let entity = self.parse(&scanned)?;
+                if query.matches(&entity) {
+                    matches.push((entity, scanned.line));
+                }
+            }
+            query.order(&mut matches);
+            return query.page(&matches, cursor, page).map_err(unsupported);
+        }
+
+        // Otherwise, a cursor is where to resume the scan: `{part}-{offset}-{count}`.
+        let (from, known_count) = match cursor {
+            Some("*") | None => (Location::default(), None),
+            Some(cursor) => parse_cursor(cursor).ok_or_else(|| {
+                unsupported(format!("`{cursor}` is not a cursor from this snapshot"))
+            })?,
+        };
+        let mut skip = match (cursor, page) {
+            (None, Some(page)) => page.saturating_sub(1) * query.per_page,
+            _ => 0,
+        };
+        let mut results = vec![];
+        let mut next = None;
+        // Without a count from the cursor, the scan carries on past the page to count matches.
+        let mut count = 0;
+        for scanned in self.scan(endpoint, from) {
+            let scanned = scanned?;
+            if !query.matches(&self.parse(&scanned)?) {
+                continue;
+            }
+            count += 1;
+            if next.is_some() {
+                continue;
+            }
+            if skip > 0 {
+                skip -= 1;
+                continue;
+            }
+            results.push(scanned.line);
+            if results.len() == query.per_page {
+                next = Some(scanned.next);
+                if known_count.is_some() {
+                    break;
+                }
+            }
+        }
+        let count = known_count.unwrap_or(count);
+
+        let next_cursor = match (cursor, next) {
+            (Some(_), Some(next)) => Some(format!("{}-{}-{count}", next.part, next.offset)),
+            _ => None,
+        };
+        Ok(page_body(
+            results.iter().map(|line| line.as_str()),
+            count,
+            page,
+            query.per_page,
+            next_cursor,
+        ))
+    }
+
+    fn autocomplete(
+        &self,
+        entity: Option<&str>,
+        params: &HashMap<String, String>,
+    ) -> Result<String, SnapshotError> {
+        let q = params
+            .get("q")
+            .map(|q| q.to_lowercase())
+            .unwrap_or_default();
+        let kinds = VertexKind::ALL
+            .into_iter()
+            .filter(|kind| entity.is_none_or(|entity| entity == kind.endpoint()));
+
+        let mut results = vec![];
+        'kinds: for kind in kinds {
+            for scanned in self.scan(kind.endpoint(), Location::default()) {
+                if let Some(result) = autocomplete_result(kind, &self.parse(&scanned?)?, &q) {
+                    results.push(result);
+                }
+                if results.len() == MAX_AUTOCOMPLETE_RESULTS {
+                    break 'kinds;
+                }
+            }
+        }
+
+        Ok(autocomplete_body(results))
+    }
+
+    fn parse(&self, scanned: &Scanned) -> Result<Value, SnapshotError> {
+        serde_json::from_str(&scanned.line).map_err(|e| SnapshotError::Corrupt {
+            path: self
+                .dir
+                .join(&self.index.parts[scanned.location.part as usize].path),
+            message: format!("line at offset {}: {e}", scanned.location.offset),
+        })
+    }
+}
+
+struct Scanned {
+    location: Location,
+    /// Where the line after this one starts.
+    next: Location,
+    line: String,
+}
+
+struct Scan<'a> {
+    snapshot: &'a Snapshot,
+    parts: Vec<u32>,
+    next_part: usize,
+    from: Location,
+    current: Option<PartReader>,
+}
+
+impl Iterator for Scan<'_> {
+    type Item = Result<Scanned, SnapshotError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.current.is_none() {
+                let part = *self.parts.get(self.next_part)?;
+                self.next_part += 1;
+
+                let mut lines = match self.snapshot.open_part(part) {
+                    Ok(lines) => lines,
+                    Err(e) => return Some(Err(e)),
+                };
+                let mut position = 0;
+                if part == self.from.part {
+                    let path = &self.snapshot.index.parts[part as usize].path;
+                    if let Err(error) =
+                        io::copy(&mut (&mut lines).take(self.from.offset), &mut io::sink())
+                    {
+                        return Some(Err(self.snapshot.io_error(path, error)));
+                    }
+                    position = self.from.offset;
+                }
+                self.current = Some(PartReader {
+                    part,
+                    position,
+                    lines,
+                });
+            }
+
+            let reader = self.current.as_mut().expect("no part is being scanned");
+            let mut line = String::new();
+            match reader.lines.read_line(&mut line) {
+                Ok(0) => self.current = None,
+                Ok(read) => {
+                    let location = Location {
+                        part: reader.part,
+                        offset: reader.position,
+                    };
+                    reader.position += read as u64;
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    return Some(Ok(Scanned {
+                        location,
+                        next: Location {
+                            part: reader.part,
+                            offset: reader.position,
+                        },
+                        line,
+                    }));
+                }
+                Err(error) => {
+                    let path = &self.snapshot.index.parts[reader.part as usize].path;
+                    let error = self.snapshot.io_error(path, error);
+                    self.current = None;
+                    return Some(Err(error));
+                }
+            }
+        }
+    }
+}
+
+/// The index key of an OpenAlex ID, e.g. `works/W2741809807` or `fields/17`.
+fn entity_key(id: &str) -> Option<String> {
+    let kind = VertexKind::from_id(id)?;
+    let short_id = id.strip_prefix("https://openalex.org/")?;
+    if short_id.contains('/') {
+        Some(short_id.to_string())
+    } else {
+        Some(format!("{}/{}", kind.endpoint(), short_id.to_uppercase()))
+    }
+}
+
+fn parse_cursor(cursor: &str) -> Option<(Location, Option<usize>)> {
+    let mut fields = cursor.splitn(3, '-');
+    let part = fields.next()?.parse().ok()?;
+    let offset = fields.next()?.parse().ok()?;
+    let count = fields.next()?.parse().ok()?;
+    Some((Location { part, offset }, Some(count)))
+}
+
+/// Every `*.gz` part of every entity type, in a stable order.
+fn discover_parts(dir: &Path) -> Result<Vec<Part>, SnapshotError> {
+    let data_dir = if dir.join("data").is_dir() {
+        dir.join("data")
+    } else {
+        dir.to_path_buf()
+    };
+
+    let mut parts = vec![];
+    for kind in VertexKind::ALL {
+        let mut paths = vec![];
+        collect_parts(&data_dir.join(kind.endpoint()), &mut paths)?;
+        paths.sort();
+
+        for path in paths {
+            let len = fs::metadata(&path)
+                .map_err(|error| SnapshotError::Io {
+                    path: path.clone(),
+                    error,
+                })?
+                .len();
+            parts.push(Part {
+                endpoint: kind.endpoint().to_string(),
+                path: path.strip_prefix(dir).unwrap_or(&path).to_path_buf(),
+                len,
+            });
+        }
+    }
+    Ok(parts)
+}
+
+fn collect_parts(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<(), SnapshotError> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+
+    let entries = fs::read_dir(dir).map_err(|error| SnapshotError::Io {
+        path: dir.to_path_buf(),
+        error,
+    })?;
+    for entry in entries {
+        let path = entry
+            .map_err(|error| SnapshotError::Io {
+                path: dir.to_path_buf(),
+                error,
+            })?
+            .path();
+        if path.is_dir() {
+            collect_parts(&path, paths)?;
+        } else if path.extension().is_some_and(|extension| extension == "gz") {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+impl Backend for Snapshot {
+    fn get(&self, kind: VertexKind, id: &str) -> Result<String, FetchError> {
+        Ok(self.respond(&entity_url(kind, id))?)
+    }
+
+    fn list(&self, kind: VertexKind, query: &ListQuery) -> Result<String, FetchError> {
+        Ok(self.respond(&query.url(kind))?)
+    }
+
+    fn group_by(
+        &self,
+        kind: VertexKind,
+        query: &ListQuery,
+        key: &str,
+    ) -> Result<String, FetchError> {
+        Ok(self.respond(&query.group_url(kind, key))?)
+    }
+
+    fn autocomplete(&self, entity: Option<&str>, q: &str) -> Result<String, FetchError> {
+        Ok(self.respond(&autocomplete_url(entity, q))?)
+    }
+}
@@ -1,8 +1,10 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
-use serde::Deserialize;
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
+use serde_json::Value;
 
-#[derive(Clone, Debug, Deserialize)]
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug)]
 pub enum Vertex {
     Work(Work),
     Author(Author),
@@ -11,8 +13,26 @@ pub enum Vertex {
     Institution(Institution),
     Publisher(Publisher),
     Funder(Funder),
+    Topic(Topic),
+    Subfield(Subfield),
+    Field(Field),
+    Domain(Domain),
+    Sdg(SustainableObject),
+    Keyword(Keyword),
+    YearCount(YearCount),
+    Mesh(Mesh),
+    Role(Role),
+    Affiliation(Affiliation),
+    Collaboration(Collaboration),
+    WorkOverlap(WorkOverlap),
+    Citation(Citation),
+    FilteredCount(FilteredCount),
+    GroupBucket(GroupBucket),
+    AutocompleteResult(AutocompleteResult),
+    JsonValue(Value),
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum VertexKind {
     Work,
     Author,
@@ -21,20 +41,137 @@ pub enum VertexKind {
     Institution,
     Publisher,
     Funder,
+    Topic,
+    Subfield,
+    Field,
+    Domain,
+}
+
+impl VertexKind {
+    pub const ALL: [VertexKind; 11] = [
+        VertexKind::Work,
+        VertexKind::Author,
+        VertexKind::Concept,
+        VertexKind::Source,
+        VertexKind::Institution,
+        VertexKind::Publisher,
+        VertexKind::Funder,
+        VertexKind::Topic,
+        VertexKind::Subfield,
+        VertexKind::Field,
+        VertexKind::Domain,
+    ];
+
+    /// The schema type of vertices of this kind.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            VertexKind::Work => "Work",
+            VertexKind::Author => "Author",
+            VertexKind::Concept => "Concept",
+            VertexKind::Source => "Source",
+            VertexKind::Institution => "Institution",
+            VertexKind::Publisher => "Publisher",
+            VertexKind::Funder => "Funder",
+            VertexKind::Topic => "Topic",
+            VertexKind::Subfield => "Subfield",
+            VertexKind::Field => "Field",
+            VertexKind::Domain => "Domain",
+        }
+    }
+
+    /// The API path segment for this kind, e.g. `works` in `https://api.openalex.org/works`.
+    pub fn endpoint(&self) -> &'static str {
+        match self {
+            VertexKind::Work => "works",
+            VertexKind::Author => "authors",
+            VertexKind::Concept => "concepts",
+            VertexKind::Source => "sources",
+            VertexKind::Institution => "institutions",
+            VertexKind::Publisher => "publishers",
+            VertexKind::Funder => "funders",
+            VertexKind::Topic => "topics",
+            VertexKind::Subfield => "subfields",
+            VertexKind::Field => "fields",
+            VertexKind::Domain => "domains",
+        }
+    }
+
+    /// Works out which kind of entity an OpenAlex ID refers to, e.g. `https://openalex.org/I27837315`
+    /// or `https://openalex.org/fields/17`. Returns `None` for anything that isn't an entity ID.
+    pub fn from_id(id: &str) -> Option<VertexKind> {
+        let short_id = id.strip_prefix("https://openalex.org/")?;
+        if let Some((path, _)) = short_id.split_once('/') {
+            return match path {
+                "subfields" => Some(VertexKind::Subfield),
+                "fields" => Some(VertexKind::Field),
+                "domains" => Some(VertexKind::Domain),
+                _ => None,
+            };
+        }
+
+        match short_id.chars().next()?.to_ascii_uppercase() {
+            'W' => Some(VertexKind::Work),
+            'A' => Some(VertexKind::Author),
+            'C' => Some(VertexKind::Concept),
+            'S' => Some(VertexKind::Source),
+            'I' => Some(VertexKind::Institution),
+            'P' => Some(VertexKind::Publisher),
+            'F' => Some(VertexKind::Funder),
+            'T' => Some(VertexKind::Topic),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct FilteredVertices<T> {
     pub results: Vec<T>,
-    meta: MetaData,
+    pub meta: MetaData,
 }
 
+// The adapter only reads the count and cursor; OpenAlex also sends `db_response_time_ms`,
+// `page` and `per_page`.
 #[derive(Clone, Debug, Deserialize)]
 pub struct MetaData {
-    count: u32,
-    db_response_time_ms: u32,
-    page: u32,
-    per_page: u32,
+    pub count: u32,
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct GroupedVertices {
+    pub group_by: Vec<GroupBucket>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GroupBucket {
+    pub key: String,
+    pub key_display_name: Option<String>,
+    pub count: u32,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AutocompleteResult {
+    pub id: String,
+    #[serde(default, deserialize_with = "lenient")]
+    pub display_name: Option<String>,
+    pub cited_by_count: Option<u32>,
+    pub entity_type: Option<String>,
+    pub external_id: Option<String>,
+    pub hint: Option<String>,
+    pub works_count: Option<u32>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+// The `meta.count` of a list request, without its results.
+#[derive(Clone, Debug, Deserialize)]
+pub struct FilteredCount {
+    pub filter: String,
+    pub count: u32,
 }
 
 impl Vertex {
@@ -47,6 +184,128 @@ impl Vertex {
             Vertex::Institution(..) => "Institution",
             Vertex::Publisher(..) => "Publisher",
             Vertex::Funder(..) => "Funder",
+            Vertex::Topic(..) => "Topic",
+            Vertex::Subfield(..) => "Subfield",
+            Vertex::Field(..) => "Field",
+            Vertex::Domain(..) => "Domain",
+            Vertex::Sdg(..) => "SDG",
+            Vertex::Keyword(..) => "Keyword",
+            Vertex::YearCount(..) => "YearCount",
+            Vertex::Mesh(..) => "Mesh",
+            Vertex::Role(..) => "Role",
+            Vertex::Affiliation(..) => "Affiliation",
+            Vertex::Collaboration(..) => "Collaboration",
+            Vertex::WorkOverlap(..) => "WorkOverlap",
+            Vertex::Citation(..) => "Citation",
+            Vertex::FilteredCount(..) => "FilteredCount",
+            Vertex::GroupBucket(..) => "GroupBucket",
+            Vertex::AutocompleteResult(..) => "AutocompleteResult",
+            Vertex::JsonValue(..) => "JsonValue",
+        }
+    }
+
+    /// Deserializes an entity of `kind`, keeping the JSON it was read from.
+    pub fn from_json(kind: VertexKind, json: Value) -> Result<Vertex, serde_json::Error> {
+        let raw = Arc::new(json);
+        Ok(match kind {
+            VertexKind::Work => Vertex::Work(Work {
+                raw: raw.clone(),
+                ..Work::deserialize(&*raw)?
+            }),
+            VertexKind::Author => Vertex::Author(Author {
+                raw: raw.clone(),
+                ..Author::deserialize(&*raw)?
+            }),
+            VertexKind::Concept => Vertex::Concept(Concept {
+                raw: raw.clone(),
+                ..Concept::deserialize(&*raw)?
+            }),
+            VertexKind::Source => Vertex::Source(Source {
+                raw: raw.clone(),
+                ..Source::deserialize(&*raw)?
+            }),
+            VertexKind::Institution => Vertex::Institution(Institution {
+                raw: raw.clone(),
+                ..Institution::deserialize(&*raw)?
+            }),
+            VertexKind::Publisher => Vertex::Publisher(Publisher {
+                raw: raw.clone(),
+                ..Publisher::deserialize(&*raw)?
+            }),
+            VertexKind::Funder => Vertex::Funder(Funder {
+                raw: raw.clone(),
+                ..Funder::deserialize(&*raw)?
+            }),
+            VertexKind::Topic => Vertex::Topic(Topic {
+                raw: raw.clone(),
+                ..Topic::deserialize(&*raw)?
+            }),
+            VertexKind::Subfield => Vertex::Subfield(Subfield {
+                raw: raw.clone(),
+                ..Subfield::deserialize(&*raw)?
+            }),
+            VertexKind::Field => Vertex::Field(Field {
+                raw: raw.clone(),
+                ..Field::deserialize(&*raw)?
+            }),
+            VertexKind::Domain => Vertex::Domain(Domain {
+                raw: raw.clone(),
+                ..Domain::deserialize(&*raw)?
+            }),
+        })
+    }
+
+    /// The JSON this vertex was read from. Entities keep their API response as it was sent;
+    /// objects nested in one are written back out from their fields, unrecognized ones
+    /// included. Vertices the adapter derives itself have none.
+    pub fn raw_json(&self) -> Option<Arc<Value>> {
+        let raw = match self {
+            Vertex::Work(work) => work.raw.clone(),
+            Vertex::Author(author) => author.raw.clone(),
+            Vertex::Concept(concept) => concept.raw.clone(),
+            Vertex::Source(source) => source.raw.clone(),
+            Vertex::Institution(institution) => institution.raw.clone(),
+            Vertex::Publisher(publisher) => publisher.raw.clone(),
+            Vertex::Funder(funder) => funder.raw.clone(),
+            Vertex::Topic(topic) => topic.raw.clone(),
+            Vertex::Subfield(subfield) => subfield.raw.clone(),
+            Vertex::Field(field) => field.raw.clone(),
+            Vertex::Domain(domain) => domain.raw.clone(),
+            Vertex::Sdg(sdg) => Arc::new(serde_json::to_value(sdg).ok()?),
+            Vertex::Keyword(keyword) => Arc::new(serde_json::to_value(keyword).ok()?),
+            Vertex::YearCount(year_count) => Arc::new(serde_json::to_value(year_count).ok()?),
+            Vertex::Mesh(mesh) => Arc::new(serde_json::to_value(mesh).ok()?),
+            Vertex::Role(role) => Arc::new(serde_json::to_value(role).ok()?),
+            Vertex::Affiliation(affiliation) => Arc::new(serde_json::to_value(affiliation).ok()?),
+            Vertex::GroupBucket(bucket) => Arc::new(serde_json::to_value(bucket).ok()?),
+            Vertex::AutocompleteResult(result) => Arc::new(serde_json::to_value(result).ok()?),
+            Vertex::JsonValue(value) => Arc::new(value.clone()),
+            Vertex::Collaboration(..)
+            | Vertex::WorkOverlap(..)
+            | Vertex::Citation(..)
+            | Vertex::FilteredCount(..) => return None,
+        };
+
+        // An entity built some other way than from an API response has none.
+        (!raw.is_null()).then_some(raw)
+    }
+
+    /// The properties shared by works, authors, sources, concepts, institutions, publishers
+    /// and funders.
+    pub fn as_object(&self) -> Option<&OpenAlexObject> {
+        match self {
+            Vertex::Work(work) => Some(&work.object),
+            Vertex::Author(author) => Some(&author.object),
+            Vertex::Concept(concept) => Some(&concept.object),
+            Vertex::Source(source) => Some(&source.object),
+            Vertex::Institution(institution) => Some(&institution.object),
+            Vertex::Publisher(publisher) => Some(&publisher.object),
+            Vertex::Funder(funder) => Some(&funder.object),
+            Vertex::Topic(topic) => Some(&topic.object),
+            Vertex::Subfield(subfield) => Some(&subfield.object),
+            Vertex::Field(field) => Some(&field.object),
+            Vertex::Domain(domain) => Some(&domain.object),
+            _ => None,
         }
     }
 
@@ -98,20 +357,165 @@ impl Vertex {
             _ => None,
         }
     }
+
+    pub fn as_topic(&self) -> Option<&Topic> {
+        match self {
+            Vertex::Topic(topic) => Some(topic),
+            _ => None,
+        }
+    }
+
+    pub fn as_subfield(&self) -> Option<&Subfield> {
+        match self {
+            Vertex::Subfield(subfield) => Some(subfield),
+            _ => None,
+        }
+    }
+
+    pub fn as_field(&self) -> Option<&Field> {
+        match self {
+            Vertex::Field(field) => Some(field),
+            _ => None,
+        }
+    }
+
+    pub fn as_domain(&self) -> Option<&Domain> {
+        match self {
+            Vertex::Domain(domain) => Some(domain),
+            _ => None,
+        }
+    }
+
+    pub fn as_sdg(&self) -> Option<&SustainableObject> {
+        match self {
+            Vertex::Sdg(sdg) => Some(sdg),
+            _ => None,
+        }
+    }
+
+    pub fn as_keyword(&self) -> Option<&Keyword> {
+        match self {
+            Vertex::Keyword(keyword) => Some(keyword),
+            _ => None,
+        }
+    }
+
+    pub fn as_year_count(&self) -> Option<&YearCount> {
+        match self {
+            Vertex::YearCount(year_count) => Some(year_count),
+            _ => None,
+        }
+    }
+
+    pub fn as_mesh(&self) -> Option<&Mesh> {
+        match self {
+            Vertex::Mesh(mesh) => Some(mesh),
+            _ => None,
+        }
+    }
+
+    pub fn as_role(&self) -> Option<&Role> {
+        match self {
+            Vertex::Role(role) => Some(role),
+            _ => None,
+        }
+    }
+
+    pub fn as_affiliation(&self) -> Option<&Affiliation> {
+        match self {
+            Vertex::Affiliation(affiliation) => Some(affiliation),
+            _ => None,
+        }
+    }
+
+    pub fn as_collaboration(&self) -> Option<&Collaboration> {
+        match self {
+            Vertex::Collaboration(collaboration) => Some(collaboration),
+            _ => None,
+        }
+    }
+
+    pub fn as_work_overlap(&self) -> Option<&WorkOverlap> {
+        match self {
+            Vertex::WorkOverlap(overlap) => Some(overlap),
+            _ => None,
+        }
+    }
+
+    pub fn as_citation(&self) -> Option<&Citation> {
+        match self {
+            Vertex::Citation(citation) => Some(citation),
+            _ => None,
+        }
+    }
+
+    pub fn as_filtered_count(&self) -> Option<&FilteredCount> {
+        match self {
+            Vertex::FilteredCount(count) => Some(count),
+            _ => None,
+        }
+    }
+
+    pub fn as_group_bucket(&self) -> Option<&GroupBucket> {
+        match self {
+            Vertex::GroupBucket(bucket) => Some(bucket),
+            _ => None,
+        }
+    }
+
+    pub fn as_autocomplete_result(&self) -> Option<&AutocompleteResult> {
+        match self {
+            Vertex::AutocompleteResult(result) => Some(result),
+            _ => None,
+        }
+    }
+
+    pub fn as_json_value(&self) -> Option<&Value> {
+        match self {
+            Vertex::JsonValue(value) => Some(value),
+            _ => None,
+        }
+    }
 }
+/// Reads a field OpenAlex has been known to send as null, drop or change the type of, as
+/// `None` instead of failing the whole response.
+fn lenient<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let value = Value::deserialize(deserializer)?;
+    Ok(T::deserialize(value).ok())
+}
+
+/// Reads a collection or nested object like `lenient`, as its empty value instead of `None`.
+fn lenient_or_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned + Default,
+{
+    Ok(lenient(deserializer)?.unwrap_or_default())
+}
+
 // Basic object with properties shared by all OpenAlex entities
 #[derive(Clone, Debug, Deserialize)]
 pub struct OpenAlexObject {
-    pub cited_by_count: u32,
+    #[serde(default, deserialize_with = "lenient")]
+    pub cited_by_count: Option<u32>,
+    #[serde(default, deserialize_with = "lenient_or_default")]
     pub counts_by_year: Vec<YearCount>,
-    pub created_date: String,
-    pub display_name: String,
+    #[serde(default, deserialize_with = "lenient")]
+    pub created_date: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub display_name: Option<String>,
     pub id: String,
+    #[serde(default, deserialize_with = "lenient_or_default")]
     pub ids: IDObject, // Fix
-    pub updated_date: String,
+    #[serde(default, deserialize_with = "lenient")]
+    pub updated_date: Option<String>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize)]
 pub struct IDObject {
     pub doi: Option<String>,
     pub mag: Option<String>,
@@ -136,46 +540,83 @@ pub struct Work {
     pub object: OpenAlexObject,
 
     pub abstract_inverted_index: Option<HashMap<String, Vec<u32>>>,
+    #[serde(default, deserialize_with = "lenient_or_default")]
     pub authorships: Vec<Authorship>,
     pub apc_list: Option<Payment>,
     pub apc_paid: Option<Payment>,
     pub best_oa_location: Option<Location>,
     pub biblio: Option<Biblio>,
-    pub cited_by_api_url: String,
+    #[serde(default, deserialize_with = "lenient")]
+    pub cited_by_api_url: Option<String>,
+    #[serde(default, deserialize_with = "lenient_or_default")]
     pub concepts: Vec<DehydratedConcept>,
     pub corresponding_author_ids: Option<Vec<String>>,
     pub corresponding_institution_ids: Option<Vec<String>>,
     pub doi: Option<String>,
+    #[serde(default, deserialize_with = "lenient_or_default")]
     pub grants: Vec<Grant>,
     pub institutions_distinct_count: Option<u32>,
     pub is_oa: Option<bool>,
     pub is_paratext: Option<bool>,
     pub is_retracted: Option<bool>,
+    pub keywords: Option<Vec<Keyword>>,
     pub language: Option<String>,
     pub license: Option<String>,
+    #[serde(default, deserialize_with = "lenient_or_default")]
     pub locations: Vec<Location>,
     pub locations_count: Option<u32>,
+    #[serde(default, deserialize_with = "lenient_or_default")]
     pub mesh: Vec<Mesh>,
     pub ngrams_url: Option<String>,
     pub open_access: Option<OpenAccess>,
     pub primary_location: Option<Location>,
+    pub primary_topic: Option<DehydratedTopic>,
     pub publication_date: Option<String>,
     pub publication_year: Option<u32>,
+    #[serde(default, deserialize_with = "lenient_or_default")]
     pub referenced_works: Vec<String>,
+    #[serde(default, deserialize_with = "lenient_or_default")]
     pub related_works: Vec<String>,
+    #[serde(default, deserialize_with = "lenient_or_default")]
     pub sustainable_development_goals: Vec<SustainableObject>,
     pub title: Option<String>,
+    pub topics: Option<Vec<DehydratedTopic>>,
 
     #[serde(rename(deserialize = "type"))]
     pub ttype: Option<String>,
 
     pub type_crossref: Option<String>,
+
+    /// The response this entity was read from, including fields not modelled above; see
+    /// `Vertex::raw_json`.
+    #[serde(skip)]
+    pub raw: Arc<Value>,
+}
+
+// Derived from citation data: a work related to another through shared references
+// (bibliographic coupling) or shared citing works (co-citation).
+#[derive(Clone, Debug)]
+pub struct WorkOverlap {
+    pub work_id: String,
+    pub strength: u32,
+}
+
+// Derived: a single citation from `citing` to `cited`, with its self-citation flags.
+// Every citation of a work shares that one cited work.
+#[derive(Clone, Debug)]
+pub struct Citation {
+    pub cited: Arc<Work>,
+    pub citing: Box<Work>,
+    pub is_author_self_citation: bool,
+    pub is_institution_self_citation: bool,
+    pub is_source_self_citation: bool,
 }
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct Authorship {
     pub author_position: Option<String>,
     pub author: DehydratedAuthor,
+    #[serde(default, deserialize_with = "lenient_or_default")]
     pub institutions: Vec<DehydratedInstitution>,
     pub countries: Option<Vec<String>>,
     pub is_corresponding: Option<bool>,
@@ -198,17 +639,24 @@ pub struct Biblio {
     pub last_page: Option<String>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct YearCount {
-    pub year: u32,
+    #[serde(default, deserialize_with = "lenient")]
+    pub year: Option<u32>,
     pub works_count: Option<u32>,
-    pub cited_by_count: u32,
+    #[serde(default, deserialize_with = "lenient")]
+    pub cited_by_count: Option<u32>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct Grant {
-    pub funder: String,
-    pub funder_display_name: String,
+    #[serde(default, deserialize_with = "lenient")]
+    pub funder: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub funder_display_name: Option<String>,
     pub award_id: Option<String>,
 }
 
@@ -224,13 +672,16 @@ pub struct Location {
     pub version: Option<String>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Mesh {
     pub descriptor_ui: Option<String>,
     pub descriptor_name: Option<String>,
     pub qualifier_ui: Option<String>,
     pub qualifier_name: Option<String>,
     pub is_major_topic: Option<bool>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -241,11 +692,26 @@ pub struct OpenAccess {
     pub any_repository_has_fulltext: Option<bool>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct SustainableObject {
-    id: Option<String>,
-    display_name: Option<String>,
-    score: Option<f64>,
+    pub id: Option<String>,
+    pub display_name: Option<String>,
+    pub score: Option<f64>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Keyword {
+    pub id: Option<String>,
+    pub display_name: Option<String>,
+    // Older payloads carry the keyword text here instead of in `display_name`.
+    pub keyword: Option<String>,
+    pub score: Option<f64>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
 // OpenAlexAuthor structs
@@ -254,17 +720,36 @@ pub struct Author {
     #[serde(flatten)]
     pub object: OpenAlexObject,
 
+    pub affiliations: Option<Vec<Affiliation>>,
     pub display_name_alternatives: Option<Vec<String>>,
     pub last_known_institution: Option<DehydratedInstitution>,
+    pub last_known_institutions: Option<Vec<DehydratedInstitution>>,
     pub orcid: Option<String>,
     pub summary_stats: Option<SummaryStats>,
-    pub works_api_url: String,
+    pub topics: Option<Vec<DehydratedTopic>>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub works_api_url: Option<String>,
     pub works_count: Option<u32>,
     // x_concepts?
+    /// The response this entity was read from, including fields not modelled above; see
+    /// `Vertex::raw_json`.
+    #[serde(skip)]
+    pub raw: Arc<Value>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Affiliation {
+    pub institution: DehydratedInstitution,
+    #[serde(default, deserialize_with = "lenient_or_default")]
+    pub years: Vec<u32>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct SummaryStats {
+    #[serde(rename(deserialize = "2yr_mean_citedness"))]
     pub two_year_mean_citedness: Option<f64>,
     pub h_index: Option<u32>,
     pub i10_index: Option<u32>,
@@ -273,13 +758,24 @@ pub struct SummaryStats {
 #[derive(Clone, Debug, Deserialize)]
 pub struct DehydratedAuthor {
     pub id: String,
-    pub display_name: String,
+    #[serde(default, deserialize_with = "lenient")]
+    pub display_name: Option<String>,
     pub orcid: Option<String>,
 }
 
+// Derived from an author's works rather than returned by the API.
+#[derive(Clone, Debug)]
+pub struct Collaboration {
+    pub coauthor: DehydratedAuthor,
+    pub shared_works_count: u32,
+    pub first_year: Option<u32>,
+    pub last_year: Option<u32>,
+}
+
 // Concept structs
 #[derive(Clone, Debug, Deserialize)]
 pub struct Concept {
+    #[serde(default, deserialize_with = "lenient_or_default")]
     pub ancestors: Vec<DehydratedConcept>,
     #[serde(flatten)]
     pub object: OpenAlexObject,
@@ -288,19 +784,28 @@ pub struct Concept {
     pub image_thumbnail_url: Option<String>,
     pub image_url: Option<String>,
     pub level: Option<u32>,
+    #[serde(default, deserialize_with = "lenient_or_default")]
     pub related_concepts: Vec<DehydratedConcept>,
     pub summary_stats: Option<SummaryStats>,
     pub wikidata: Option<String>,
-    pub works_api_url: String,
+    #[serde(default, deserialize_with = "lenient")]
+    pub works_api_url: Option<String>,
     pub works_count: Option<u32>,
+
+    /// The response this entity was read from, including fields not modelled above; see
+    /// `Vertex::raw_json`.
+    #[serde(skip)]
+    pub raw: Arc<Value>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct DehydratedConcept {
     pub id: String,
     pub wikidata: Option<String>,
-    pub display_name: String,
-    pub level: u32,
+    #[serde(default, deserialize_with = "lenient")]
+    pub display_name: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub level: Option<u32>,
     pub score: Option<f64>, // Fix
 }
 
@@ -317,39 +822,53 @@ pub struct Source {
     pub country_code: Option<String>,
     pub homepage_url: Option<String>,
     pub host_organization: Option<String>,
+    #[serde(default, deserialize_with = "lenient_or_default")]
     pub host_organization_lineage: Vec<String>,
     pub host_organization_name: Option<String>,
     pub is_in_doaj: Option<bool>,
     pub issn_l: Option<String>,
     pub issn: Option<Vec<String>>,
     pub is_oa: Option<bool>,
+    #[serde(default, deserialize_with = "lenient_or_default")]
     pub societies: Vec<Society>,
     pub summary_stats: Option<SummaryStats>,
+    pub topics: Option<Vec<DehydratedTopic>>,
 
     #[serde(rename(deserialize = "type"))]
     pub ttype: Option<String>,
 
-    pub works_api_url: String,
+    #[serde(default, deserialize_with = "lenient")]
+    pub works_api_url: Option<String>,
     pub works_count: Option<u32>,
+
+    /// The response this entity was read from, including fields not modelled above; see
+    /// `Vertex::raw_json`.
+    #[serde(skip)]
+    pub raw: Arc<Value>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct Price {
-    pub price: u32,
-    pub currency: String,
+    #[serde(default, deserialize_with = "lenient")]
+    pub price: Option<u32>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub currency: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct Society {
-    pub url: String,
-    pub organization: String,
+    #[serde(default, deserialize_with = "lenient")]
+    pub url: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub organization: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct DehydratedSource {
     // Fix
     pub id: String,
-    pub display_name: String,
+    #[serde(default, deserialize_with = "lenient")]
+    pub display_name: Option<String>,
     pub issn_l: Option<String>,
     pub issn: Option<Vec<String>>,
     pub host_organization: Option<String>,
@@ -364,6 +883,7 @@ pub struct Institution {
     #[serde(flatten)]
     pub object: OpenAlexObject,
 
+    #[serde(default, deserialize_with = "lenient_or_default")]
     pub associated_institutions: Vec<DehydratedInstitution>,
     pub display_name_alternatives: Option<Vec<String>>,
     pub display_name_acronyms: Option<Vec<String>>,
@@ -372,7 +892,10 @@ pub struct Institution {
     pub homepage_url: Option<String>,
     pub image_thumbnail_url: Option<String>,
     pub image_url: Option<String>,
+    pub lineage: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "lenient_or_default")]
     pub repositories: Vec<DehydratedSource>,
+    #[serde(default, deserialize_with = "lenient_or_default")]
     pub roles: Vec<Role>,
     pub ror: Option<String>,
     pub summary_stats: Option<SummaryStats>,
@@ -380,27 +903,45 @@ pub struct Institution {
     #[serde(rename(deserialize = "type"))]
     pub ttype: Option<String>,
 
-    pub works_api_url: String,
+    #[serde(default, deserialize_with = "lenient")]
+    pub works_api_url: Option<String>,
     pub works_count: Option<u32>,
+
+    /// The response this entity was read from, including fields not modelled above; see
+    /// `Vertex::raw_json`.
+    #[serde(skip)]
+    pub raw: Arc<Value>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct DehydratedInstitution {
     // Fix
     pub id: String,
-    pub display_name: String,
+    #[serde(default, deserialize_with = "lenient")]
+    pub display_name: Option<String>,
     pub ror: Option<String>,
     pub country_code: Option<String>,
+    pub lineage: Option<Vec<String>>,
+    // Only set on `associated_institutions`: "parent", "child" or "related".
+    pub relationship: Option<String>,
 
-    #[serde(rename(deserialize = "type"))]
+    #[serde(rename = "type")]
     pub ttype: Option<String>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Role {
-    pub role: String,
+    #[serde(default, deserialize_with = "lenient")]
+    pub role: Option<String>,
     pub id: String,
-    pub works_count: u32,
+    #[serde(default, deserialize_with = "lenient")]
+    pub works_count: Option<u32>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -425,12 +966,27 @@ pub struct Publisher {
     pub hierarchy_level: Option<u32>,
     pub image_thumbnail_url: Option<String>,
     pub image_url: Option<String>,
+    #[serde(default, deserialize_with = "lenient_or_default")]
     pub lineage: Vec<String>,
-    pub parent_publisher: Option<String>,
+    pub parent_publisher: Option<DehydratedPublisher>,
+    #[serde(default, deserialize_with = "lenient_or_default")]
     pub roles: Vec<Role>,
-    pub sources_api_url: String,
+    #[serde(default, deserialize_with = "lenient")]
+    pub sources_api_url: Option<String>,
     pub summary_stats: Option<SummaryStats>,
     pub works_count: Option<u32>,
+
+    /// The response this entity was read from, including fields not modelled above; see
+    /// `Vertex::raw_json`.
+    #[serde(skip)]
+    pub raw: Arc<Value>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct DehydratedPublisher {
+    pub id: String,
+    #[serde(default, deserialize_with = "lenient")]
+    pub display_name: Option<String>,
 }
 
 // Funder structs
@@ -446,7 +1002,113 @@ pub struct Funder {
     pub homepage_url: Option<String>,
     pub image_thumbnail_url: Option<String>,
     pub image_url: Option<String>,
+    #[serde(default, deserialize_with = "lenient_or_default")]
     pub roles: Vec<Role>,
     pub summary_stats: Option<SummaryStats>,
     pub works_count: Option<u32>,
+
+    /// The response this entity was read from, including fields not modelled above; see
+    /// `Vertex::raw_json`.
+    #[serde(skip)]
+    pub raw: Arc<Value>,
+}
+
+// Topic hierarchy structs (domain -> field -> subfield -> topic)
+#[derive(Clone, Debug, Deserialize)]
+pub struct Topic {
+    #[serde(flatten)]
+    pub object: OpenAlexObject,
+
+    pub description: Option<String>,
+    pub domain: Option<DehydratedTopicLevel>,
+    pub field: Option<DehydratedTopicLevel>,
+    pub keywords: Option<Vec<String>>,
+    pub subfield: Option<DehydratedTopicLevel>,
+    pub works_api_url: Option<String>,
+    pub works_count: Option<u32>,
+
+    // Only present when the topic was reached through a work, author or source.
+    pub score: Option<f64>,
+    pub count: Option<u32>,
+
+    /// The response this entity was read from, including fields not modelled above; see
+    /// `Vertex::raw_json`.
+    #[serde(skip)]
+    pub raw: Arc<Value>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Subfield {
+    #[serde(flatten)]
+    pub object: OpenAlexObject,
+
+    pub description: Option<String>,
+    pub display_name_alternatives: Option<Vec<String>>,
+    pub domain: Option<DehydratedTopicLevel>,
+    pub field: Option<DehydratedTopicLevel>,
+    #[serde(default, deserialize_with = "lenient_or_default")]
+    pub topics: Vec<DehydratedTopicLevel>,
+    pub works_api_url: Option<String>,
+    pub works_count: Option<u32>,
+
+    /// The response this entity was read from, including fields not modelled above; see
+    /// `Vertex::raw_json`.
+    #[serde(skip)]
+    pub raw: Arc<Value>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Field {
+    #[serde(flatten)]
+    pub object: OpenAlexObject,
+
+    pub description: Option<String>,
+    pub display_name_alternatives: Option<Vec<String>>,
+    pub domain: Option<DehydratedTopicLevel>,
+    #[serde(default, deserialize_with = "lenient_or_default")]
+    pub subfields: Vec<DehydratedTopicLevel>,
+    pub works_api_url: Option<String>,
+    pub works_count: Option<u32>,
+
+    /// The response this entity was read from, including fields not modelled above; see
+    /// `Vertex::raw_json`.
+    #[serde(skip)]
+    pub raw: Arc<Value>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Domain {
+    #[serde(flatten)]
+    pub object: OpenAlexObject,
+
+    pub description: Option<String>,
+    pub display_name_alternatives: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "lenient_or_default")]
+    pub fields: Vec<DehydratedTopicLevel>,
+    pub works_api_url: Option<String>,
+    pub works_count: Option<u32>,
+
+    /// The response this entity was read from, including fields not modelled above; see
+    /// `Vertex::raw_json`.
+    #[serde(skip)]
+    pub raw: Arc<Value>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct DehydratedTopic {
+    pub id: String,
+    #[serde(default, deserialize_with = "lenient")]
+    pub display_name: Option<String>,
+    pub count: Option<u32>,
+    pub domain: Option<DehydratedTopicLevel>,
+    pub field: Option<DehydratedTopicLevel>,
+    pub score: Option<f64>,
+    pub subfield: Option<DehydratedTopicLevel>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct DehydratedTopicLevel {
+    pub id: String,
+    #[serde(default, deserialize_with = "lenient")]
+    pub display_name: Option<String>,
 }
@@ -0,0 +1,329 @@
+//! The behavior every `Backend` must share. Each backend here serves the fixture corpus,
+//! directly or through layers, and must answer the same requests the same way.
+
+mod support;
+
+use std::collections::BTreeSet;
+
+use openalex_adapter::{
+    Backend, BudgetError, Cassette, CassetteBackend, FetchError, ListQuery, Mirror, MirrorBackend,
+    Page, VertexKind,
+};
+use serde_json::Value;
+
+use support::{fixture_ids, fixtures, snapshot};
+
+/// Stands in for the API under layers that must not need it, refusing every request.
+#[derive(Debug)]
+struct NoRequests;
+
+impl NoRequests {
+    fn refuse() -> Result<String, FetchError> {
+        Err(FetchError::Budget(BudgetError::MaxRequests { limit: 0 }))
+    }
+}
+
+impl Backend for NoRequests {
+    fn get(&self, _kind: VertexKind, _id: &str) -> Result<String, FetchError> {
+        Self::refuse()
+    }
+
+    fn list(&self, _kind: VertexKind, _query: &ListQuery) -> Result<String, FetchError> {
+        Self::refuse()
+    }
+
+    fn group_by(
+        &self,
+        _kind: VertexKind,
+        _query: &ListQuery,
+        _key: &str,
+    ) -> Result<String, FetchError> {
+        Self::refuse()
+    }
+
+    fn autocomplete(&self, _entity: Option<&str>, _q: &str) -> Result<String, FetchError> {
+        Self::refuse()
+    }
+}
+
+fn json(body: Result<String, FetchError>) -> Value {
+    serde_json::from_str(&body.unwrap()).unwrap()
+}
+
+fn result_ids(page: &Value) -> Vec<String> {
+    page["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entity| entity["id"].as_str().unwrap().to_string())
+        .collect()
+}
+
+fn work_ids(short_ids: &[&str]) -> BTreeSet<String> {
+    short_ids
+        .iter()
+        .map(|id| format!("https://openalex.org/{id}"))
+        .collect()
+}
+
+/// Every page of a list, following cursors `per_page` results at a time.
+fn list_all(
+    backend: &dyn Backend,
+    kind: VertexKind,
+    query: &ListQuery,
+    per_page: u32,
+) -> Vec<String> {
+    let mut ids = vec![];
+    let mut cursor = Some("*".to_string());
+    while let Some(current) = cursor.take() {
+        let page = json(backend.list(
+            kind,
+            &ListQuery {
+                per_page: Some(per_page),
+                page: Some(Page::Cursor(current)),
+                ..query.clone()
+            },
+        ));
+        let page_ids = result_ids(&page);
+        assert!(page_ids.len() <= per_page as usize);
+        if !page_ids.is_empty() {
+            cursor = page["meta"]["next_cursor"].as_str().map(|c| c.to_string());
+        }
+        ids.extend(page_ids);
+    }
+    ids
+}
+
+fn check_get(backend: &dyn Backend) {
+    for kind in VertexKind::ALL {
+        for id in fixture_ids(kind) {
+            let short_id = id.trim_start_matches("https://openalex.org/");
+            let short_id = short_id.rsplit('/').next().unwrap();
+            assert_eq!(json(backend.get(kind, short_id))["id"], id.as_str());
+            assert_eq!(json(backend.get(kind, &id))["id"], id.as_str());
+        }
+    }
+
+    let by_doi = json(backend.get(VertexKind::Work, "doi:10.7717/peerj.4375"));
+    assert_eq!(by_doi["id"], "https://openalex.org/W2741809807");
+    assert!(backend.get(VertexKind::Work, "W1").is_err());
+}
+
+fn check_get_many(backend: &dyn Backend) {
+    let ids = fixture_ids(VertexKind::Author);
+    let bodies = backend.get_many(VertexKind::Author, &ids).unwrap();
+    let got = bodies
+        .iter()
+        .map(|body| serde_json::from_str::<Value>(body).unwrap()["id"].clone())
+        .collect::<Vec<_>>();
+    assert_eq!(got, ids);
+}
+
+fn check_random(backend: &dyn Backend) {
+    for kind in VertexKind::ALL {
+        let random = json(backend.get(kind, "random"));
+        assert!(fixture_ids(kind).contains(&random["id"].as_str().unwrap().to_string()));
+    }
+}
+
+fn check_list(backend: &dyn Backend) {
+    let all = list_all(backend, VertexKind::Work, &ListQuery::default(), 200);
+    assert_eq!(
+        all.iter().cloned().collect::<BTreeSet<_>>(),
+        fixture_ids(VertexKind::Work).into_iter().collect()
+    );
+
+    let cites = ListQuery::filtered("cites:W2100837269");
+    let page = json(backend.list(VertexKind::Work, &cites));
+    assert_eq!(page["meta"]["count"], 4);
+    assert_eq!(
+        result_ids(&page).into_iter().collect::<BTreeSet<_>>(),
+        work_ids(&["W2741809807", "W2149914327", "W2963951065", "W3123456789"])
+    );
+
+    let by_author = ListQuery::filtered("author.id:A5023888391,type:article");
+    assert_eq!(
+        list_all(backend, VertexKind::Work, &by_author, 200)
+            .into_iter()
+            .collect::<BTreeSet<_>>(),
+        work_ids(&["W2741809807", "W3123456789"])
+    );
+
+    let searched = ListQuery {
+        search: Some("manifesto".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        list_all(backend, VertexKind::Work, &searched, 200)
+            .into_iter()
+            .collect::<BTreeSet<_>>(),
+        work_ids(&["W2100837269", "W2149914327"])
+    );
+
+    let institutions = ListQuery::filtered("country_code:us");
+    assert_eq!(
+        list_all(backend, VertexKind::Institution, &institutions, 200).len(),
+        2
+    );
+}
+
+fn check_paging(backend: &dyn Backend) {
+    let paged = list_all(backend, VertexKind::Work, &ListQuery::default(), 2);
+    assert_eq!(paged.len(), fixtures(VertexKind::Work).len());
+    assert_eq!(
+        paged.iter().collect::<BTreeSet<_>>().len(),
+        paged.len(),
+        "a work was returned on two pages"
+    );
+
+    let first_page = json(backend.list(
+        VertexKind::Work,
+        &ListQuery {
+            per_page: Some(2),
+            page: Some(Page::Number(1)),
+            ..Default::default()
+        },
+    ));
+    assert_eq!(result_ids(&first_page).len(), 2);
+    assert_eq!(first_page["meta"]["count"], 5);
+}
+
+fn check_sort(backend: &dyn Backend) {
+    let most_cited = ListQuery {
+        sort: Some("cited_by_count:desc".to_string()),
+        ..Default::default()
+    };
+    let ids = list_all(backend, VertexKind::Work, &most_cited, 2);
+    assert_eq!(
+        ids,
+        [
+            "W2149914327",
+            "W2100837269",
+            "W2741809807",
+            "W2963951065",
+            "W3123456789"
+        ]
+        .map(|id| format!("https://openalex.org/{id}"))
+    );
+}
+
+fn check_group_by(backend: &dyn Backend) {
+    let groups = json(backend.group_by(
+        VertexKind::Work,
+        &ListQuery::default(),
+        "open_access.oa_status",
+    ));
+    let buckets = groups["group_by"].as_array().unwrap();
+    assert_eq!(buckets[0]["key"], "gold");
+    assert_eq!(buckets[0]["count"], 2);
+    assert_eq!(
+        buckets
+            .iter()
+            .map(|bucket| bucket["count"].as_u64().unwrap())
+            .sum::<u64>(),
+        5
+    );
+
+    let filtered = json(backend.group_by(
+        VertexKind::Work,
+        &ListQuery::filtered("primary_location.source.id:S1983995261"),
+        "publication_year",
+    ));
+    let keys = filtered["group_by"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|bucket| bucket["key"].as_str().unwrap().to_string())
+        .collect::<BTreeSet<_>>();
+    assert_eq!(keys, ["2018", "2021"].map(String::from).into());
+}
+
+fn check_autocomplete(backend: &dyn Backend) {
+    let results = json(backend.autocomplete(Some("institutions"), "michigan"));
+    let ids = result_ids(&results).into_iter().collect::<BTreeSet<_>>();
+    assert_eq!(
+        ids,
+        work_ids(&["I27837315", "I4210137306"]),
+        "autocomplete should find both Michigan institutions"
+    );
+    for result in results["results"].as_array().unwrap() {
+        assert_eq!(result["entity_type"], "institution");
+    }
+
+    let anywhere = json(backend.autocomplete(None, "larivi"));
+    assert!(result_ids(&anywhere).contains(&"https://openalex.org/A5072548367".to_string()));
+}
+
+fn check_backend(backend: &dyn Backend) {
+    check_get(backend);
+    check_get_many(backend);
+    check_random(backend);
+    check_list(backend);
+    check_paging(backend);
+    check_sort(backend);
+    check_group_by(backend);
+    check_autocomplete(backend);
+}
+
+#[test]
+fn snapshot_conforms() {
+    let (_dir, snapshot) = snapshot();
+    check_backend(&snapshot);
+    assert_eq!(snapshot.requests(), 0);
+}
+
+#[test]
+fn mirror_over_snapshot_conforms() {
+    let (_dir, snapshot) = snapshot();
+    let mirror_dir = tempfile::tempdir().unwrap();
+    let mirror = Mirror::open(mirror_dir.path().join("mirror.db")).unwrap();
+    check_backend(&MirrorBackend::new(mirror, snapshot));
+}
+
+#[test]
+fn offline_mirror_conforms() {
+    let (_dir, snapshot) = snapshot();
+    let mirror_dir = tempfile::tempdir().unwrap();
+    let path = mirror_dir.path().join("mirror.db");
+
+    // Listing every entity through the mirror stores them all.
+    let filling = MirrorBackend::new(Mirror::open(&path).unwrap(), snapshot);
+    for kind in VertexKind::ALL {
+        list_all(&filling, kind, &ListQuery::default(), 200);
+    }
+    assert_eq!(
+        filling.mirror().len().unwrap(),
+        VertexKind::ALL
+            .iter()
+            .map(|kind| fixtures(*kind).len())
+            .sum::<usize>()
+    );
+
+    let offline = Mirror::open(&path).unwrap().offline();
+    check_backend(&MirrorBackend::new(offline, NoRequests));
+}
+
+#[test]
+fn cassette_conforms() {
+    let (_dir, snapshot) = snapshot();
+    let cassette_dir = tempfile::tempdir().unwrap();
+    let path = cassette_dir.path().join("conformance.jsonl");
+
+    let recording = CassetteBackend::new(Cassette::record(&path).unwrap(), snapshot);
+    check_backend(&recording);
+
+    let replaying = CassetteBackend::new(Cassette::replay(&path).unwrap(), NoRequests);
+    check_backend(&replaying);
+}
+
+#[test]
+fn refusing_backend_fails_every_operation() {
+    let backend: Box<dyn Backend> = Box::new(NoRequests);
+    assert!(backend.get(VertexKind::Work, "W2741809807").is_err());
+    assert!(backend
+        .get_many(VertexKind::Work, &["W2741809807".to_string()])
+        .is_err());
+    assert!(backend
+        .list(VertexKind::Work, &ListQuery::default())
+        .is_err());
+}
@@ -0,0 +1,1808 @@
+//! Trustfall queries over every edge the adapter resolves, answered over HTTP by a local
+//! stand-in for the OpenAlex API serving the fixture corpus.
+
+mod support;
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use openalex_adapter::{
+    Backend, BudgetError, Diagnostic, FetchError, FilterError, HttpBackend, ListQuery,
+    OpenAlexAdapter, TruncationReason, VertexKind,
+};
+use serde_json::{json, Value};
+use trustfall::{execute_query, FieldValue, Schema, TransparentValue};
+
+use support::server::{Fault, MockServer};
+use support::{fixture_ids, fixtures};
+
+static SCHEMA: Lazy<Schema> =
+    Lazy::new(|| Schema::parse(include_str!("../openalex_schema.graphql")).unwrap());
+
+fn adapter(server: &MockServer) -> Arc<OpenAlexAdapter> {
+    Arc::new(OpenAlexAdapter::with_backend(
+        HttpBackend::default().with_base_url(server.url()),
+    ))
+}
+
+fn run(adapter: &Arc<OpenAlexAdapter>, query: &str) -> Vec<Value> {
+    execute_query(
+        &SCHEMA,
+        adapter.clone(),
+        query,
+        BTreeMap::<Arc<str>, FieldValue>::new(),
+    )
+    .unwrap_or_else(|e| panic!("not a valid query: {e}\n{query}"))
+    .map(|row| {
+        let row = row
+            .into_iter()
+            .map(|(name, value)| (name, TransparentValue::from(value)))
+            .collect::<BTreeMap<_, _>>();
+        serde_json::to_value(row).unwrap()
+    })
+    .collect()
+}
+
+/// Runs `query` against a fresh server.
+fn query(query: &str) -> Vec<Value> {
+    let server = MockServer::start();
+    run(&adapter(&server), query)
+}
+
+/// The sorted values of one output, with the `https://openalex.org/` of IDs left out.
+fn column(rows: &[Value], name: &str) -> Vec<String> {
+    let mut values = rows
+        .iter()
+        .map(|row| match &row[name] {
+            Value::String(s) => s.trim_start_matches("https://openalex.org/").to_string(),
+            value => value.to_string(),
+        })
+        .collect::<Vec<_>>();
+    values.sort();
+    values
+}
+
+fn strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
+
+// Starting edges
+
+#[test]
+fn id_search_work() {
+    for id in [
+        "W2741809807",
+        "https://openalex.org/W2741809807",
+        "doi:10.7717/peerj.4375",
+    ] {
+        let rows = query(&format!(
+            r#"{{ OpenAlexIDSearchWork(id: "{id}") {{ id: object_id @output title @output }} }}"#
+        ));
+        assert_eq!(column(&rows, "id"), strings(&["W2741809807"]), "{id}");
+    }
+    assert!(query(r#"{ OpenAlexIDSearchWork(id: "W1") { object_id @output } }"#).is_empty());
+}
+
+#[test]
+fn id_search_topic_hierarchy() {
+    for (edge, id, expected) in [
+        ("OpenAlexIDSearchTopic", "T10102", "T10102"),
+        (
+            "OpenAlexIDSearchSubfield",
+            "https://openalex.org/subfields/3309",
+            "subfields/3309",
+        ),
+        (
+            "OpenAlexIDSearchField",
+            "https://openalex.org/fields/33",
+            "fields/33",
+        ),
+        (
+            "OpenAlexIDSearchDomain",
+            "https://openalex.org/domains/2",
+            "domains/2",
+        ),
+    ] {
+        let rows = query(&format!(
+            r#"{{ {edge}(id: "{id}") {{ id: object_id @output }} }}"#
+        ));
+        assert_eq!(column(&rows, "id"), strings(&[expected]), "{edge}");
+    }
+}
+
+#[test]
+fn random_entities() {
+    for (edge, kind) in [
+        ("OpenAlexRandomWork", VertexKind::Work),
+        ("OpenAlexRandomAuthor", VertexKind::Author),
+        ("OpenAlexRandomSource", VertexKind::Source),
+        ("OpenAlexRandomConcept", VertexKind::Concept),
+        ("OpenAlexRandomInstitution", VertexKind::Institution),
+        ("OpenAlexRandomPublisher", VertexKind::Publisher),
+        ("OpenAlexRandomFunder", VertexKind::Funder),
+    ] {
+        let rows = query(&format!("{{ {edge} {{ id: object_id @output }} }}"));
+        assert_eq!(rows.len(), 1, "{edge}");
+        assert!(fixture_ids(kind).contains(&rows[0]["id"].as_str().unwrap().to_string()));
+    }
+}
+
+#[test]
+fn sdg_and_keyword_works() {
+    let rows = query(
+        r#"{ OpenAlexSDGWorks(sdg: "https://metadata.un.org/sdg/4", sort: "publication_year") {
+            id: object_id @output
+        } }"#,
+    );
+    assert_eq!(
+        column(&rows, "id"),
+        strings(&["W2741809807", "W2963951065", "W3123456789"])
+    );
+
+    let rows = query(
+        r#"{ OpenAlexKeywordWorks(keyword: "https://openalex.org/keywords/open-access") {
+            id: object_id @output
+        } }"#,
+    );
+    assert_eq!(
+        column(&rows, "id"),
+        strings(&["W2741809807", "W3123456789"])
+    );
+}
+
+#[test]
+fn tags_with_separators_are_rejected() {
+    let server = MockServer::start();
+    let adapter = adapter(&server);
+
+    for (edge, parameter, key) in [
+        (
+            "OpenAlexSDGWorks",
+            "sdg",
+            "sustainable_development_goals.id",
+        ),
+        ("OpenAlexKeywordWorks", "keyword", "keywords.id"),
+    ] {
+        let tag = "https://metadata.un.org/sdg/4|https://metadata.un.org/sdg/13";
+        let rows = run(
+            &adapter,
+            &format!(r#"{{ {edge}({parameter}: "{tag}") {{ id: object_id @output }} }}"#),
+        );
+        assert!(rows.is_empty(), "{edge}");
+        assert_eq!(
+            adapter.take_diagnostics(),
+            [Diagnostic::InvalidFilter(FilterError::Unescapable {
+                kind: VertexKind::Work,
+                key,
+                value: tag.to_string(),
+            })]
+        );
+    }
+    assert!(server.requests().is_empty());
+}
+
+#[test]
+fn group_by() {
+    let rows = query(
+        r#"{ GroupWorks(group_by: "open_access.oa_status", filter: "type:article") {
+            key @output
+            count @output
+        } }"#,
+    );
+    assert_eq!(
+        rows,
+        [
+            json!({ "key": "gold", "count": 2 }),
+            json!({ "key": "closed", "count": 1 }),
+            json!({ "key": "hybrid", "count": 1 }),
+        ]
+    );
+
+    for (edge, group_by, total) in [
+        ("GroupAuthors", "last_known_institutions.country_code", 4),
+        ("GroupSources", "type", 4),
+        ("GroupInstitutions", "country_code", 4),
+        ("GroupConcepts", "level", 3),
+        ("GroupPublishers", "hierarchy_level", 4),
+        ("GroupFunders", "country_code", 3),
+        ("GroupTopics", "domain.id", 2),
+    ] {
+        let rows = query(&format!(
+            r#"{{ {edge}(group_by: "{group_by}") {{ key @output count @output }} }}"#
+        ));
+        let counted = rows
+            .iter()
+            .map(|row| row["count"].as_u64().unwrap())
+            .sum::<u64>();
+        assert_eq!(counted, total, "{edge}");
+    }
+}
+
+#[test]
+fn autocomplete() {
+    let rows = query(
+        r#"{ Autocomplete(entity: "institutions", q: "michigan") {
+            id @output
+            entity_type @output
+        } }"#,
+    );
+    assert_eq!(column(&rows, "id"), strings(&["I27837315", "I4210137306"]));
+    assert_eq!(column(&rows, "entity_type"), strings(&["institution"; 2]));
+
+    let rows = query(r#"{ Autocomplete(q: "manifesto") { id @output } }"#);
+    assert_eq!(
+        column(&rows, "id"),
+        strings(&["W2100837269", "W2149914327"])
+    );
+}
+
+#[test]
+fn samples() {
+    for (edge, kind) in [
+        ("SampleWorks", VertexKind::Work),
+        ("SampleAuthors", VertexKind::Author),
+        ("SampleSources", VertexKind::Source),
+        ("SampleInstitutions", VertexKind::Institution),
+        ("SampleConcepts", VertexKind::Concept),
+        ("SamplePublishers", VertexKind::Publisher),
+        ("SampleFunders", VertexKind::Funder),
+        ("SampleTopics", VertexKind::Topic),
+    ] {
+        let server = MockServer::start();
+        let adapter = adapter(&server);
+        let sample = format!("{{ {edge}(size: 2, seed: 42) {{ id: object_id @output }} }}");
+        let rows = run(&adapter, &sample);
+        assert_eq!(rows.len(), 2, "{edge}");
+        assert_ne!(rows[0], rows[1], "{edge}");
+        for row in &rows {
+            assert!(fixture_ids(kind).contains(&row["id"].as_str().unwrap().to_string()));
+        }
+        assert_eq!(run(&adapter, &sample), rows, "{edge} with the same seed");
+    }
+}
+
+#[test]
+fn lists() {
+    for (edge, kind) in [
+        ("ListWorks", VertexKind::Work),
+        ("ListAuthors", VertexKind::Author),
+        ("ListSources", VertexKind::Source),
+        ("ListInstitutions", VertexKind::Institution),
+        ("ListConcepts", VertexKind::Concept),
+        ("ListPublishers", VertexKind::Publisher),
+        ("ListFunders", VertexKind::Funder),
+        ("ListTopics", VertexKind::Topic),
+        ("ListSubfields", VertexKind::Subfield),
+        ("ListFields", VertexKind::Field),
+        ("ListDomains", VertexKind::Domain),
+    ] {
+        let rows = query(&format!("{{ {edge} {{ id: object_id @output }} }}"));
+        let mut expected = fixture_ids(kind)
+            .into_iter()
+            .map(|id| id.trim_start_matches("https://openalex.org/").to_string())
+            .collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(column(&rows, "id"), expected, "{edge}");
+    }
+}
+
+#[test]
+fn list_filter_sort_and_search() {
+    let rows = query(
+        r#"{ ListWorks(filter: "cites:W2100837269", sort: "cited_by_count:desc") {
+            id: object_id @output
+        } }"#,
+    );
+    let ids = rows
+        .iter()
+        .map(|row| row["id"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        ids,
+        [
+            "https://openalex.org/W2149914327",
+            "https://openalex.org/W2741809807",
+            "https://openalex.org/W2963951065",
+            "https://openalex.org/W3123456789",
+        ]
+    );
+
+    let rows = query(r#"{ ListWorks(search: "manifesto") { id: object_id @output } }"#);
+    assert_eq!(
+        column(&rows, "id"),
+        strings(&["W2100837269", "W2149914327"])
+    );
+
+    let rows =
+        query(r#"{ ListInstitutions(filter: "country_code:us") { id: object_id @output } }"#);
+    assert_eq!(column(&rows, "id"), strings(&["I27837315", "I4210137306"]));
+}
+
+// Work edges
+
+#[test]
+fn work_cited_by_count_filtered() {
+    let rows = query(
+        r#"{ OpenAlexIDSearchWork(id: "W2100837269") {
+            cited_by_count_filtered(filter: "publication_year:>2020") {
+                filter @output
+                count @output
+            }
+        } }"#,
+    );
+    assert_eq!(
+        rows,
+        [json!({ "filter": "cites:W2100837269,publication_year:>2020", "count": 1 })]
+    );
+}
+
+#[test]
+fn work_topics() {
+    let rows = query(
+        r#"{ OpenAlexIDSearchWork(id: "W2963951065") {
+            primary_topic { primary: object_id @output }
+        } }"#,
+    );
+    assert_eq!(column(&rows, "primary"), strings(&["T11937"]));
+
+    let rows = query(
+        r#"{ OpenAlexIDSearchWork(id: "W2963951065") {
+            topics { id: object_id @output score @output }
+        } }"#,
+    );
+    assert_eq!(column(&rows, "id"), strings(&["T10102", "T11937"]));
+    assert!(rows.iter().all(|row| row["score"].is_f64()));
+}
+
+#[test]
+fn work_citations() {
+    let rows = query(
+        r#"{ OpenAlexIDSearchWork(id: "W2100837269") {
+            citations {
+                citing_work_id @output
+                is_author_self_citation @output
+                citing_work { title @output }
+                cited_work { cited_title: title @output }
+            }
+        } }"#,
+    );
+    let mut self_citations = rows
+        .iter()
+        .map(|row| {
+            (
+                row["citing_work_id"].as_str().unwrap(),
+                row["is_author_self_citation"].as_bool().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    self_citations.sort();
+    assert_eq!(
+        self_citations,
+        [
+            ("https://openalex.org/W2149914327", false),
+            ("https://openalex.org/W2741809807", true),
+            ("https://openalex.org/W2963951065", false),
+            ("https://openalex.org/W3123456789", true),
+        ]
+    );
+    assert!(rows
+        .iter()
+        .all(|row| row["cited_title"] == "Altmetrics: a manifesto"));
+    assert!(rows.iter().all(|row| row["title"].is_string()));
+}
+
+#[test]
+fn derived_edges_respect_limit() {
+    let server = MockServer::start();
+    let adapter = adapter(&server);
+    let rows = run(
+        &adapter,
+        r#"{ OpenAlexIDSearchWork(id: "W2100837269") {
+            citations(limit: 2) { citing_work_id @output }
+        } }"#,
+    );
+    assert_eq!(rows.len(), 2);
+
+    match adapter.take_diagnostics().as_slice() {
+        [Diagnostic::Truncated {
+            fetched, reason, ..
+        }] => {
+            assert_eq!(*fetched, 2);
+            assert_eq!(*reason, TruncationReason::Limit(2));
+        }
+        diagnostics => panic!("unexpected diagnostics: {diagnostics:?}"),
+    }
+}
+
+#[test]
+fn work_bibliographically_coupled() {
+    let rows = query(
+        r#"{ OpenAlexIDSearchWork(id: "W2741809807") {
+            bibliographically_coupled {
+                work_id @output
+                strength @output
+                work { title @output }
+            }
+        } }"#,
+    );
+    let mut overlaps = rows
+        .iter()
+        .map(|row| {
+            (
+                row["work_id"].as_str().unwrap(),
+                row["strength"].as_u64().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    overlaps.sort();
+    assert_eq!(
+        overlaps,
+        [
+            ("https://openalex.org/W2149914327", 1),
+            ("https://openalex.org/W2963951065", 2),
+            ("https://openalex.org/W3123456789", 1),
+        ]
+    );
+    assert!(rows.iter().all(|row| row["title"].is_string()));
+}
+
+/// A work citing 120 others, each cited by one more work: more references than fit in one
+/// `cites:` batch of 50.
+#[derive(Debug, Default)]
+struct ManyReferences {
+    filters: Mutex<Vec<String>>,
+}
+
+impl Backend for ManyReferences {
+    fn get(&self, _kind: VertexKind, _id: &str) -> Result<String, FetchError> {
+        let references = (1..=120)
+            .map(|n| format!("https://openalex.org/W{n}"))
+            .collect::<Vec<_>>();
+        Ok(json!({ "id": "https://openalex.org/W0", "referenced_works": references }).to_string())
+    }
+
+    /// One citing work per reference in the `cites:` filter, all on one page.
+    fn list(&self, _kind: VertexKind, query: &ListQuery) -> Result<String, FetchError> {
+        let filter = query.filter.clone().unwrap();
+        self.filters.lock().unwrap().push(filter.clone());
+        let results = filter
+            .trim_start_matches("cites:")
+            .split('|')
+            .map(|id| {
+                json!({
+                    "id": format!("https://openalex.org/C{id}"),
+                    "referenced_works": [format!("https://openalex.org/{id}")],
+                })
+            })
+            .collect::<Vec<_>>();
+        Ok(json!({
+            "meta": { "count": results.len(), "next_cursor": null },
+            "results": results,
+        })
+        .to_string())
+    }
+
+    fn group_by(&self, _: VertexKind, _: &ListQuery, _: &str) -> Result<String, FetchError> {
+        unimplemented!()
+    }
+
+    fn autocomplete(&self, _: Option<&str>, _: &str) -> Result<String, FetchError> {
+        unimplemented!()
+    }
+}
+
+#[test]
+fn bibliographic_coupling_shares_one_budget_across_batches() {
+    let adapter = Arc::new(OpenAlexAdapter::with_backend(ManyReferences::default()));
+    let rows = execute_query(
+        &SCHEMA,
+        adapter.clone(),
+        r#"{ OpenAlexIDSearchWork(id: "W0") {
+            bibliographically_coupled(limit: 60) { work_id @output }
+        } }"#,
+        BTreeMap::<Arc<str>, FieldValue>::new(),
+    )
+    .unwrap()
+    .count();
+
+    // Two batches cover the 60 works allowed; the third is never asked for.
+    assert_eq!(rows, 60);
+    assert_eq!(adapter.backend().filters.lock().unwrap().len(), 2);
+    match adapter.take_diagnostics().as_slice() {
+        [Diagnostic::Truncated {
+            fetched,
+            total,
+            reason,
+            ..
+        }] => {
+            assert_eq!(*fetched, 60);
+            assert_eq!(*total, None);
+            assert_eq!(*reason, TruncationReason::Limit(60));
+        }
+        diagnostics => panic!("unexpected diagnostics: {diagnostics:?}"),
+    }
+}
+
+#[test]
+fn work_co_cited_with() {
+    let rows = query(
+        r#"{ OpenAlexIDSearchWork(id: "W2149914327") {
+            co_cited_with {
+                work_id @output
+                strength @output
+                work { title @output }
+            }
+        } }"#,
+    );
+    assert_eq!(
+        rows,
+        [json!({
+            "work_id": "https://openalex.org/W2100837269",
+            "strength": 2,
+            "title": "Altmetrics: a manifesto",
+        })]
+    );
+}
+
+#[test]
+fn work_sdgs_and_keywords() {
+    let rows = query(
+        r#"{ OpenAlexIDSearchWork(id: "W2741809807") {
+            sdgs { sdg: id @output }
+        } }"#,
+    );
+    assert_eq!(
+        column(&rows, "sdg"),
+        strings(&["https://metadata.un.org/sdg/4"])
+    );
+
+    let rows = query(
+        r#"{ OpenAlexIDSearchWork(id: "W2741809807") {
+            keywords { keyword: id @output }
+        } }"#,
+    );
+    assert_eq!(
+        column(&rows, "keyword"),
+        strings(&["keywords/citation-advantage", "keywords/open-access"])
+    );
+}
+
+#[test]
+fn work_authorships() {
+    let rows = query(
+        r#"{ OpenAlexIDSearchWork(id: "W3123456789") {
+            authorships { author: object_id @output }
+        } }"#,
+    );
+    assert_eq!(
+        column(&rows, "author"),
+        strings(&["A5023888391", "A5048491430"])
+    );
+}
+
+#[test]
+fn work_cited_by_api_url() {
+    let rows = query(
+        r#"{ OpenAlexIDSearchWork(id: "W2149914327") {
+            cited_by_api_url { citing: object_id @output }
+        } }"#,
+    );
+    assert_eq!(
+        column(&rows, "citing"),
+        strings(&["W2741809807", "W2963951065"])
+    );
+}
+
+#[test]
+fn work_concepts() {
+    let rows = query(
+        r#"{ OpenAlexIDSearchWork(id: "W2741809807") {
+            concepts { concept: object_id @output }
+        } }"#,
+    );
+    assert_eq!(
+        column(&rows, "concept"),
+        strings(&["C161191863", "C41008148"])
+    );
+}
+
+#[test]
+fn work_grants() {
+    let rows = query(
+        r#"{ OpenAlexIDSearchWork(id: "W2963951065") {
+            grants { funder: object_id @output }
+        } }"#,
+    );
+    assert_eq!(
+        column(&rows, "funder"),
+        strings(&["F4320306076", "F4320309225"])
+    );
+}
+
+#[test]
+fn work_referenced_works() {
+    let rows = query(
+        r#"{ OpenAlexIDSearchWork(id: "W3123456789") {
+            referenced_works { reference: object_id @output }
+        } }"#,
+    );
+    assert_eq!(
+        column(&rows, "reference"),
+        strings(&["W2100837269", "W2741809807"])
+    );
+}
+
+#[test]
+fn work_related_works() {
+    let rows = query(
+        r#"{ OpenAlexIDSearchWork(id: "W2741809807") {
+            related_works { related: object_id @output }
+        } }"#,
+    );
+    assert_eq!(
+        column(&rows, "related"),
+        strings(&["W2963951065", "W3123456789"])
+    );
+}
+
+// Author edges
+
+#[test]
+fn author_works_count_filtered() {
+    let rows = query(
+        r#"{ ListAuthors(filter: "orcid:0000-0001-6187-6610") {
+            works_count_filtered(filter: "type:article") { count @output }
+        } }"#,
+    );
+    assert_eq!(rows, [json!({ "count": 2 })]);
+}
+
+#[test]
+fn author_coauthors() {
+    let rows = query(
+        r#"{ ListAuthors(filter: "openalex:A5023888391") {
+            coauthors {
+                coauthor_id @output
+                shared_works_count @output
+                coauthor { name: object_display_name @output }
+            }
+        } }"#,
+    );
+    let mut collaborations = rows
+        .iter()
+        .map(|row| {
+            (
+                row["coauthor_id"].as_str().unwrap(),
+                row["shared_works_count"].as_u64().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    collaborations.sort();
+    assert_eq!(
+        collaborations,
+        [
+            ("https://openalex.org/A5019479433", 1),
+            ("https://openalex.org/A5048491430", 2),
+            ("https://openalex.org/A5072548367", 1),
+        ]
+    );
+    assert!(rows.iter().all(|row| row["name"].is_string()));
+}
+
+/// An author with one work, which lists its coauthor twice, once per affiliation.
+#[derive(Debug)]
+struct RepeatedAuthorship;
+
+impl Backend for RepeatedAuthorship {
+    fn get(&self, _kind: VertexKind, _id: &str) -> Result<String, FetchError> {
+        unimplemented!()
+    }
+
+    /// The author, or their works.
+    fn list(&self, kind: VertexKind, _query: &ListQuery) -> Result<String, FetchError> {
+        if kind == VertexKind::Author {
+            return Ok(json!({
+                "meta": { "count": 1, "next_cursor": null },
+                "results": [{ "id": "https://openalex.org/A1" }],
+            })
+            .to_string());
+        }
+
+        let authorship = |id: &str, institution: &str| {
+            json!({
+                "author": { "id": format!("https://openalex.org/{id}") },
+                "institutions": [{ "id": format!("https://openalex.org/{institution}") }],
+            })
+        };
+        let work = json!({
+            "id": "https://openalex.org/W1",
+            "publication_year": 2020,
+            "authorships": [
+                authorship("A1", "I1"),
+                authorship("A2", "I1"),
+                authorship("A2", "I2"),
+            ],
+        });
+        Ok(json!({
+            "meta": { "count": 1, "next_cursor": null },
+            "results": [work],
+        })
+        .to_string())
+    }
+
+    fn group_by(&self, _: VertexKind, _: &ListQuery, _: &str) -> Result<String, FetchError> {
+        unimplemented!()
+    }
+
+    fn autocomplete(&self, _: Option<&str>, _: &str) -> Result<String, FetchError> {
+        unimplemented!()
+    }
+}
+
+#[test]
+fn coauthors_listed_twice_on_a_work_share_it_once() {
+    let adapter = Arc::new(OpenAlexAdapter::with_backend(RepeatedAuthorship));
+    let rows = execute_query(
+        &SCHEMA,
+        adapter,
+        r#"{ ListAuthors(filter: "openalex:A1") {
+            coauthors { coauthor_id @output shared_works_count @output }
+        } }"#,
+        BTreeMap::<Arc<str>, FieldValue>::new(),
+    )
+    .unwrap()
+    .map(|row| {
+        (
+            row["coauthor_id"].clone(),
+            row["shared_works_count"].clone(),
+        )
+    })
+    .collect::<Vec<_>>();
+    assert_eq!(
+        rows,
+        [(
+            FieldValue::from("https://openalex.org/A2"),
+            FieldValue::Uint64(1)
+        )]
+    );
+}
+
+#[test]
+fn author_affiliations() {
+    let rows = query(
+        r#"{ ListAuthors(filter: "openalex:A5023888391") {
+            affiliations {
+                institution_id @output
+                institution { name: object_display_name @output }
+            }
+        } }"#,
+    );
+    assert_eq!(
+        column(&rows, "institution_id"),
+        strings(&["I27837315", "I4210137306"])
+    );
+    assert_eq!(
+        column(&rows, "name"),
+        strings(&["Michigan Medicine", "University of Michigan–Ann Arbor"])
+    );
+}
+
+#[test]
+fn author_topics() {
+    let rows = query(
+        r#"{ ListAuthors(filter: "openalex:A5023888391") {
+            topics { id: object_id @output count @output }
+        } }"#,
+    );
+    assert_eq!(column(&rows, "id"), strings(&["T10102", "T11937"]));
+    assert!(rows.iter().all(|row| row["count"].is_u64()));
+}
+
+#[test]
+fn author_last_known_institution() {
+    let rows = query(
+        r#"{ ListAuthors(filter: "openalex:A5072548367") {
+            last_known_institution { institution: object_id @output }
+        } }"#,
+    );
+    assert_eq!(column(&rows, "institution"), strings(&["I70931966"]));
+}
+
+#[test]
+fn author_works_api_url() {
+    let rows = query(
+        r#"{ ListAuthors(filter: "openalex:A5023888391") {
+            works_api_url { work: object_id @output }
+        } }"#,
+    );
+    assert_eq!(
+        column(&rows, "work"),
+        strings(&["W2100837269", "W2741809807", "W3123456789"])
+    );
+}
+
+// Source edges
+
+#[test]
+fn source_works_count_filtered() {
+    let rows = query(
+        r#"{ ListSources(filter: "openalex:S1983995261") {
+            works_count_filtered(filter: "publication_year:2021") { count @output }
+        } }"#,
+    );
+    assert_eq!(rows, [json!({ "count": 1 })]);
+}
+
+#[test]
+fn source_topics() {
+    let rows = query(
+        r#"{ ListSources(filter: "openalex:S1983995261") {
+            topics { id: object_id @output count @output }
+        } }"#,
+    );
+    assert!(!rows.is_empty());
+    for id in column(&rows, "id") {
+        assert!(fixture_ids(VertexKind::Topic).contains(&format!("https://openalex.org/{id}")));
+    }
+}
+
+#[test]
+fn source_host_organization() {
+    let rows = query(
+        r#"{ ListSources(filter: "openalex:S4306402567") {
+            host_organization { host: object_id @output }
+        } }"#,
+    );
+    assert_eq!(column(&rows, "host"), strings(&["I27837315"]));
+}
+
+#[test]
+fn source_host_organization_lineage() {
+    let rows = query(
+        r#"{ ListSources(filter: "openalex:S4210178049") {
+            host_organization_lineage { publisher: object_id @output }
+        } }"#,
+    );
+    assert_eq!(
+        column(&rows, "publisher"),
+        strings(&["P4310315673", "P4310320990"])
+    );
+}
+
+#[test]
+fn source_works_api_url() {
+    let rows = query(
+        r#"{ ListSources(filter: "openalex:S4210178049") {
+            works_api_url { work: object_id @output }
+        } }"#,
+    );
+    assert_eq!(
+        column(&rows, "work"),
+        strings(&["W2149914327", "W2963951065"])
+    );
+}
+
+// Concept edges
+
+#[test]
+fn concept_ancestors() {
+    let rows = query(
+        r#"{ ListConcepts(filter: "openalex:C2522767166") {
+            ancestors { ancestor: object_id @output }
+        } }"#,
+    );
+    assert_eq!(column(&rows, "ancestor"), strings(&["C41008148"]));
+}
+
+#[test]
+fn concept_related_concepts() {
+    let rows = query(
+        r#"{ ListConcepts(filter: "openalex:C2522767166") {
+            related_concepts { related: object_id @output }
+        } }"#,
+    );
+    assert_eq!(column(&rows, "related"), strings(&["C161191863"]));
+}
+
+#[test]
+fn concept_works_api_url() {
+    let rows = query(
+        r#"{ ListConcepts(filter: "openalex:C41008148") {
+            works_api_url { work: object_id @output }
+        } }"#,
+    );
+    assert!(!rows.is_empty());
+}
+
+// Institution edges
+
+#[test]
+fn institution_works_count_filtered() {
+    let rows = query(
+        r#"{ ListInstitutions(filter: "openalex:I27837315") {
+            works_count_filtered(filter: "type:article") { count @output }
+        } }"#,
+    );
+    assert_eq!(rows, [json!({ "count": 2 })]);
+}
+
+#[test]
+fn institution_hierarchy() {
+    for (edge, id, expected) in [
+        ("parents", "I4210137306", vec!["I27837315"]),
+        ("parents", "I27837315", vec![]),
+        ("children", "I27837315", vec!["I4210137306"]),
+        ("related", "I27837315", vec!["I1294671590"]),
+        ("lineage", "I4210137306", vec!["I27837315", "I4210137306"]),
+    ] {
+        let rows = query(&format!(
+            r#"{{ ListInstitutions(filter: "openalex:{id}") {{
+                {edge} {{ neighbor: object_id @output }}
+            }} }}"#
+        ));
+        assert_eq!(column(&rows, "neighbor"), strings(&expected), "{id} {edge}");
+    }
+}
+
+#[test]
+fn institution_authors() {
+    let rows = query(
+        r#"{ ListInstitutions(filter: "openalex:I27837315") {
+            authors(sort: "display_name") { author: object_id @output }
+        } }"#,
+    );
+    assert_eq!(
+        column(&rows, "author"),
+        strings(&["A5023888391", "A5048491430"])
+    );
+
+    let rows = query(
+        r#"{ ListInstitutions(filter: "openalex:I4210137306") {
+            current: authors { author: object_id @output }
+        } }"#,
+    );
+    assert!(rows.is_empty());
+
+    let rows = query(
+        r#"{ ListInstitutions(filter: "openalex:I4210137306") {
+            authors(include_past: true) { author: object_id @output }
+        } }"#,
+    );
+    assert_eq!(column(&rows, "author"), strings(&["A5023888391"]));
+}
+
+#[test]
+fn institution_associated_institutions() {
+    let rows = query(
+        r#"{ ListInstitutions(filter: "openalex:I27837315") {
+            associated_institutions { associated: object_id @output }
+        } }"#,
+    );
+    assert_eq!(
+        column(&rows, "associated"),
+        strings(&["I1294671590", "I4210137306"])
+    );
+}
+
+#[test]
+fn institution_repositories() {
+    let rows = query(
+        r#"{ ListInstitutions(filter: "openalex:I27837315") {
+            repositories { repository: object_id @output }
+        } }"#,
+    );
+    assert_eq!(column(&rows, "repository"), strings(&["S4306402567"]));
+}
+
+#[test]
+fn institution_works_api_url() {
+    let rows = query(
+        r#"{ ListInstitutions(filter: "openalex:I70931966") {
+            works_api_url { work: object_id @output }
+        } }"#,
+    );
+    assert_eq!(
+        column(&rows, "work"),
+        strings(&["W2149914327", "W2741809807", "W2963951065"])
+    );
+}
+
+// Publisher and funder edges
+
+#[test]
+fn publisher_children_and_works() {
+    let rows = query(
+        r#"{ ListPublishers(filter: "openalex:P4310320990") {
+            children { child: object_id @output }
+        } }"#,
+    );
+    assert_eq!(column(&rows, "child"), strings(&["P4310315673"]));
+
+    let rows = query(
+        r#"{ ListPublishers(filter: "openalex:P4310320990") {
+            works(sort: "publication_year") { work: object_id @output }
+        } }"#,
+    );
+    assert_eq!(
+        column(&rows, "work"),
+        strings(&["W2149914327", "W2963951065"])
+    );
+}
+
+#[test]
+fn publisher_lineage() {
+    let rows = query(
+        r#"{ ListPublishers(filter: "openalex:P4310315673") {
+            lineage { ancestor: object_id @output }
+        } }"#,
+    );
+    assert_eq!(
+        column(&rows, "ancestor"),
+        strings(&["P4310315673", "P4310320990"])
+    );
+}
+
+#[test]
+fn publisher_sources_api_url() {
+    let rows = query(
+        r#"{ ListPublishers(filter: "openalex:P4310310987") {
+            sources_api_url { source: object_id @output }
+        } }"#,
+    );
+    assert_eq!(column(&rows, "source"), strings(&["S1983995261"]));
+}
+
+#[test]
+fn funder_works() {
+    let rows = query(
+        r#"{ ListFunders(filter: "openalex:F4320306076") {
+            works { work: object_id @output }
+        } }"#,
+    );
+    assert_eq!(
+        column(&rows, "work"),
+        strings(&["W2741809807", "W2963951065"])
+    );
+
+    let rows = query(
+        r#"{ ListFunders(filter: "openalex:F4320306076") {
+            works(award_id: "1931531") { work: object_id @output }
+        } }"#,
+    );
+    assert_eq!(column(&rows, "work"), strings(&["W2963951065"]));
+}
+
+#[test]
+fn award_ids_with_separators_are_rejected() {
+    let server = MockServer::start();
+    let adapter = adapter(&server);
+
+    // OpenAlex would read `1346575|1931531` as either award, and has no way to escape it.
+    let rows = run(
+        &adapter,
+        r#"{ ListFunders(filter: "openalex:F4320306076") {
+            display_name: object_display_name @output
+            works(award_id: "1346575|1931531") @optional { work: object_id @output }
+        } }"#,
+    );
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0]["work"], Value::Null);
+    assert_eq!(server.requests().len(), 1);
+    assert_eq!(
+        adapter.take_diagnostics(),
+        [Diagnostic::InvalidFilter(FilterError::Unescapable {
+            kind: VertexKind::Work,
+            key: "grants.award_id",
+            value: "1346575|1931531".to_string(),
+        })]
+    );
+}
+
+// Topic hierarchy edges
+
+#[test]
+fn topic_hierarchy() {
+    let rows = query(
+        r#"{ OpenAlexIDSearchTopic(id: "T10102") {
+            subfield { subfield: object_id @output }
+            field { field: object_id @output }
+            domain { domain: object_id @output }
+        } }"#,
+    );
+    assert_eq!(
+        rows,
+        [json!({
+            "subfield": "https://openalex.org/subfields/3309",
+            "field": "https://openalex.org/fields/33",
+            "domain": "https://openalex.org/domains/2",
+        })]
+    );
+
+    let rows = query(
+        r#"{ OpenAlexIDSearchSubfield(id: "https://openalex.org/subfields/3309") {
+            topics { topic: object_id @output }
+            field { field: object_id @output }
+            domain { domain: object_id @output }
+        } }"#,
+    );
+    assert_eq!(column(&rows, "topic"), strings(&["T10102"]));
+    assert_eq!(column(&rows, "field"), strings(&["fields/33"]));
+    assert_eq!(column(&rows, "domain"), strings(&["domains/2"]));
+
+    let rows = query(
+        r#"{ OpenAlexIDSearchField(id: "https://openalex.org/fields/33") {
+            subfields { subfield: object_id @output }
+            domain { domain: object_id @output }
+        } }"#,
+    );
+    assert_eq!(column(&rows, "subfield"), strings(&["subfields/3309"]));
+    assert_eq!(column(&rows, "domain"), strings(&["domains/2"]));
+
+    let rows = query(
+        r#"{ OpenAlexIDSearchDomain(id: "https://openalex.org/domains/2") {
+            fields { field: object_id @output }
+        } }"#,
+    );
+    assert_eq!(column(&rows, "field"), strings(&["fields/33"]));
+}
+
+// Group bucket and autocomplete result edges
+
+/// For each edge out of a group bucket or autocomplete result: the kind of entity it leads
+/// to, and a works grouping keyed by such entities.
+const ENTITY_EDGES: [(&str, VertexKind, &str); 11] = [
+    (
+        "work",
+        VertexKind::Work,
+        "GroupWorks(group_by: \"referenced_works\")",
+    ),
+    (
+        "author",
+        VertexKind::Author,
+        "GroupWorks(group_by: \"author.id\")",
+    ),
+    (
+        "source",
+        VertexKind::Source,
+        "GroupWorks(group_by: \"primary_location.source.id\")",
+    ),
+    (
+        "institution",
+        VertexKind::Institution,
+        "GroupWorks(group_by: \"institutions.id\")",
+    ),
+    (
+        "concept",
+        VertexKind::Concept,
+        "GroupWorks(group_by: \"concepts.id\")",
+    ),
+    (
+        "publisher",
+        VertexKind::Publisher,
+        "GroupSources(group_by: \"host_organization.id\")",
+    ),
+    (
+        "funder",
+        VertexKind::Funder,
+        "GroupWorks(group_by: \"grants.funder\")",
+    ),
+    (
+        "topic",
+        VertexKind::Topic,
+        "GroupWorks(group_by: \"primary_topic.id\")",
+    ),
+    (
+        "subfield",
+        VertexKind::Subfield,
+        "GroupWorks(group_by: \"primary_topic.subfield.id\")",
+    ),
+    (
+        "field",
+        VertexKind::Field,
+        "GroupWorks(group_by: \"primary_topic.field.id\")",
+    ),
+    (
+        "domain",
+        VertexKind::Domain,
+        "GroupWorks(group_by: \"primary_topic.domain.id\")",
+    ),
+];
+
+#[test]
+fn group_bucket_entities() {
+    for (edge, kind, group) in ENTITY_EDGES {
+        let rows = query(&format!(
+            "{{ {group} {{ key @output {edge} {{ entity: object_id @output }} }} }}"
+        ));
+        assert!(!rows.is_empty(), "{edge}");
+        for row in &rows {
+            assert_eq!(row["entity"], row["key"], "{edge}");
+            assert!(fixture_ids(kind).contains(&row["key"].as_str().unwrap().to_string()));
+        }
+    }
+
+    // Buckets keyed by something other than an entity have no neighbors.
+    let rows = query(
+        r#"{ GroupWorks(group_by: "publication_year") {
+            work @optional { entity: object_id @output }
+            key @output
+        } }"#,
+    );
+    assert_eq!(rows.len(), 5);
+    assert!(rows.iter().all(|row| row["entity"].is_null()));
+}
+
+#[test]
+fn autocomplete_result_entities() {
+    for (edge, kind, _) in ENTITY_EDGES {
+        let entity = &fixtures(kind)[0];
+        let q = entity["display_name"].as_str().unwrap().to_lowercase();
+        let rows = query(&format!(
+            r#"{{ Autocomplete(entity: "{}", q: "{q}") {{
+                id @output
+                {edge} {{ entity: object_id @output }}
+            }} }}"#,
+            kind.endpoint()
+        ));
+        assert!(!rows.is_empty(), "{edge}");
+        assert!(
+            rows.iter().any(|row| row["entity"] == entity["id"]),
+            "{edge}"
+        );
+        for row in &rows {
+            assert_eq!(row["entity"], row["id"], "{edge}");
+        }
+    }
+
+    // Only the edge matching the result's entity type leads anywhere.
+    let rows = query(
+        r#"{ Autocomplete(entity: "institutions", q: "michigan") {
+            author @optional { entity: object_id @output }
+            id @output
+        } }"#,
+    );
+    assert_eq!(rows.len(), 2);
+    assert!(rows.iter().all(|row| row["entity"].is_null()));
+}
+
+// Schema drift
+
+#[test]
+fn drifted_fields_fall_back_instead_of_failing() {
+    let server = MockServer::start();
+    let adapter = adapter(&server);
+
+    server.fail_once("W2741809807", Fault::Drifted);
+    let rows = run(
+        &adapter,
+        r#"{ OpenAlexIDSearchWork(id: "W2741809807") {
+            cited_by_count: object_cited_by_count @output
+            display_name: object_display_name @output
+            updated_date: object_updated_date @output
+            title @output
+            concepts @fold { concept: object_id @output }
+            grants @fold { funder: object_id @output }
+            object_counts_by_year @fold {
+                year @output
+                yearly_citations: cited_by_count @output
+            }
+            authorships @fold { author: object_id @output }
+            related_work_ids @output
+        } }"#,
+    );
+    assert_eq!(rows.len(), 1);
+    assert!(rows[0]["cited_by_count"].is_null());
+    assert!(rows[0]["display_name"].is_null());
+    assert!(rows[0]["updated_date"].is_null());
+    assert!(rows[0]["title"].is_string());
+    for field in ["year", "yearly_citations"] {
+        let values = rows[0][field].as_array().unwrap();
+        assert!(!values.is_empty());
+        assert!(values.iter().all(Value::is_null), "{field}");
+    }
+    assert_eq!(rows[0]["author"].as_array().unwrap().len(), 4);
+    assert_eq!(rows[0]["related_work_ids"], json!([]));
+    assert_eq!(
+        rows[0]["concept"],
+        json!([
+            "https://openalex.org/C41008148",
+            "https://openalex.org/C161191863"
+        ])
+    );
+    // The grant no longer names its funder, so there's no funder to follow.
+    assert_eq!(rows[0]["funder"], json!([]));
+
+    server.fail_once("institutions", Fault::Drifted);
+    let rows = run(
+        &adapter,
+        "{ ListInstitutions { roles @fold { works_count @output } } }",
+    );
+    assert_eq!(rows.len(), fixtures(VertexKind::Institution).len());
+    for row in &rows {
+        let works_counts = row["works_count"].as_array().unwrap();
+        assert!(!works_counts.is_empty());
+        assert!(works_counts.iter().all(Value::is_null));
+    }
+
+    server.fail_once("sources", Fault::Drifted);
+    let rows = run(
+        &adapter,
+        r#"{ ListSources(filter: "issn:2167-8359") { apc_prices @output } }"#,
+    );
+    assert_eq!(rows, vec![json!({ "apc_prices": [] })]);
+}
+
+#[test]
+fn entities_missing_whole_fields_still_load() {
+    let server = MockServer::start();
+    let adapter = adapter(&server);
+
+    for kind in VertexKind::ALL {
+        server.fail_once(&format!("/{}?", kind.endpoint()), Fault::Drifted);
+        let rows = run(
+            &adapter,
+            &format!(
+                "{{ List{}s(max_pages: 10) {{ raw_json @output }} }}",
+                kind.type_name()
+            ),
+        );
+        assert_eq!(
+            rows.len(),
+            fixtures(kind).len(),
+            "{kind:?} {:?}",
+            adapter.take_diagnostics()
+        );
+        for row in &rows {
+            let raw_json: Value = serde_json::from_str(row["raw_json"].as_str().unwrap()).unwrap();
+            assert!(raw_json.get("display_name").is_none(), "{kind:?}");
+            assert!(raw_json.get("ids").is_none(), "{kind:?}");
+        }
+    }
+    assert_eq!(adapter.take_diagnostics(), []);
+}
+
+#[test]
+fn unmodelled_fields_are_reachable_through_json_path() {
+    let server = MockServer::start();
+    let adapter = adapter(&server);
+
+    server.fail_once("W2741809807", Fault::Drifted);
+    let rows = run(
+        &adapter,
+        r#"{ OpenAlexIDSearchWork(id: "W2741809807") {
+            raw_json @output
+            json_path(path: "not_yet_modelled.values[-1]") { last: integer @output }
+            json_path(path: "grants[0].funder") { funder: json_type @output }
+        } }"#,
+    );
+    assert_eq!(rows.len(), 1);
+    let raw_json: Value = serde_json::from_str(rows[0]["raw_json"].as_str().unwrap()).unwrap();
+    assert_eq!(raw_json["id"], "https://openalex.org/W2741809807");
+    assert_eq!(raw_json["not_yet_modelled"], json!({ "values": [1, 2, 3] }));
+    assert_eq!(rows[0]["last"], 3);
+    assert_eq!(rows[0]["funder"], "null");
+
+    // Nested objects keep the fields the adapter doesn't know, too.
+    server.fail_once("institutions", Fault::Drifted);
+    let rows = run(
+        &adapter,
+        r#"{ ListInstitutions {
+            roles { json_path(path: "not_yet_modelled") { flag: boolean @output } }
+        } }"#,
+    );
+    assert!(!rows.is_empty());
+    assert!(rows.iter().all(|row| row["flag"] == true));
+}
+
+#[test]
+fn json_path_selects_every_match() {
+    let rows = query(
+        r#"{ OpenAlexIDSearchWork(id: "W2741809807") {
+            json_path(path: "$.authorships[*]['author'].display_name") @fold {
+                name: string @output
+            }
+            json_path(path: "ids.*") @fold { id: string @output }
+        } }"#,
+    );
+    assert_eq!(
+        rows[0]["name"],
+        json!([
+            "Heather Piwowar",
+            "Jason Priem",
+            "Vincent Larivière",
+            "Stefanie Haustein"
+        ])
+    );
+    assert!(rows[0]["id"]
+        .as_array()
+        .unwrap()
+        .contains(&json!("https://openalex.org/W2741809807")));
+
+    // A path that doesn't parse selects nothing, and says why.
+    let server = MockServer::start();
+    let adapter = adapter(&server);
+    for path in [
+        "authorships[0",
+        "authorships[first]",
+        "authorships..id",
+        "ids[*]]",
+    ] {
+        let rows = run(
+            &adapter,
+            &format!(
+                r#"{{ OpenAlexIDSearchWork(id: "W2741809807") {{
+                    json_path(path: "{path}") {{ json_type @output }}
+                }} }}"#
+            ),
+        );
+        assert!(rows.is_empty(), "{path}");
+        match adapter.take_diagnostics().as_slice() {
+            [Diagnostic::InvalidJsonPath(e)] => assert_eq!(e.path, path),
+            diagnostics => panic!("{path}: {diagnostics:?}"),
+        }
+    }
+}
+
+// Paging, budgets and failures
+
+#[test]
+fn lists_follow_cursors_across_pages() {
+    let server = MockServer::start();
+    server.limit_page_size(2);
+    let rows = run(
+        &adapter(&server),
+        "{ ListWorks(max_pages: 10) { id: object_id @output } }",
+    );
+    assert_eq!(rows.len(), 5);
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 3, "{requests:?}");
+    assert!(requests[0].contains("cursor=*"));
+    assert!(requests[1..]
+        .iter()
+        .all(|request| !request.contains("cursor=*")));
+}
+
+#[test]
+fn samples_use_numbered_pages() {
+    let server = MockServer::start();
+    server.limit_page_size(2);
+    let rows = run(
+        &adapter(&server),
+        "{ SampleWorks(size: 5, seed: 7) { id: object_id @output } }",
+    );
+    let mut ids = column(&rows, "id");
+    ids.dedup();
+    assert_eq!(ids.len(), 5);
+
+    let requests = server.requests();
+    for (request, page) in requests.iter().zip(["page=1", "page=2", "page=3"]) {
+        assert!(request.contains("sample=5") && request.contains("seed=7"));
+        assert!(request.contains(page), "{request}");
+    }
+}
+
+#[test]
+fn samples_stop_at_their_budget() {
+    for (budget, rows, requests, reason) in [
+        ("max_pages: 2", 4, 2, TruncationReason::MaxPages(2)),
+        ("limit: 3", 3, 2, TruncationReason::Limit(3)),
+    ] {
+        let server = MockServer::start();
+        server.limit_page_size(2);
+        let adapter = adapter(&server);
+        let sample = run(
+            &adapter,
+            &format!("{{ SampleWorks(size: 5, seed: 7, {budget}) {{ object_id @output }} }}"),
+        );
+        assert_eq!(sample.len(), rows, "{budget}");
+        assert_eq!(server.requests().len(), requests, "{budget}");
+        match adapter.take_diagnostics().as_slice() {
+            [Diagnostic::Truncated {
+                fetched,
+                total,
+                reason: truncated_by,
+                ..
+            }] => {
+                assert_eq!(*fetched, rows, "{budget}");
+                assert_eq!(*total, Some(5), "{budget}");
+                assert_eq!(*truncated_by, reason, "{budget}");
+            }
+            diagnostics => panic!("unexpected diagnostics: {diagnostics:?}"),
+        }
+    }
+}
+
+#[test]
+fn samples_without_a_seed_send_none() {
+    let server = MockServer::start();
+    let rows = run(
+        &adapter(&server),
+        "{ SampleWorks(size: 2) { id: object_id @output } }",
+    );
+    assert_eq!(rows.len(), 2);
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].contains("sample=2") && !requests[0].contains("seed="));
+}
+
+#[test]
+fn negative_sample_sizes_sample_nothing() {
+    let server = MockServer::start();
+    let rows = run(
+        &adapter(&server),
+        "{ SampleWorks(size: -5, seed: 7) { id: object_id @output } }",
+    );
+    assert!(rows.is_empty());
+    assert!(server.requests().is_empty());
+}
+
+#[test]
+fn lists_fetch_one_page_by_default() {
+    let server = MockServer::start();
+    server.limit_page_size(2);
+    let adapter = adapter(&server);
+    let rows = run(&adapter, "{ ListWorks { id: object_id @output } }");
+    assert_eq!(rows.len(), 2);
+    assert_eq!(server.requests().len(), 1);
+
+    match adapter.take_diagnostics().as_slice() {
+        [Diagnostic::Truncated { reason, .. }] => {
+            assert_eq!(*reason, TruncationReason::MaxPages(1));
+        }
+        diagnostics => panic!("unexpected diagnostics: {diagnostics:?}"),
+    }
+}
+
+#[test]
+fn limit_stops_paging_and_reports_truncation() {
+    let server = MockServer::start();
+    server.limit_page_size(2);
+    let adapter = adapter(&server);
+    let rows = run(
+        &adapter,
+        "{ ListWorks(limit: 3) { id: object_id @output } }",
+    );
+    assert_eq!(rows.len(), 3);
+    assert_eq!(server.requests().len(), 2);
+    assert_eq!(adapter.requests(), 2);
+
+    match adapter.take_diagnostics().as_slice() {
+        [Diagnostic::Truncated {
+            fetched,
+            total,
+            reason,
+            ..
+        }] => {
+            assert_eq!(*fetched, 3);
+            assert_eq!(*total, Some(5));
+            assert_eq!(*reason, TruncationReason::Limit(3));
+        }
+        diagnostics => panic!("unexpected diagnostics: {diagnostics:?}"),
+    }
+}
+
+#[test]
+fn rate_limited_requests_fail_only_their_edge() {
+    let server = MockServer::start();
+    let adapter = adapter(&server);
+
+    server.fail_once("", Fault::TooManyRequests);
+    assert!(run(&adapter, "{ ListWorks { id: object_id @output } }").is_empty());
+    assert_eq!(
+        run(&adapter, "{ ListWorks { id: object_id @output } }").len(),
+        5
+    );
+
+    // One topic rate limited: the work and its other topic still come through.
+    server.fail_once("T10102", Fault::TooManyRequests);
+    let rows = run(
+        &adapter,
+        r#"{ OpenAlexIDSearchWork(id: "W2963951065") { topics { id: object_id @output } } }"#,
+    );
+    assert_eq!(column(&rows, "id"), strings(&["T11937"]));
+
+    match adapter.take_diagnostics().as_slice() {
+        [Diagnostic::Failed { url: list, .. }, Diagnostic::Failed { url: topic, .. }] => {
+            assert!(list.contains("/works"), "{list}");
+            assert!(topic.ends_with("T10102"), "{topic}");
+        }
+        diagnostics => panic!("unexpected diagnostics: {diagnostics:?}"),
+    }
+}
+
+#[test]
+fn invalid_filters_send_no_request() {
+    let server = MockServer::start();
+    let adapter = adapter(&server);
+    let rows = run(
+        &adapter,
+        r#"{ ListWorks(filter: "publication_yaer:2020") { id: object_id @output } }"#,
+    );
+    assert!(rows.is_empty());
+    assert!(server.requests().is_empty());
+    assert_eq!(
+        adapter.take_diagnostics(),
+        [Diagnostic::InvalidFilter(FilterError::UnknownKey {
+            kind: VertexKind::Work,
+            key: "publication_yaer".to_string(),
+            suggestion: Some("publication_year"),
+        })]
+    );
+
+    // Nested edges with a bad filter are empty too, without failing their parent.
+    let rows = run(
+        &adapter,
+        r#"{ OpenAlexIDSearchWork(id: "W2100837269") {
+            title @output
+            cited_by_count_filtered(filter: "is_oa") @optional { count @output }
+        } }"#,
+    );
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0]["count"], Value::Null);
+    assert!(matches!(
+        adapter.take_diagnostics().as_slice(),
+        [Diagnostic::InvalidFilter(FilterError::Malformed { .. })]
+    ));
+}
+
+#[test]
+fn requests_over_budget_are_reported_once() {
+    let server = MockServer::start();
+    let adapter = Arc::new(OpenAlexAdapter::with_backend(
+        HttpBackend::default()
+            .with_base_url(server.url())
+            .with_max_requests(1),
+    ));
+    let rows = run(
+        &adapter,
+        r#"{ OpenAlexIDSearchWork(id: "W2963951065") { topics { id: object_id @output } } }"#,
+    );
+    assert!(rows.is_empty());
+    assert_eq!(server.requests().len(), 1);
+    assert_eq!(
+        adapter.take_diagnostics(),
+        [Diagnostic::OverBudget(BudgetError::MaxRequests {
+            limit: 1
+        })]
+    );
+}
+
+/// An adapter whose daily request count is kept in `usage_path`.
+fn daily_adapter(
+    server: &MockServer,
+    max_daily_requests: Option<u64>,
+    usage_path: &Path,
+) -> Arc<OpenAlexAdapter> {
+    let backend = HttpBackend::default().with_base_url(server.url());
+    Arc::new(OpenAlexAdapter::with_backend(match max_daily_requests {
+        Some(max) => backend.with_max_daily_requests(max, usage_path),
+        None => backend.with_usage_path(usage_path),
+    }))
+}
+
+#[test]
+fn daily_cap_holds_across_runs() {
+    let server = MockServer::start();
+    server.limit_page_size(2);
+    let dir = tempfile::tempdir().unwrap();
+    let usage_path = dir.path().join("usage.json");
+    let list = "{ ListWorks(max_pages: 10) { id: object_id @output } }";
+
+    assert_eq!(
+        run(&daily_adapter(&server, Some(2), &usage_path), list).len(),
+        4
+    );
+
+    // A new adapter reads the day's count back and refuses the first request.
+    let adapter = daily_adapter(&server, Some(2), &usage_path);
+    assert!(run(&adapter, list).is_empty());
+    assert_eq!(server.requests().len(), 2);
+    assert_eq!(
+        adapter.take_diagnostics(),
+        [Diagnostic::OverBudget(BudgetError::MaxDailyRequests {
+            limit: 2,
+            date: Utc::now().date_naive(),
+        })]
+    );
+}
+
+#[test]
+fn usage_is_persisted_without_a_daily_cap() {
+    let server = MockServer::start();
+    server.limit_page_size(2);
+    let dir = tempfile::tempdir().unwrap();
+    let usage_path = dir.path().join("usage.json");
+    let list = "{ ListWorks(max_pages: 10) { id: object_id @output } }";
+
+    let adapter = daily_adapter(&server, None, &usage_path);
+    assert_eq!(run(&adapter, list).len(), 5);
+    assert_eq!(adapter.backend().budget().daily_requests(), Ok(3));
+
+    let adapter = daily_adapter(&server, None, &usage_path);
+    assert_eq!(run(&adapter, list).len(), 5);
+    assert_eq!(adapter.backend().budget().daily_requests(), Ok(6));
+    assert_eq!(adapter.requests(), 3);
+}
+
+#[test]
+fn daily_count_starts_over_on_a_new_day() {
+    let server = MockServer::start();
+    let dir = tempfile::tempdir().unwrap();
+    let usage_path = dir.path().join("usage.json");
+    fs::write(&usage_path, r#"{"date":"2000-01-01","requests":100}"#).unwrap();
+
+    let adapter = daily_adapter(&server, Some(100), &usage_path);
+    assert_eq!(
+        run(&adapter, "{ ListWorks { id: object_id @output } }").len(),
+        5
+    );
+    assert!(adapter.take_diagnostics().is_empty());
+
+    let usage: Value = serde_json::from_str(&fs::read_to_string(&usage_path).unwrap()).unwrap();
+    assert_eq!(
+        usage,
+        json!({ "date": Utc::now().date_naive().to_string(), "requests": 1 })
+    );
+}
+
+#[test]
+fn malformed_pages_end_the_list() {
+    let server = MockServer::start();
+    server.limit_page_size(2);
+    let adapter = adapter(&server);
+    let list = "{ ListWorks(max_pages: 10) { id: object_id @output } }";
+
+    assert_eq!(run(&adapter, list).len(), 5);
+    let second_page = server.requests()[1].clone();
+
+    // The results before the malformed page still come through.
+    server.fail_once(&second_page, Fault::Truncated);
+    assert_eq!(run(&adapter, list).len(), 2);
+
+    server.fail_once("", Fault::UnexpectedShape);
+    assert!(run(&adapter, list).is_empty());
+}
+
+#[test]
+fn malformed_entities_are_skipped() {
+    let server = MockServer::start();
+    let adapter = adapter(&server);
+
+    server.fail_once("W2741809807", Fault::UnexpectedShape);
+    assert!(run(
+        &adapter,
+        r#"{ OpenAlexIDSearchWork(id: "W2741809807") { object_id @output } }"#
+    )
+    .is_empty());
+
+    server.fail_once("T11937", Fault::Truncated);
+    let rows = run(
+        &adapter,
+        r#"{ OpenAlexIDSearchWork(id: "W2963951065") { topics { id: object_id @output } } }"#,
+    );
+    assert_eq!(column(&rows, "id"), strings(&["T10102"]));
+}
+
+#[test]
+fn every_request_goes_to_the_local_server() {
+    let server = MockServer::start();
+    let adapter = adapter(&server);
+    run(
+        &adapter,
+        r#"{ OpenAlexIDSearchWork(id: "https://openalex.org/W2963951065") {
+            topics { subfield { field { domain { id: object_id @output } } } }
+        } }"#,
+    );
+    let requests = server.requests();
+    assert_eq!(adapter.requests(), requests.len() as u64);
+    assert_eq!(requests[0], "/W2963951065");
+    assert!(requests.contains(&"/domains/2".to_string()), "{requests:?}");
+}
@@ -0,0 +1,195 @@
+//! The request estimates `OpenAlexAdapter::explain` makes, without sending anything.
+
+use std::fs;
+
+use once_cell::sync::Lazy;
+use openalex_adapter::{OpenAlexAdapter, QueryPlan, RequestPattern};
+use serde::Deserialize;
+use trustfall::Schema;
+
+static SCHEMA: Lazy<Schema> =
+    Lazy::new(|| Schema::parse(include_str!("../openalex_schema.graphql")).unwrap());
+
+const EXAMPLE_QUERIES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/example_queries");
+
+fn explain(query: &str) -> QueryPlan {
+    let query = trustfall_core::frontend::parse(&SCHEMA, query)
+        .unwrap_or_else(|e| panic!("not a valid query: {e}\n{query}"));
+    OpenAlexAdapter::new().explain(&query.ir_query, false)
+}
+
+#[test]
+fn starting_lists_are_bounded_by_their_limit() {
+    let plan = explain(r#"{ ListWorks(filter: "is_oa:true", limit: 450) { title @output } }"#);
+    let [edge] = plan.edges.as_slice() else {
+        panic!("unexpected plan: {plan:?}");
+    };
+    assert_eq!(
+        edge.pattern,
+        RequestPattern::Paginated {
+            max_pages: None,
+            max_results: Some(450),
+        }
+    );
+    assert_eq!(
+        edge.url.as_deref(),
+        Some("https://api.openalex.org/works?filter=is_oa%3Atrue")
+    );
+    assert_eq!(plan.estimated_requests, Some(3));
+
+    // Without `limit` or `max_pages`, a list fetches a single page.
+    let plan = explain("{ ListAuthors { object_id @output } }");
+    assert_eq!(plan.estimated_requests, Some(1));
+}
+
+#[test]
+fn neighbor_edges_multiply_by_their_parents() {
+    let plan = explain(
+        r#"{ ListWorks(limit: 30) {
+            primary_topic { object_display_name @output }
+            referenced_works { title @output }
+        } }"#,
+    );
+    match plan.edges.as_slice() {
+        [list, topic, references] => {
+            assert_eq!(list.estimated_requests, Some(1));
+            assert_eq!(
+                topic.pattern,
+                RequestPattern::PerNeighborId { max_ids: Some(1) }
+            );
+            assert_eq!(topic.estimated_requests, Some(30));
+            assert_eq!(
+                references.pattern,
+                RequestPattern::PerNeighborId { max_ids: None }
+            );
+            assert_eq!(references.estimated_requests, None);
+        }
+        edges => panic!("unexpected edges: {edges:?}"),
+    }
+    assert_eq!(plan.estimated_requests, None);
+}
+
+#[test]
+fn capped_neighbor_lists_show_their_url() {
+    let plan = explain(
+        r#"{ OpenAlexIDSearchWork(id: "W2741809807") {
+            cited_by_api_url(max_pages: 3) { title @output }
+            citations(limit: 250) { is_author_self_citation @output }
+        } }"#,
+    );
+    match plan.edges.as_slice() {
+        [work, cited_by, citations] => {
+            assert_eq!(work.pattern, RequestPattern::Single { max_results: 1 });
+            assert_eq!(cited_by.estimated_requests, Some(3));
+            assert_eq!(
+                cited_by.url.as_deref(),
+                Some("https://api.openalex.org/works?filter=cites:{id}")
+            );
+            assert_eq!(citations.estimated_requests, Some(2));
+        }
+        edges => panic!("unexpected edges: {edges:?}"),
+    }
+    assert_eq!(plan.estimated_requests, Some(6));
+}
+
+#[test]
+fn bibliographic_coupling_is_bounded_by_one_budget() {
+    let plan = explain(
+        r#"{ ListWorks(limit: 10) {
+            bibliographically_coupled(max_pages: 2) { strength @output }
+        } }"#,
+    );
+    match plan.edges.as_slice() {
+        [_, coupled] => {
+            assert_eq!(
+                coupled.pattern,
+                RequestPattern::Derived {
+                    max_pages: Some(2),
+                    max_results: None,
+                }
+            );
+            assert_eq!(coupled.estimated_requests, Some(20));
+        }
+        edges => panic!("unexpected edges: {edges:?}"),
+    }
+    assert_eq!(plan.estimated_requests, Some(21));
+}
+
+#[test]
+fn derived_edges_bound_their_requests_but_not_their_rows() {
+    // Each work citing this one can add dozens of co-cited works.
+    let plan = explain(
+        r#"{ OpenAlexIDSearchWork(id: "W2741809807") {
+            co_cited_with(limit: 10) { work { title @output } }
+        } }"#,
+    );
+    match plan.edges.as_slice() {
+        [_, co_cited, work] => {
+            assert_eq!(co_cited.estimated_requests, Some(1));
+            assert_eq!(
+                co_cited.pattern,
+                RequestPattern::Derived {
+                    max_pages: None,
+                    max_results: Some(10),
+                }
+            );
+            assert_eq!(work.estimated_requests, None);
+        }
+        edges => panic!("unexpected edges: {edges:?}"),
+    }
+    assert_eq!(plan.estimated_requests, None);
+}
+
+#[test]
+fn samples_are_bounded_by_their_size_and_budget() {
+    let plan = explain("{ SampleWorks(size: 500) { title @output } }");
+    assert_eq!(plan.estimated_requests, Some(3));
+
+    let plan = explain("{ SampleWorks(size: 500, max_pages: 1) { title @output } }");
+    assert_eq!(
+        plan.edges[0].pattern,
+        RequestPattern::Paginated {
+            max_pages: Some(1),
+            max_results: Some(500),
+        }
+    );
+    assert_eq!(plan.estimated_requests, Some(1));
+}
+
+#[test]
+fn edges_that_send_nothing_cost_nothing() {
+    for query in [
+        r#"{ ListWorks(filter: "colour:blue") { title @output } }"#,
+        r#"{ SampleWorks(size: -1) { title @output } }"#,
+    ] {
+        let plan = explain(query);
+        assert_eq!(plan.edges[0].pattern, RequestPattern::NoRequests, "{query}");
+        assert_eq!(plan.estimated_requests, Some(0), "{query}");
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct InputQuery {
+    query: String,
+}
+
+/// Explain describes edges from the same table the adapter resolves them with, so an edge it
+/// doesn't know is one the table is missing.
+#[test]
+fn every_example_query_edge_is_known() {
+    for entry in fs::read_dir(EXAMPLE_QUERIES).unwrap() {
+        let path = entry.unwrap().path();
+        let input: InputQuery = ron::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let plan = explain(&input.query);
+        for edge in &plan.edges {
+            assert_ne!(
+                edge.pattern,
+                RequestPattern::Unknown,
+                "{}: {:?}.{}",
+                path.display(),
+                edge.from_type,
+                edge.edge_name
+            );
+        }
+    }
+}
@@ -0,0 +1,187 @@
+//! Checking the raw OpenAlex filter expressions in `filter` edge parameters against the keys
+//! each entity's endpoint accepts, before any request is sent.
+
+use once_cell::sync::Lazy;
+use openalex_adapter::{validate_query, FilterError, VertexKind};
+use trustfall::Schema;
+
+static SCHEMA: Lazy<Schema> =
+    Lazy::new(|| Schema::parse(include_str!("../openalex_schema.graphql")).unwrap());
+
+fn validate(query: &str) -> Result<(), FilterError> {
+    let query = trustfall_core::frontend::parse(&SCHEMA, query)
+        .unwrap_or_else(|e| panic!("not a valid query: {e}\n{query}"));
+    validate_query(&query.ir_query)
+}
+
+#[test]
+fn known_keys_are_accepted() {
+    for query in [
+        r#"{ ListWorks(filter: "publication_year:2020,is_oa:true") { object_id @output } }"#,
+        r#"{ ListWorks(filter: "cites:W2100837269|W2741809807") { object_id @output } }"#,
+        r#"{ SampleAuthors(filter: "summary_stats.h_index:>10", size: 5) { object_id @output } }"#,
+        r#"{ GroupTopics(filter: "field.id:17", group_by: "domain.id") { key @output } }"#,
+        r#"{ ListWorks { object_id @output } }"#,
+        r#"{ ListWorks(filter: "") { object_id @output } }"#,
+    ] {
+        assert_eq!(validate(query), Ok(()), "{query}");
+    }
+}
+
+#[test]
+fn unknown_keys_are_rejected_with_a_suggestion() {
+    assert_eq!(
+        validate(
+            r#"{ ListWorks(filter: "is_oa:true,publication_yaer:2020") { object_id @output } }"#
+        ),
+        Err(FilterError::UnknownKey {
+            kind: VertexKind::Work,
+            key: "publication_yaer".to_string(),
+            suggestion: Some("publication_year"),
+        })
+    );
+
+    let error =
+        validate(r#"{ ListWorks(filter: "colour:blue") { object_id @output } }"#).unwrap_err();
+    assert_eq!(
+        error,
+        FilterError::UnknownKey {
+            kind: VertexKind::Work,
+            key: "colour".to_string(),
+            suggestion: None,
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "invalid works filter: unknown key `colour`"
+    );
+}
+
+#[test]
+fn malformed_items_are_rejected() {
+    let error =
+        validate(r#"{ ListWorks(filter: "is_oa:true,is_oa") { object_id @output } }"#).unwrap_err();
+    assert_eq!(
+        error,
+        FilterError::Malformed {
+            kind: VertexKind::Work,
+            item: "is_oa".to_string(),
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "invalid works filter: expected `key:value`, got `is_oa`"
+    );
+
+    assert!(matches!(
+        validate(r#"{ ListAuthors(filter: ":2020") { object_id @output } }"#),
+        Err(FilterError::Malformed { .. })
+    ));
+}
+
+#[test]
+fn each_entity_has_its_own_keys() {
+    // Works filter on `publication_year`; authors don't have one.
+    assert_eq!(
+        validate(r#"{ ListAuthors(filter: "publication_year:2020") { object_id @output } }"#),
+        Err(FilterError::UnknownKey {
+            kind: VertexKind::Author,
+            key: "publication_year".to_string(),
+            suggestion: None,
+        })
+    );
+
+    // Summary stats exist for authors, but not for topics.
+    assert_eq!(
+        validate(r#"{ ListAuthors(filter: "summary_stats.h_index:>10") { object_id @output } }"#),
+        Ok(())
+    );
+    assert!(matches!(
+        validate(r#"{ ListTopics(filter: "summary_stats.h_index:>10") { object_id @output } }"#),
+        Err(FilterError::UnknownKey {
+            kind: VertexKind::Topic,
+            ..
+        })
+    ));
+
+    // Subfields, fields and domains share a list of keys.
+    for edge in ["ListSubfields", "ListFields", "ListDomains"] {
+        assert_eq!(
+            validate(&format!(
+                r#"{{ {edge}(filter: "display_name.search:science") {{ object_id @output }} }}"#
+            )),
+            Ok(()),
+            "{edge}"
+        );
+    }
+}
+
+#[test]
+fn nested_and_folded_filters_are_checked() {
+    assert_eq!(
+        validate(
+            r#"{ OpenAlexIDSearchWork(id: "W2100837269") {
+                cited_by_count_filtered(filter: "publication_yr:>2020") { count @output }
+            } }"#
+        ),
+        Err(FilterError::UnknownKey {
+            kind: VertexKind::Work,
+            key: "publication_yr".to_string(),
+            suggestion: Some("publication_year"),
+        })
+    );
+
+    assert!(matches!(
+        validate(
+            r#"{ ListAuthors(filter: "openalex:A5023888391") {
+                works_api_url @fold {
+                    cited_by_count_filtered(filter: "colour:blue") { count @output }
+                }
+            } }"#
+        ),
+        Err(FilterError::UnknownKey { .. })
+    ));
+}
+
+#[test]
+fn award_ids_must_be_a_single_value() {
+    let query = |award_id: &str| {
+        format!(
+            r#"{{ ListFunders(filter: "openalex:F4320306076") {{
+                works(award_id: "{award_id}") {{ object_id @output }}
+            }} }}"#
+        )
+    };
+    assert_eq!(validate(&query("ANR-10-LABX")), Ok(()));
+    for award_id in ["1346575,1931531", "1346575|1931531"] {
+        let error = validate(&query(award_id)).unwrap_err();
+        assert_eq!(
+            error,
+            FilterError::Unescapable {
+                kind: VertexKind::Work,
+                key: "grants.award_id",
+                value: award_id.to_string(),
+            }
+        );
+    }
+    assert_eq!(
+        validate(&query("1,2")).unwrap_err().to_string(),
+        "invalid works filter: `grants.award_id` value `1,2` can't contain `,` or `|`"
+    );
+}
+
+#[test]
+fn sdg_and_keyword_tags_must_be_a_single_value() {
+    assert_eq!(
+        validate(r#"{ OpenAlexKeywordWorks(keyword: "open-access") { object_id @output } }"#),
+        Ok(())
+    );
+    assert_eq!(
+        validate(r#"{ OpenAlexSDGWorks(sdg: "sdg/4,sdg/13") { object_id @output } }"#),
+        Err(FilterError::Unescapable {
+            kind: VertexKind::Work,
+            key: "sustainable_development_goals.id",
+            value: "sdg/4,sdg/13".to_string(),
+        })
+    );
+}
@@ -0,0 +1,330 @@
+//! Runs every query in `example_queries/` against the fixture corpus and compares its results
+//! to the golden JSON checked in under `tests/golden/`, so that a change in what a query
+//! returns, or a schema field the adapter no longer resolves, shows up as a failing test.
+//!
+//! Run with `UPDATE_GOLDEN=1` to write the current results out as the new golden files.
+
+mod support;
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env, fs,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use async_graphql_parser::types::{TypeKind, TypeSystemDefinition};
+use once_cell::sync::Lazy;
+use openalex_adapter::{Backend, FetchError, ListQuery, OpenAlexAdapter, Snapshot, VertexKind};
+use serde::Deserialize;
+use serde_json::Value;
+use tempfile::TempDir;
+use trustfall::{execute_query, FieldValue, Schema, TransparentValue};
+use trustfall_core::{
+    interpreter::{
+        Adapter, ContextIterator, ContextOutcomeIterator, ResolveEdgeInfo, ResolveInfo,
+        VertexIterator,
+    },
+    ir::EdgeParameters,
+};
+
+use support::{fixture_ids, snapshot};
+
+const SCHEMA_TEXT: &str = include_str!("../openalex_schema.graphql");
+const EXAMPLE_QUERIES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/example_queries");
+const GOLDEN: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden");
+
+/// Queries over lists stop after this many rows, as `main` stops after a few results.
+const MAX_ROWS: usize = 50;
+
+static SCHEMA: Lazy<Schema> = Lazy::new(|| Schema::parse(SCHEMA_TEXT).unwrap());
+
+/// Every example query run once, shared by the tests below.
+static RUNS: Lazy<Runs> = Lazy::new(Runs::execute);
+
+#[derive(Debug, Deserialize)]
+struct InputQuery {
+    query: String,
+    args: BTreeMap<Arc<str>, FieldValue>,
+}
+
+/// Serves the fixture corpus, answering `random` with the first fixture of each kind so
+/// that the results of queries starting from a random entity don't change between runs.
+#[derive(Debug)]
+struct FixedRandom(Snapshot);
+
+impl Backend for FixedRandom {
+    fn get(&self, kind: VertexKind, id: &str) -> Result<String, FetchError> {
+        match id {
+            "random" => self.0.get(kind, &fixture_ids(kind)[0]),
+            id => self.0.get(kind, id),
+        }
+    }
+
+    fn list(&self, kind: VertexKind, query: &ListQuery) -> Result<String, FetchError> {
+        self.0.list(kind, query)
+    }
+
+    fn group_by(
+        &self,
+        kind: VertexKind,
+        query: &ListQuery,
+        key: &str,
+    ) -> Result<String, FetchError> {
+        self.0.group_by(kind, query, key)
+    }
+
+    fn autocomplete(&self, entity: Option<&str>, q: &str) -> Result<String, FetchError> {
+        self.0.autocomplete(entity, q)
+    }
+}
+
+/// Passes everything on to the OpenAlex adapter, noting each schema field it resolves on
+/// a vertex. A field asked for only on contexts without one, e.g. below an edge that came
+/// back empty, isn't noted.
+struct Recording {
+    inner: Arc<OpenAlexAdapter<FixedRandom>>,
+    fields: Arc<Mutex<BTreeSet<(String, String)>>>,
+}
+
+impl Recording {
+    fn record(&self, type_name: &str, field_name: &str) {
+        self.fields
+            .lock()
+            .unwrap()
+            .insert((type_name.to_string(), field_name.to_string()));
+    }
+
+    /// Records `field_name` once a context with an active vertex passes through.
+    fn recording(
+        &self,
+        contexts: ContextIterator<'static, Vertex>,
+        type_name: &str,
+        field_name: &str,
+    ) -> ContextIterator<'static, Vertex> {
+        let fields = self.fields.clone();
+        let field = (type_name.to_string(), field_name.to_string());
+        Box::new(contexts.inspect(move |ctx| {
+            if ctx.active_vertex().is_some() {
+                fields.lock().unwrap().insert(field.clone());
+            }
+        }))
+    }
+}
+
+type Vertex = <OpenAlexAdapter<FixedRandom> as Adapter<'static>>::Vertex;
+
+impl Adapter<'static> for Recording {
+    type Vertex = Vertex;
+
+    fn resolve_starting_vertices(
+        &self,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveInfo,
+    ) -> VertexIterator<'static, Self::Vertex> {
+        self.record("RootSchemaQuery", edge_name);
+        self.inner
+            .resolve_starting_vertices(edge_name, parameters, resolve_info)
+    }
+
+    fn resolve_property(
+        &self,
+        contexts: ContextIterator<'static, Self::Vertex>,
+        type_name: &Arc<str>,
+        property_name: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'static, Self::Vertex, FieldValue> {
+        let contexts = self.recording(contexts, type_name, property_name);
+        self.inner
+            .resolve_property(contexts, type_name, property_name, resolve_info)
+    }
+
+    fn resolve_neighbors(
+        &self,
+        contexts: ContextIterator<'static, Self::Vertex>,
+        type_name: &Arc<str>,
+        edge_name: &Arc<str>,
+        parameters: &EdgeParameters,
+        resolve_info: &ResolveEdgeInfo,
+    ) -> ContextOutcomeIterator<'static, Self::Vertex, VertexIterator<'static, Self::Vertex>> {
+        let contexts = self.recording(contexts, type_name, edge_name);
+        self.inner
+            .resolve_neighbors(contexts, type_name, edge_name, parameters, resolve_info)
+    }
+
+    fn resolve_coercion(
+        &self,
+        contexts: ContextIterator<'static, Self::Vertex>,
+        type_name: &Arc<str>,
+        coerce_to_type: &Arc<str>,
+        resolve_info: &ResolveInfo,
+    ) -> ContextOutcomeIterator<'static, Self::Vertex, bool> {
+        self.inner
+            .resolve_coercion(contexts, type_name, coerce_to_type, resolve_info)
+    }
+}
+
+struct Runs {
+    /// The rows of each example query, by file stem, or why it couldn't be run.
+    results: BTreeMap<String, Result<Value, String>>,
+    /// Every `(type, field)` the adapter was asked to resolve, across all the queries.
+    fields: BTreeSet<(String, String)>,
+    _snapshot_dir: TempDir,
+}
+
+impl Runs {
+    fn execute() -> Self {
+        let (snapshot_dir, snapshot) = snapshot();
+        let fields = Arc::new(Mutex::new(BTreeSet::new()));
+        let adapter = Arc::new(Recording {
+            inner: Arc::new(OpenAlexAdapter::with_backend(FixedRandom(snapshot))),
+            fields: fields.clone(),
+        });
+
+        let results = example_queries()
+            .into_iter()
+            .map(|path| {
+                let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+                (name, run(&adapter, &path))
+            })
+            .collect();
+
+        let fields = fields.lock().unwrap().clone();
+        Runs {
+            results,
+            fields,
+            _snapshot_dir: snapshot_dir,
+        }
+    }
+}
+
+fn example_queries() -> Vec<PathBuf> {
+    let mut paths = fs::read_dir(EXAMPLE_QUERIES)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "ron"))
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths
+}
+
+/// The rows `path`'s query returns, as JSON. A query that panics, e.g. on a schema field the
+/// adapter doesn't know, is reported along with the others instead of stopping the test.
+fn run(adapter: &Arc<Recording>, path: &Path) -> Result<Value, String> {
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let input: InputQuery = ron::from_str(&content).map_err(|e| format!("not RON: {e}"))?;
+
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        let rows = execute_query(&SCHEMA, adapter.clone(), &input.query, input.args)
+            .map_err(|e| format!("not a valid query: {e}"))?
+            .take(MAX_ROWS)
+            .map(|row| {
+                row.into_iter()
+                    .map(|(name, value)| (name, TransparentValue::from(value)))
+                    .collect::<BTreeMap<_, _>>()
+            })
+            .collect::<Vec<_>>();
+        Ok(serde_json::to_value(rows).unwrap())
+    }))
+    .unwrap_or_else(|panic| {
+        let message = panic
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| panic.downcast_ref::<&str>().map(|s| s.to_string()))
+            .unwrap_or_default();
+        Err(format!("panicked: {message}"))
+    })
+}
+
+fn golden_path(name: &str) -> PathBuf {
+    Path::new(GOLDEN).join(format!("{name}.json"))
+}
+
+#[test]
+fn example_queries_match_golden_files() {
+    let update = env::var_os("UPDATE_GOLDEN").is_some();
+    let mut failures = vec![];
+
+    for (name, result) in &RUNS.results {
+        let rows = match result {
+            Ok(rows) => rows,
+            Err(e) => {
+                failures.push(format!("{name}: {e}"));
+                continue;
+            }
+        };
+        if rows.as_array().is_some_and(Vec::is_empty) {
+            failures.push(format!("{name}: no rows, so there's nothing to compare"));
+            continue;
+        }
+        let expected = serde_json::to_string_pretty(rows).unwrap() + "\n";
+
+        let path = golden_path(name);
+        if update {
+            fs::create_dir_all(GOLDEN).unwrap();
+            fs::write(&path, expected).unwrap();
+            continue;
+        }
+        match fs::read_to_string(&path) {
+            Ok(golden) if golden == expected => {}
+            Ok(golden) => failures.push(format!(
+                "{name}: results differ from {}\n--- golden\n{golden}\n--- actual\n{expected}",
+                path.display()
+            )),
+            Err(_) => failures.push(format!("{name}: no golden file at {}", path.display())),
+        }
+    }
+
+    // Golden files left behind by removed or renamed queries.
+    for entry in fs::read_dir(GOLDEN).into_iter().flatten() {
+        let path = entry.unwrap().path();
+        let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+        if !RUNS.results.contains_key(&name) {
+            if update {
+                fs::remove_file(&path).unwrap();
+            } else {
+                failures.push(format!("{}: no example query", path.display()));
+            }
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{}\n\nRun with UPDATE_GOLDEN=1 to accept the current results.",
+        failures.join("\n\n")
+    );
+}
+
+#[test]
+fn example_queries_cover_the_schema() {
+    let document = async_graphql_parser::parse_schema(SCHEMA_TEXT).unwrap();
+    let mut uncovered = vec![];
+    for definition in document.definitions {
+        let TypeSystemDefinition::Type(definition) = definition else {
+            continue;
+        };
+        let type_name = definition.node.name.node.to_string();
+        let fields = match definition.node.kind {
+            TypeKind::Object(object) => object.fields,
+            TypeKind::Interface(interface) => interface.fields,
+            _ => continue,
+        };
+        for field in fields {
+            let field_name = field.node.name.node.to_string();
+            if !RUNS
+                .fields
+                .contains(&(type_name.clone(), field_name.clone()))
+            {
+                uncovered.push(format!("{type_name}.{field_name}"));
+            }
+        }
+    }
+
+    assert!(
+        uncovered.is_empty(),
+        "no example query uses {}",
+        uncovered.join(", ")
+    );
+}
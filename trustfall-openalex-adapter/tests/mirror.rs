@@ -0,0 +1,226 @@
+//! Bringing a mirror up to date with `MirrorBackend::sync`, against an API whose entities
+//! change between syncs.
+
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use chrono::Utc;
+use openalex_adapter::{
+    Backend, BudgetError, FetchError, ListQuery, Mirror, MirrorBackend, OpenAlexAdapter,
+    SyncReport, VertexKind,
+};
+use rusqlite::{params, Connection};
+use serde_json::{json, Value};
+
+/// More works than fit in one sync batch of 50 IDs.
+const WORKS: usize = 120;
+
+/// Serves `WORKS` works from memory, recording the filter of every list request.
+#[derive(Debug, Default)]
+struct ChangingApi {
+    updated_dates: Mutex<HashMap<String, String>>,
+    filters: Mutex<Vec<Option<String>>>,
+}
+
+impl ChangingApi {
+    fn new() -> Self {
+        let updated_dates = (1..=WORKS)
+            .map(|n| (format!("W{n}"), "2023-01-01".to_string()))
+            .collect();
+        ChangingApi {
+            updated_dates: Mutex::new(updated_dates),
+            ..Default::default()
+        }
+    }
+
+    /// Updates a work in OpenAlex, as of `date`.
+    fn update(&self, id: &str, date: &str) {
+        self.updated_dates
+            .lock()
+            .unwrap()
+            .insert(id.to_string(), date.to_string());
+    }
+
+    fn work(id: &str, updated_date: &str) -> Value {
+        json!({
+            "id": format!("https://openalex.org/{id}"),
+            "display_name": format!("{id}, as of {updated_date}"),
+            "updated_date": updated_date,
+        })
+    }
+
+    fn refuse() -> Result<String, FetchError> {
+        Err(FetchError::Budget(BudgetError::MaxRequests { limit: 0 }))
+    }
+}
+
+impl Backend for ChangingApi {
+    fn get(&self, _kind: VertexKind, _id: &str) -> Result<String, FetchError> {
+        Self::refuse()
+    }
+
+    /// Every work, or only those `from_updated_date:` and `openalex:` select.
+    fn list(&self, kind: VertexKind, query: &ListQuery) -> Result<String, FetchError> {
+        assert_eq!(kind, VertexKind::Work);
+        self.filters.lock().unwrap().push(query.filter.clone());
+
+        let filter: HashMap<&str, &str> = query
+            .filter
+            .iter()
+            .flat_map(|filter| filter.split(','))
+            .filter_map(|item| item.split_once(':'))
+            .collect();
+        let mut results = self
+            .updated_dates
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(id, date)| {
+                filter
+                    .get("from_updated_date")
+                    .is_none_or(|since| date.as_str() >= *since)
+                    && filter
+                        .get("openalex")
+                        .is_none_or(|ids| ids.split('|').any(|wanted| wanted == id.as_str()))
+            })
+            .map(|(id, date)| Self::work(id, date))
+            .collect::<Vec<_>>();
+        results.sort_by_key(|work| work["id"].to_string());
+
+        Ok(json!({
+            "meta": { "count": results.len(), "next_cursor": null },
+            "results": results,
+        })
+        .to_string())
+    }
+
+    fn group_by(
+        &self,
+        _kind: VertexKind,
+        _query: &ListQuery,
+        _key: &str,
+    ) -> Result<String, FetchError> {
+        Self::refuse()
+    }
+
+    fn autocomplete(&self, _entity: Option<&str>, _q: &str) -> Result<String, FetchError> {
+        Self::refuse()
+    }
+}
+
+/// Backdates when every mirrored entity was last synced, as if the mirror had sat unused.
+fn last_synced(path: &Path, date: &str) {
+    Connection::open(path)
+        .unwrap()
+        .execute("UPDATE entities SET synced_date = ?1", params![date])
+        .unwrap();
+}
+
+fn synced_dates(path: &Path) -> Vec<String> {
+    let connection = Connection::open(path).unwrap();
+    let mut statement = connection
+        .prepare("SELECT DISTINCT synced_date FROM entities")
+        .unwrap();
+    let dates = statement
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<Vec<String>, _>>()
+        .unwrap();
+    dates
+}
+
+#[test]
+fn sync_refreshes_what_changed_in_batches() {
+    let mirror_dir = tempfile::tempdir().unwrap();
+    let path = mirror_dir.path().join("mirror.db");
+    let api = Arc::new(ChangingApi::new());
+    let backend = MirrorBackend::new(Mirror::open(&path).unwrap(), api.clone());
+
+    backend
+        .list(VertexKind::Work, &ListQuery::default())
+        .unwrap();
+    assert_eq!(backend.mirror().len().unwrap(), WORKS);
+    api.filters.lock().unwrap().clear();
+
+    last_synced(&path, "2024-01-01");
+    api.update("W7", "2024-03-01");
+    api.update("W99", "2024-01-01");
+    api.update("W120", "2023-12-31");
+
+    let report = backend.sync().unwrap();
+    assert_eq!(
+        report,
+        SyncReport {
+            checked: WORKS,
+            refreshed: 2,
+        }
+    );
+
+    // Only works are mirrored, so only works are asked for: every ID once, 50 at a time,
+    // changed since the works were last synced.
+    let filters = api.filters.lock().unwrap().clone();
+    let batches = filters
+        .iter()
+        .map(|filter| {
+            let filter = filter.as_deref().unwrap();
+            let ids = filter
+                .strip_prefix("from_updated_date:2024-01-01,openalex:")
+                .unwrap_or_else(|| panic!("unexpected filter: {filter}"));
+            ids.split('|').count()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(batches, vec![50, 50, 20]);
+
+    // Changed works are stored as they were sent; the rest stay as they were.
+    let body = |id: &str| -> Value {
+        serde_json::from_str(&backend.get(VertexKind::Work, id).unwrap()).unwrap()
+    };
+    assert_eq!(body("W7")["updated_date"], "2024-03-01");
+    assert_eq!(body("W99")["updated_date"], "2024-01-01");
+    assert_eq!(body("W120")["updated_date"], "2023-01-01");
+
+    // Every work checked counts as synced today, whether it changed or not.
+    assert_eq!(
+        synced_dates(&path),
+        vec![Utc::now().date_naive().to_string()]
+    );
+}
+
+#[test]
+fn adapters_sync_their_mirror() {
+    let mirror_dir = tempfile::tempdir().unwrap();
+    let path = mirror_dir.path().join("mirror.db");
+    let api = Arc::new(ChangingApi::new());
+    let mirror = Mirror::open(&path).unwrap();
+    let adapter = OpenAlexAdapter::with_backend(MirrorBackend::new(mirror, api.clone()));
+
+    // An empty mirror has nothing to check.
+    assert_eq!(adapter.sync_mirror().unwrap(), SyncReport::default());
+    assert!(api.filters.lock().unwrap().is_empty());
+
+    adapter
+        .backend()
+        .list(VertexKind::Work, &ListQuery::default())
+        .unwrap();
+    api.filters.lock().unwrap().clear();
+
+    // Just mirrored, nothing has changed since today.
+    let today = Utc::now().date_naive().to_string();
+    let report = adapter.sync_mirror().unwrap();
+    assert_eq!(
+        report,
+        SyncReport {
+            checked: WORKS,
+            refreshed: 0,
+        }
+    );
+    let filters = api.filters.lock().unwrap().clone();
+    assert_eq!(filters.len(), 3);
+    assert!(filters.iter().all(|filter| filter
+        .as_deref()
+        .unwrap()
+        .starts_with(&format!("from_updated_date:{today},openalex:"))));
+}
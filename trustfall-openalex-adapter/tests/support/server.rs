@@ -0,0 +1,311 @@
+//! A local stand-in for `api.openalex.org`, answering from the fixture corpus over HTTP, so
+//! that tests exercise the same requests, URLs and responses as the real API.
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    thread::{self, JoinHandle},
+};
+
+use openalex_adapter::{Backend, FetchError, ListQuery, Snapshot, SnapshotError, VertexKind};
+use reqwest::Url;
+use serde_json::{json, Value};
+use tempfile::TempDir;
+use tiny_http::{Header, Request, Response, Server};
+
+/// A way for the server to misbehave, as the real API sometimes does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fault {
+    /// `429 Too Many Requests`, as sent once the rate limit is exceeded.
+    TooManyRequests,
+    /// The response cut off halfway, so that it isn't valid JSON.
+    Truncated,
+    /// Valid JSON, in a shape the adapter doesn't expect.
+    UnexpectedShape,
+    /// The entities as a later version of the API might send them: fields the adapter reads
+    /// gone null or changed type, and a field it has never heard of.
+    Drifted,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Faults still to be injected, each into the first request containing its pattern.
+    faults: Mutex<VecDeque<(String, Fault)>>,
+    /// The path and query of every request received, in order.
+    requests: Mutex<Vec<String>>,
+    /// The most results served per page, whatever `per-page` asks for.
+    page_size: Mutex<Option<u32>>,
+}
+
+/// Serves the OpenAlex API on `127.0.0.1` for as long as it's alive: single entities,
+/// `random`, lists with filters, search, sorting, cursor and numbered pages, `group_by`
+/// and autocomplete.
+pub struct MockServer {
+    url: String,
+    server: Arc<Server>,
+    state: Arc<State>,
+    thread: Option<JoinHandle<()>>,
+    _snapshot_dir: TempDir,
+}
+
+impl MockServer {
+    pub fn start() -> Self {
+        let (snapshot_dir, snapshot) = super::snapshot();
+        let server = Arc::new(Server::http("127.0.0.1:0").expect("could not start mock server"));
+        let url = format!("http://{}", server.server_addr());
+        let state = Arc::new(State::default());
+
+        let thread = {
+            let server = server.clone();
+            let state = state.clone();
+            thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    handle(&snapshot, &state, request);
+                }
+            })
+        };
+
+        MockServer {
+            url,
+            server,
+            state,
+            thread: Some(thread),
+            _snapshot_dir: snapshot_dir,
+        }
+    }
+
+    /// The base URL to hand to `HttpBackend::with_base_url`.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Answers the next request whose path and query contain `pattern` with `fault`;
+    /// an empty pattern matches the very next request.
+    pub fn fail_once(&self, pattern: &str, fault: Fault) {
+        self.state
+            .faults
+            .lock()
+            .unwrap()
+            .push_back((pattern.to_string(), fault));
+    }
+
+    /// Serves at most `page_size` results per page, so that the few fixtures span several
+    /// pages even at the 200 results per page the adapter asks for.
+    pub fn limit_page_size(&self, page_size: u32) {
+        *self.state.page_size.lock().unwrap() = Some(page_size);
+    }
+
+    /// The path and query of every request received so far, e.g. `/works/W2741809807`.
+    pub fn requests(&self) -> Vec<String> {
+        self.state.requests.lock().unwrap().clone()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.server.unblock();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn handle(snapshot: &Snapshot, state: &State, request: Request) {
+    let path = request.url().to_string();
+    state.requests.lock().unwrap().push(path.clone());
+
+    let fault = {
+        let mut faults = state.faults.lock().unwrap();
+        let position = faults
+            .iter()
+            .position(|(pattern, _)| path.contains(pattern.as_str()));
+        position.and_then(|position| faults.remove(position))
+    };
+
+    let (status, body) = match (route(snapshot, state, &path), fault) {
+        (_, Some((_, Fault::TooManyRequests))) => {
+            let body = error_body("Rate limit exceeded", "Too many requests; slow down.");
+            let response = Response::from_string(body)
+                .with_status_code(429)
+                .with_header(json_header())
+                .with_header(Header::from_bytes("Retry-After", "1").unwrap());
+            let _ = request.respond(response);
+            return;
+        }
+        (Ok(body), Some((_, Fault::Truncated))) => (200, body[..body.len() / 2].to_string()),
+        (Ok(_), Some((_, Fault::UnexpectedShape))) => (
+            200,
+            json!({ "meta": { "count": "many" }, "results": { "id": 1 } }).to_string(),
+        ),
+        (Ok(body), Some((_, Fault::Drifted))) => (200, drift(&body)),
+        (Ok(body), None) => (200, body),
+        (Err((status, body)), _) => (status, body),
+    };
+
+    let response = Response::from_string(body)
+        .with_status_code(status)
+        .with_header(json_header());
+    let _ = request.respond(response);
+}
+
+/// Dispatches a request path to the snapshot backend, as the API would: `/works/W1`,
+/// `/W1`, `/works?filter=...`, `/works?group_by=...` or `/autocomplete/authors?q=...`.
+fn route(snapshot: &Snapshot, state: &State, path: &str) -> Result<String, (u16, String)> {
+    let url = Url::parse(&format!("https://api.openalex.org{path}"))
+        .map_err(|e| (400, error_body("Invalid URL", &e.to_string())))?;
+    let segments = url
+        .path_segments()
+        .map(|segments| segments.collect::<Vec<_>>())
+        .unwrap_or_default();
+    let param = |name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    };
+
+    let response = match segments.as_slice() {
+        ["autocomplete"] => snapshot.autocomplete(None, &param("q").unwrap_or_default()),
+        ["autocomplete", entity] => {
+            snapshot.autocomplete(Some(entity), &param("q").unwrap_or_default())
+        }
+        [endpoint] => match endpoint_kind(endpoint) {
+            Some(kind) => {
+                let mut query = ListQuery::from_url(url.as_str()).unwrap_or_default();
+                if let Some(page_size) = *state.page_size.lock().unwrap() {
+                    query.per_page = Some(query.per_page.map_or(page_size, |p| p.min(page_size)));
+                }
+                match param("group_by") {
+                    Some(key) => snapshot.group_by(kind, &query, &key),
+                    None => snapshot.list(kind, &query),
+                }
+            }
+            None => match VertexKind::from_id(&format!("https://openalex.org/{endpoint}")) {
+                Some(kind) => snapshot.get(kind, endpoint),
+                None => return Err(not_found(path)),
+            },
+        },
+        [endpoint, id @ ..] => match endpoint_kind(endpoint) {
+            Some(kind) => snapshot.get(kind, &decode(&id.join("/"))),
+            None => return Err(not_found(path)),
+        },
+        [] => return Err(not_found(path)),
+    };
+
+    response.map_err(|e| match e {
+        FetchError::Snapshot(SnapshotError::NotFound { .. }) => not_found(path),
+        e => (500, error_body("Internal error", &e.to_string())),
+    })
+}
+
+/// Drifts a single entity, or every entity in a page of results.
+fn drift(body: &str) -> String {
+    let mut json: Value = serde_json::from_str(body).unwrap();
+    match json.get_mut("results").and_then(Value::as_array_mut) {
+        Some(results) => results.iter_mut().for_each(drift_entity),
+        None => drift_entity(&mut json),
+    }
+    json.to_string()
+}
+
+fn drift_entity(entity: &mut Value) {
+    entity["cited_by_count"] = Value::Null;
+    entity["updated_date"] = Value::Null;
+    entity["not_yet_modelled"] = json!({ "values": [1, 2, 3] });
+    // Whole arrays, strings and objects gone.
+    for field in [
+        "display_name",
+        "cited_by_api_url",
+        "works_api_url",
+        "sources_api_url",
+        "ids",
+        "related_works",
+        "sustainable_development_goals",
+        "mesh",
+        "locations",
+        "affiliations",
+        "ancestors",
+        "related_concepts",
+        "host_organization_lineage",
+        "societies",
+        "associated_institutions",
+        "repositories",
+        "lineage",
+        "topics",
+        "subfields",
+        "fields",
+    ] {
+        without(entity, field);
+    }
+
+    nested(entity, "concepts", |concept| {
+        concept["level"] = json!("top");
+        without(concept, "display_name");
+    });
+    nested(entity, "grants", |grant| {
+        grant["funder"] = Value::Null;
+        grant["funder_display_name"] = Value::Null;
+    });
+    nested(entity, "counts_by_year", |count| {
+        count["year"] = json!(count["year"].to_string());
+        count["cited_by_count"] = Value::Null;
+    });
+    nested(entity, "authorships", |authorship| {
+        authorship["author"]["display_name"] = Value::Null;
+        nested(authorship, "institutions", |institution| {
+            without(institution, "display_name")
+        });
+    });
+    if let Some(source) = entity.pointer_mut("/primary_location/source") {
+        without(source, "display_name");
+    }
+    nested(entity, "apc_prices", |price| {
+        price["price"] = Value::Null;
+        without(price, "currency");
+    });
+    nested(entity, "roles", |role| {
+        role["works_count"] = Value::Null;
+        role["not_yet_modelled"] = json!(true);
+    });
+}
+
+/// Drifts every object in the array at `field`, if there is one.
+fn nested(entity: &mut Value, field: &str, drift: fn(&mut Value)) {
+    if let Some(objects) = entity.get_mut(field).and_then(Value::as_array_mut) {
+        objects.iter_mut().for_each(drift);
+    }
+}
+
+fn without(object: &mut Value, field: &str) {
+    if let Some(object) = object.as_object_mut() {
+        object.remove(field);
+    }
+}
+
+fn endpoint_kind(endpoint: &str) -> Option<VertexKind> {
+    VertexKind::ALL
+        .into_iter()
+        .find(|kind| kind.endpoint() == endpoint)
+}
+
+/// Undoes the percent-encoding of a path segment, e.g. in `doi:10.7717%2Fpeerj.4375`.
+fn decode(segment: &str) -> String {
+    Url::parse(&format!("https://api.openalex.org/?id={segment}"))
+        .ok()
+        .and_then(|url| url.query_pairs().next().map(|(_, id)| id.into_owned()))
+        .unwrap_or_else(|| segment.to_string())
+}
+
+fn not_found(path: &str) -> (u16, String) {
+    (
+        404,
+        error_body("Not found", &format!("{path} is not a known entity")),
+    )
+}
+
+fn error_body(error: &str, message: &str) -> String {
+    json!({ "error": error, "message": message }).to_string()
+}
+
+fn json_header() -> Header {
+    Header::from_bytes("Content-Type", "application/json").unwrap()
+}